@@ -0,0 +1,197 @@
+// src/auth/api_keys.rs
+use crate::auth::scopes::API_KEY_SCOPES;
+use crate::auth::{hash_token, random_token, unix_now};
+use crate::errors::ApiError;
+use crate::models::auth::{ApiKeyResponse, Claims, Principal};
+use crate::repository::{self, RepositoryWriter, TodoRepository};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, MutexGuard};
+
+const KEY_PREFIX: &str = "tdk";
+const KEY_ID_BYTES: usize = 8;
+const KEY_SECRET_BYTES: usize = 32;
+// `last_used_at` is only moved on by at least this much, so busy keys aren't saved on every request
+const LAST_USED_RESOLUTION_SECONDS: u64 = 60;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ApiKeyRecord {
+    pub id: String,
+    pub name: String,
+    pub wallet: String,
+    pub scopes: Vec<String>,
+    pub secret_hash: String,
+    pub created_at: u64,
+    pub last_used_at: Option<u64>,
+}
+
+impl ApiKeyRecord {
+    fn to_response(&self) -> ApiKeyResponse {
+        ApiKeyResponse {
+            id: self.id.clone(),
+            name: self.name.clone(),
+            scopes: self.scopes.clone(),
+            created_at: self.created_at,
+            last_used_at: self.last_used_at,
+        }
+    }
+}
+
+// API keys for machine clients, bound to the wallet that created them.
+// Keys look like `tdk_<id>_<secret>`; only a hash of the secret is kept.
+// Lookups are served from memory. With a repository, keys are saved before
+// create and revoke return, so they survive a restart.
+#[derive(Default)]
+pub struct ApiKeyStore {
+    keys: Mutex<HashMap<String, ApiKeyRecord>>,
+    repository: Option<Arc<dyn TodoRepository>>,
+    // Usage times are saved in the background
+    writer: Option<RepositoryWriter>,
+}
+
+impl ApiKeyStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_repository(self, repository: Arc<dyn TodoRepository>) -> Result<Self, ApiError> {
+        let saved = repository.api_keys()?;
+        self.lock()?
+            .extend(saved.into_iter().map(|record| (record.id.clone(), record)));
+
+        Ok(Self {
+            writer: Some(RepositoryWriter::spawn(repository.clone())),
+            repository: Some(repository),
+            ..self
+        })
+    }
+
+    // Returns the plaintext key, which is never retrievable again
+    pub async fn create(
+        &self,
+        wallet: &str,
+        name: &str,
+        scopes: &[String],
+    ) -> Result<(String, ApiKeyResponse), ApiError> {
+        if scopes.is_empty() {
+            return Err(ApiError::BadRequest("At least one scope is required".to_string()));
+        }
+
+        if let Some(scope) = scopes.iter().find(|scope| !API_KEY_SCOPES.contains(&scope.as_str())) {
+            return Err(ApiError::BadRequest(format!("Scope not allowed for API keys: {}", scope)));
+        }
+
+        let id = random_token(KEY_ID_BYTES);
+        let secret = random_token(KEY_SECRET_BYTES);
+
+        let mut scopes = scopes.to_vec();
+        scopes.sort();
+        scopes.dedup();
+
+        let record = ApiKeyRecord {
+            id: id.clone(),
+            name: name.to_string(),
+            wallet: wallet.to_string(),
+            scopes,
+            secret_hash: hash_token(&secret),
+            created_at: unix_now()?,
+            last_used_at: None,
+        };
+        let response = record.to_response();
+
+        // Saved first, so a key is never handed out that a restart would lose
+        if let Some(repository) = &self.repository {
+            let saved = record.clone();
+            repository::run_blocking(repository, move |repository| repository.save_api_key(&saved)).await?;
+        }
+        self.lock()?.insert(id.clone(), record);
+
+        Ok((format!("{}_{}_{}", KEY_PREFIX, id, secret), response))
+    }
+
+    pub fn list(&self, wallet: &str) -> Result<Vec<ApiKeyResponse>, ApiError> {
+        let keys = self.lock()?;
+
+        let mut listed: Vec<ApiKeyResponse> = keys
+            .values()
+            .filter(|record| record.wallet == wallet)
+            .map(ApiKeyRecord::to_response)
+            .collect();
+        listed.sort_by_key(|key| key.created_at);
+
+        Ok(listed)
+    }
+
+    pub async fn revoke(&self, wallet: &str, id: &str) -> Result<(), ApiError> {
+        {
+            let mut keys = self.lock()?;
+            match keys.get(id) {
+                Some(record) if record.wallet == wallet => {
+                    keys.remove(id);
+                }
+                _ => return Err(ApiError::NotFound(format!("API key {} not found", id))),
+            }
+        }
+
+        // Revoked in memory straight away; an error here means it may be back after a restart
+        if let Some(repository) = &self.repository {
+            let id = id.to_string();
+            repository::run_blocking(repository, move |repository| repository.delete_api_key(&id)).await?;
+        }
+
+        Ok(())
+    }
+
+    // Resolve a presented key to claims for its wallet, carrying the key's scopes
+    pub fn authenticate(&self, key: &str) -> Result<Claims, ApiError> {
+        let invalid = || ApiError::Unauthorized("Invalid API key".to_string());
+
+        let mut parts = key.splitn(3, '_');
+        let (prefix, id, secret) = match (parts.next(), parts.next(), parts.next()) {
+            (Some(prefix), Some(id), Some(secret)) => (prefix, id, secret),
+            _ => return Err(invalid()),
+        };
+
+        if prefix != KEY_PREFIX {
+            return Err(invalid());
+        }
+
+        let now = unix_now()?;
+        let mut keys = self.lock()?;
+        let record = keys.get_mut(id).ok_or_else(invalid)?;
+
+        if record.secret_hash != hash_token(secret) {
+            return Err(invalid());
+        }
+
+        if record.last_used_at.is_none_or(|last_used| now >= last_used + LAST_USED_RESOLUTION_SECONDS) {
+            record.last_used_at = Some(now);
+            if let Some(writer) = &self.writer {
+                let (id, last_used_at) = (record.id.clone(), now);
+                writer.write(move |repository| {
+                    if let Err(e) = repository.touch_api_key(&id, last_used_at) {
+                        log::warn!("Failed to save API key {} usage: {}", id, e);
+                    }
+                });
+            }
+        }
+
+        // API keys don't expire and aren't tracked by the JWT revocation list;
+        // deleting the key is how access is revoked
+        Ok(Claims {
+            sub: record.wallet.clone(),
+            exp: 0,
+            iat: record.created_at as usize,
+            jti: record.id.clone(),
+            sid: record.id.clone(),
+            scopes: record.scopes.clone(),
+            roles: Vec::new(),
+            principal: Principal::ApiKey,
+        })
+    }
+
+    fn lock(&self) -> Result<MutexGuard<'_, HashMap<String, ApiKeyRecord>>, ApiError> {
+        self.keys
+            .lock()
+            .map_err(|_| ApiError::InternalServerError("API key store poisoned".to_string()))
+    }
+}
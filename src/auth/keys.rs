@@ -0,0 +1,300 @@
+// src/auth/keys.rs
+use crate::auth::{random_token, unix_now};
+use crate::config::Config;
+use crate::errors::ApiError;
+use crate::models::auth::{Claims, Principal};
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use jsonwebtoken::jwk::{
+    AlgorithmParameters, CommonParameters, EllipticCurve, Jwk, JwkSet, OctetKeyPairParameters,
+    OctetKeyPairType, PublicKeyUse, RSAKeyParameters, RSAKeyType,
+};
+use jsonwebtoken::{decode, decode_header, encode, Algorithm, DecodingKey, EncodingKey, Header, Validation};
+use std::collections::HashMap;
+use std::fs;
+use std::str::FromStr;
+
+// DER prefix of an Ed25519 SubjectPublicKeyInfo; the raw 32-byte key follows it
+const ED25519_SPKI_PREFIX: [u8; 12] = [
+    0x30, 0x2a, 0x30, 0x05, 0x06, 0x03, 0x2b, 0x65, 0x70, 0x03, 0x21, 0x00,
+];
+
+// DER of the rsaEncryption algorithm identifier that opens an RSA SubjectPublicKeyInfo
+const RSA_ALGORITHM_IDENTIFIER: [u8; 15] = [
+    0x30, 0x0d, 0x06, 0x09, 0x2a, 0x86, 0x48, 0x86, 0xf7, 0x0d, 0x01, 0x01, 0x01, 0x05, 0x00,
+];
+
+const DER_INTEGER: u8 = 0x02;
+const DER_BIT_STRING: u8 = 0x03;
+const DER_SEQUENCE: u8 = 0x30;
+
+// Key id used for tokens signed with the shared HS256 secret
+const HS256_KEY_ID: &str = "hs256";
+
+struct VerificationKey {
+    algorithm: Algorithm,
+    key: DecodingKey,
+}
+
+// Signing key for newly issued tokens plus every key tokens may still be verified with.
+// Rotating keys means moving the old public key into the additional verification keys.
+pub struct JwtKeys {
+    signing_kid: String,
+    signing_algorithm: Algorithm,
+    encoding_key: EncodingKey,
+    verification_keys: HashMap<String, VerificationKey>,
+    jwks: JwkSet,
+}
+
+impl JwtKeys {
+    pub fn from_config(config: &Config) -> Result<Self, ApiError> {
+        let signing_algorithm = parse_algorithm(&config.jwt_algorithm)?;
+        let mut verification_keys = HashMap::new();
+        let mut jwks = JwkSet { keys: Vec::new() };
+
+        // Tokens without a kid were signed with the shared secret, if there is one
+        if let Some(secret) = &config.jwt_secret {
+            verification_keys.insert(
+                HS256_KEY_ID.to_string(),
+                VerificationKey {
+                    algorithm: Algorithm::HS256,
+                    key: DecodingKey::from_secret(secret.as_bytes()),
+                },
+            );
+        }
+
+        let (signing_kid, encoding_key) = match signing_algorithm {
+            Algorithm::HS256 => {
+                let secret = config.jwt_secret.as_ref().ok_or_else(|| {
+                    config_error("JWT_SECRET must be set for HS256")
+                })?;
+                (HS256_KEY_ID.to_string(), EncodingKey::from_secret(secret.as_bytes()))
+            }
+            Algorithm::EdDSA | Algorithm::RS256 => {
+                let private_path = config.jwt_private_key_path.as_ref().ok_or_else(|| {
+                    config_error("JWT_PRIVATE_KEY_PATH must be set for asymmetric signing")
+                })?;
+                let public_path = config.jwt_public_key_path.as_ref().ok_or_else(|| {
+                    config_error("JWT_PUBLIC_KEY_PATH must be set for asymmetric signing")
+                })?;
+
+                let private_pem = fs::read(private_path)?;
+                let encoding_key = match signing_algorithm {
+                    Algorithm::EdDSA => EncodingKey::from_ed_pem(&private_pem),
+                    _ => EncodingKey::from_rsa_pem(&private_pem),
+                }
+                .map_err(|e| config_error(&format!("Invalid JWT private key: {}", e)))?;
+
+                let jwk = public_jwk(&config.jwt_key_id, signing_algorithm, public_path)?;
+                verification_keys.insert(config.jwt_key_id.clone(), verification_key(&jwk, signing_algorithm)?);
+                jwks.keys.push(jwk);
+
+                (config.jwt_key_id.clone(), encoding_key)
+            }
+            _ => unreachable!("parse_algorithm only accepts supported algorithms"),
+        };
+
+        // Previous public keys stay valid until every token signed with them expires
+        for entry in &config.jwt_additional_public_keys {
+            let (kid, algorithm, path) = parse_additional_key(entry)?;
+            let jwk = public_jwk(&kid, algorithm, &path)?;
+            verification_keys.insert(kid, verification_key(&jwk, algorithm)?);
+            jwks.keys.push(jwk);
+        }
+
+        let keys = Self {
+            signing_kid,
+            signing_algorithm,
+            encoding_key,
+            verification_keys,
+            jwks,
+        };
+        keys.check_key_pair()?;
+
+        Ok(keys)
+    }
+
+    // A private key that doesn't match its public key would issue tokens nothing can verify
+    fn check_key_pair(&self) -> Result<(), ApiError> {
+        let now = unix_now()? as usize;
+        let probe = Claims {
+            sub: String::new(),
+            exp: now + 60,
+            iat: now,
+            jti: random_token(16),
+            sid: String::new(),
+            scopes: Vec::new(),
+            roles: Vec::new(),
+            principal: Principal::WalletSession,
+        };
+
+        self.decode(&self.encode(&probe)?)
+            .map(|_| ())
+            .map_err(|_| config_error("JWT private key doesn't match the public key"))
+    }
+
+    pub fn encode(&self, claims: &Claims) -> Result<String, ApiError> {
+        let mut header = Header::new(self.signing_algorithm);
+        header.kid = Some(self.signing_kid.clone());
+
+        Ok(encode(&header, claims, &self.encoding_key)?)
+    }
+
+    pub fn decode(&self, token: &str) -> Result<Claims, ApiError> {
+        let header = decode_header(token)?;
+        let kid = header.kid.as_deref().unwrap_or(HS256_KEY_ID);
+
+        let verification_key = self
+            .verification_keys
+            .get(kid)
+            .ok_or_else(|| ApiError::Unauthorized("Unknown signing key".to_string()))?;
+
+        // Never let the token pick its own algorithm
+        if header.alg != verification_key.algorithm {
+            return Err(ApiError::Unauthorized("Unexpected signing algorithm".to_string()));
+        }
+
+        let token_data = decode::<Claims>(
+            token,
+            &verification_key.key,
+            &Validation::new(verification_key.algorithm),
+        )?;
+
+        Ok(token_data.claims)
+    }
+
+    // Public keys in JWKS form; empty when only the shared secret is configured
+    pub fn jwks(&self) -> &JwkSet {
+        &self.jwks
+    }
+}
+
+fn parse_algorithm(name: &str) -> Result<Algorithm, ApiError> {
+    match Algorithm::from_str(name) {
+        Ok(algorithm @ (Algorithm::HS256 | Algorithm::EdDSA | Algorithm::RS256)) => Ok(algorithm),
+        _ => Err(config_error(&format!("Unsupported JWT algorithm: {}", name))),
+    }
+}
+
+// Additional keys are given as `kid=ALG:path`
+fn parse_additional_key(entry: &str) -> Result<(String, Algorithm, String), ApiError> {
+    let invalid = || config_error(&format!("Invalid JWT verification key entry: {}", entry));
+
+    let (kid, rest) = entry.split_once('=').ok_or_else(invalid)?;
+    let (algorithm, path) = rest.split_once(':').ok_or_else(invalid)?;
+    let algorithm = parse_algorithm(algorithm.trim())?;
+
+    if algorithm == Algorithm::HS256 {
+        return Err(config_error("Additional JWT verification keys must be asymmetric"));
+    }
+
+    Ok((kid.trim().to_string(), algorithm, path.trim().to_string()))
+}
+
+fn public_jwk(kid: &str, algorithm: Algorithm, path: &str) -> Result<Jwk, ApiError> {
+    let contents = fs::read_to_string(path)?;
+
+    let parameters = match algorithm {
+        Algorithm::EdDSA => {
+            let der = pem::parse(&contents)
+                .map_err(|e| config_error(&format!("Invalid Ed25519 public key {}: {}", path, e)))?
+                .contents;
+
+            let raw_key = der
+                .strip_prefix(&ED25519_SPKI_PREFIX[..])
+                .filter(|key| key.len() == 32)
+                .ok_or_else(|| config_error(&format!("Not an Ed25519 public key: {}", path)))?;
+
+            AlgorithmParameters::OctetKeyPair(OctetKeyPairParameters {
+                key_type: OctetKeyPairType::OctetKeyPair,
+                curve: EllipticCurve::Ed25519,
+                x: URL_SAFE_NO_PAD.encode(raw_key),
+            })
+        }
+        _ => {
+            let der = pem::parse(&contents)
+                .map_err(|e| config_error(&format!("Invalid RSA public key {}: {}", path, e)))?
+                .contents;
+            let (n, e) = rsa_public_key(&der)
+                .ok_or_else(|| config_error(&format!("Not an RSA public key: {}", path)))?;
+
+            AlgorithmParameters::RSA(RSAKeyParameters {
+                key_type: RSAKeyType::RSA,
+                n: URL_SAFE_NO_PAD.encode(n),
+                e: URL_SAFE_NO_PAD.encode(e),
+            })
+        }
+    };
+
+    Ok(Jwk {
+        common: CommonParameters {
+            public_key_use: Some(PublicKeyUse::Signature),
+            algorithm: Some(algorithm),
+            key_id: Some(kid.to_string()),
+            ..CommonParameters::default()
+        },
+        algorithm: parameters,
+    })
+}
+
+// The modulus and exponent of a DER RSA public key, either a SubjectPublicKeyInfo
+// (`PUBLIC KEY`) or a bare PKCS#1 key (`RSA PUBLIC KEY`)
+fn rsa_public_key(der: &[u8]) -> Option<(&[u8], &[u8])> {
+    let (spki, _) = der_element(der, DER_SEQUENCE)?;
+    let pkcs1 = match spki.strip_prefix(&RSA_ALGORITHM_IDENTIFIER[..]) {
+        Some(rest) => {
+            // The PKCS#1 key is the bit string's content, after its unused-bits byte
+            let (bits, _) = der_element(rest, DER_BIT_STRING)?;
+            bits.strip_prefix(&[0x00])?
+        }
+        None => der,
+    };
+
+    let (key, _) = der_element(pkcs1, DER_SEQUENCE)?;
+    let (n, rest) = der_element(key, DER_INTEGER)?;
+    let (e, _) = der_element(rest, DER_INTEGER)?;
+
+    Some((unsigned(n), unsigned(e)))
+}
+
+// JWKs carry the unsigned big-endian value, without DER's sign byte
+fn unsigned(value: &[u8]) -> &[u8] {
+    match value {
+        [0x00, rest @ ..] if !rest.is_empty() => rest,
+        _ => value,
+    }
+}
+
+// Split a DER element with the expected tag into its content and what follows it
+fn der_element(input: &[u8], tag: u8) -> Option<(&[u8], &[u8])> {
+    let (&found, rest) = input.split_first()?;
+    if found != tag {
+        return None;
+    }
+
+    let (&first, rest) = rest.split_first()?;
+    let (length, rest) = if first < 0x80 {
+        (first as usize, rest)
+    } else {
+        // Long form: the low bits count the length bytes that follow
+        let count = (first & 0x7f) as usize;
+        if count == 0 || count > std::mem::size_of::<usize>() || rest.len() < count {
+            return None;
+        }
+        let (bytes, rest) = rest.split_at(count);
+        (bytes.iter().fold(0usize, |length, &byte| (length << 8) | byte as usize), rest)
+    };
+
+    (rest.len() >= length).then(|| rest.split_at(length))
+}
+
+fn verification_key(jwk: &Jwk, algorithm: Algorithm) -> Result<VerificationKey, ApiError> {
+    let key = DecodingKey::from_jwk(jwk)
+        .map_err(|e| config_error(&format!("Invalid JWT public key: {}", e)))?;
+
+    Ok(VerificationKey { algorithm, key })
+}
+
+fn config_error(message: &str) -> ApiError {
+    ApiError::InternalServerError(message.to_string())
+}
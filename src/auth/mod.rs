@@ -0,0 +1,34 @@
+// src/auth/mod.rs
+pub mod api_keys;
+pub mod keys;
+pub mod nonce;
+pub mod revocation;
+pub mod scopes;
+pub mod session;
+pub mod siws;
+
+use crate::errors::ApiError;
+use base58::ToBase58;
+use rand::RngCore;
+use sha2::{Digest, Sha256};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+// Current unix time in seconds
+pub fn unix_now() -> Result<u64, ApiError> {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .map_err(|_| ApiError::InternalServerError("Clock error".to_string()))
+}
+
+// Generate a random base58 token from `len` bytes of OS randomness
+pub fn random_token(len: usize) -> String {
+    let mut bytes = vec![0u8; len];
+    rand::rngs::OsRng.fill_bytes(&mut bytes);
+    bytes.to_base58()
+}
+
+// Hash an opaque secret token for storage so a leaked store can't be replayed
+pub fn hash_token(token: &str) -> String {
+    Sha256::digest(token.as_bytes()).to_base58()
+}
@@ -0,0 +1,95 @@
+// src/auth/nonce.rs
+use crate::auth::random_token;
+use crate::errors::ApiError;
+use chrono::{DateTime, Duration as ChronoDuration, Utc};
+use solana_sdk::pubkey::Pubkey;
+use std::collections::HashMap;
+use std::str::FromStr;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+const NONCE_BYTES: usize = 16;
+
+struct PendingChallenge {
+    public_key: String,
+    expires_at: Instant,
+}
+
+pub struct IssuedNonce {
+    pub nonce: String,
+    pub issued_at: DateTime<Utc>,
+    pub expires_at: DateTime<Utc>,
+}
+
+// In-memory store of outstanding sign-in nonces.
+// Each nonce is bound to one wallet, single-use and expires after `ttl`.
+pub struct NonceStore {
+    challenges: Mutex<HashMap<String, PendingChallenge>>,
+    ttl: Duration,
+}
+
+impl NonceStore {
+    pub fn new(ttl_seconds: u64) -> Self {
+        Self {
+            challenges: Mutex::new(HashMap::new()),
+            ttl: Duration::from_secs(ttl_seconds),
+        }
+    }
+
+    pub fn issue(&self, public_key: &str) -> Result<IssuedNonce, ApiError> {
+        Pubkey::from_str(public_key)
+            .map_err(|_| ApiError::BadRequest("Invalid public key".to_string()))?;
+
+        let nonce = random_token(NONCE_BYTES);
+        let issued_at = Utc::now();
+        let expires_at = issued_at + ChronoDuration::seconds(self.ttl.as_secs() as i64);
+
+        let now = Instant::now();
+        let mut challenges = self
+            .challenges
+            .lock()
+            .map_err(|_| ApiError::InternalServerError("Nonce store poisoned".to_string()))?;
+
+        // Drop expired challenges so abandoned logins don't accumulate
+        challenges.retain(|_, challenge| challenge.expires_at > now);
+
+        challenges.insert(
+            nonce.clone(),
+            PendingChallenge {
+                public_key: public_key.to_string(),
+                expires_at: now + self.ttl,
+            },
+        );
+
+        Ok(IssuedNonce {
+            nonce,
+            issued_at,
+            expires_at,
+        })
+    }
+
+    // Consume `nonce` for `public_key`. Succeeds at most once per nonce.
+    pub fn consume(&self, public_key: &str, nonce: &str) -> Result<(), ApiError> {
+        let mut challenges = self
+            .challenges
+            .lock()
+            .map_err(|_| ApiError::InternalServerError("Nonce store poisoned".to_string()))?;
+
+        let challenge = challenges
+            .get(nonce)
+            .ok_or_else(|| ApiError::Unauthorized("Unknown or already used nonce".to_string()))?;
+
+        if challenge.public_key != public_key {
+            return Err(ApiError::Unauthorized("Nonce was issued to a different wallet".to_string()));
+        }
+
+        let expired = challenge.expires_at <= Instant::now();
+        challenges.remove(nonce);
+
+        if expired {
+            return Err(ApiError::Unauthorized("Nonce expired".to_string()));
+        }
+
+        Ok(())
+    }
+}
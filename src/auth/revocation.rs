@@ -0,0 +1,111 @@
+// src/auth/revocation.rs
+use crate::auth::unix_now;
+use crate::errors::ApiError;
+use crate::models::auth::Claims;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::{Mutex, MutexGuard};
+
+#[derive(Default, Serialize, Deserialize)]
+struct RevocationState {
+    // Revoked token ids mapped to their expiry, after which they can be forgotten
+    tokens: HashMap<String, u64>,
+    // Wallets mapped to a cutoff; tokens issued in an earlier second are revoked
+    wallets: HashMap<String, u64>,
+    // Revoked session ids mapped to when they were revoked. `iat` only has second
+    // precision, so tokens from the cutoff's own second are matched by session
+    // instead, leaving tokens from a login right after the logout valid.
+    #[serde(default)]
+    sessions: HashMap<String, u64>,
+}
+
+// Server-side list of revoked access tokens, consulted by the auth middleware.
+// Kept in memory and optionally mirrored to a JSON file so revocations survive restarts.
+pub struct RevocationStore {
+    state: Mutex<RevocationState>,
+    path: Option<PathBuf>,
+    max_token_lifetime_seconds: u64,
+}
+
+impl RevocationStore {
+    pub fn new(path: Option<PathBuf>, max_token_lifetime_seconds: u64) -> Result<Self, ApiError> {
+        let state = match &path {
+            Some(path) if path.exists() => {
+                let contents = fs::read_to_string(path)?;
+                serde_json::from_str(&contents).map_err(|e| {
+                    ApiError::InternalServerError(format!("Invalid revocation store: {}", e))
+                })?
+            }
+            _ => RevocationState::default(),
+        };
+
+        Ok(Self {
+            state: Mutex::new(state),
+            path,
+            max_token_lifetime_seconds,
+        })
+    }
+
+    pub fn revoke_token(&self, jti: &str, exp: u64) -> Result<(), ApiError> {
+        let mut state = self.lock()?;
+        state.tokens.insert(jti.to_string(), exp);
+        self.prune_and_persist(&mut state)
+    }
+
+    // Revoke every token issued to `wallet` up to now, given its open sessions
+    pub fn revoke_wallet(&self, wallet: &str, session_ids: &[String]) -> Result<(), ApiError> {
+        let now = unix_now()?;
+        let mut state = self.lock()?;
+        state.wallets.insert(wallet.to_string(), now);
+        for session_id in session_ids {
+            state.sessions.insert(session_id.clone(), now);
+        }
+        self.prune_and_persist(&mut state)
+    }
+
+    pub fn is_revoked(&self, claims: &Claims) -> Result<bool, ApiError> {
+        let state = self.lock()?;
+
+        if state.tokens.contains_key(&claims.jti) || state.sessions.contains_key(&claims.sid) {
+            return Ok(true);
+        }
+
+        Ok(state
+            .wallets
+            .get(&claims.sub)
+            .is_some_and(|&cutoff| (claims.iat as u64) < cutoff))
+    }
+
+    fn prune_and_persist(&self, state: &mut RevocationState) -> Result<(), ApiError> {
+        let now = unix_now()?;
+
+        // Entries only matter while a token they cover could still be unexpired
+        state.tokens.retain(|_, &mut exp| exp > now);
+        state
+            .wallets
+            .retain(|_, &mut cutoff| cutoff + self.max_token_lifetime_seconds > now);
+        state
+            .sessions
+            .retain(|_, &mut revoked_at| revoked_at + self.max_token_lifetime_seconds > now);
+
+        if let Some(path) = &self.path {
+            let contents = serde_json::to_string(state)
+                .map_err(|e| ApiError::InternalServerError(e.to_string()))?;
+
+            // Write then rename so a crash can't leave a truncated file behind
+            let tmp_path = path.with_extension("tmp");
+            fs::write(&tmp_path, contents)?;
+            fs::rename(&tmp_path, path)?;
+        }
+
+        Ok(())
+    }
+
+    fn lock(&self) -> Result<MutexGuard<'_, RevocationState>, ApiError> {
+        self.state
+            .lock()
+            .map_err(|_| ApiError::InternalServerError("Revocation store poisoned".to_string()))
+    }
+}
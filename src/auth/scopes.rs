@@ -0,0 +1,15 @@
+// src/auth/scopes.rs
+
+pub const TODOS_READ: &str = "todos:read";
+pub const TODOS_WRITE: &str = "todos:write";
+
+// Managing credentials (API keys, sessions) requires a wallet sign-in
+pub const ACCOUNT_MANAGE: &str = "account:manage";
+
+// Scopes granted to tokens issued from a wallet sign-in
+pub const WALLET_SCOPES: &[&str] = &[TODOS_READ, TODOS_WRITE, ACCOUNT_MANAGE];
+
+// Scopes an API key may be created with
+pub const API_KEY_SCOPES: &[&str] = &[TODOS_READ, TODOS_WRITE];
+
+pub const ADMIN_ROLE: &str = "admin";
@@ -0,0 +1,174 @@
+// src/auth/session.rs
+use crate::auth::{hash_token, random_token, unix_now};
+use crate::errors::ApiError;
+use std::collections::HashMap;
+use std::sync::{Mutex, MutexGuard};
+
+const REFRESH_TOKEN_BYTES: usize = 32;
+const SESSION_ID_BYTES: usize = 16;
+
+struct RefreshTokenRecord {
+    session_id: String,
+    used: bool,
+    expires_at: u64,
+}
+
+// All refresh tokens issued from one login form a family sharing a session id
+struct Session {
+    wallet: String,
+    scopes: Vec<String>,
+    revoked: bool,
+    expires_at: u64,
+}
+
+#[derive(Default)]
+struct SessionState {
+    // Keyed by the hash of the refresh token, never the token itself
+    tokens: HashMap<String, RefreshTokenRecord>,
+    sessions: HashMap<String, Session>,
+}
+
+pub struct IssuedRefreshToken {
+    pub token: String,
+    pub session_id: String,
+}
+
+// Who a refreshed session belongs to and what it was granted at sign-in
+pub struct SessionGrant {
+    pub wallet: String,
+    pub scopes: Vec<String>,
+}
+
+// In-memory store of wallet sessions and their rotating refresh tokens.
+// Presenting an already rotated refresh token revokes the whole session.
+pub struct SessionStore {
+    state: Mutex<SessionState>,
+    refresh_ttl_seconds: u64,
+}
+
+impl SessionStore {
+    pub fn new(refresh_ttl_seconds: u64) -> Self {
+        Self {
+            state: Mutex::new(SessionState::default()),
+            refresh_ttl_seconds,
+        }
+    }
+
+    pub fn start_session(
+        &self,
+        wallet: &str,
+        scopes: &[String],
+    ) -> Result<IssuedRefreshToken, ApiError> {
+        let now = unix_now()?;
+        let mut state = self.lock()?;
+
+        // Forget expired tokens and sessions
+        state.tokens.retain(|_, record| record.expires_at > now);
+        state.sessions.retain(|_, session| session.expires_at > now);
+
+        let session_id = random_token(SESSION_ID_BYTES);
+        state.sessions.insert(
+            session_id.clone(),
+            Session {
+                wallet: wallet.to_string(),
+                scopes: scopes.to_vec(),
+                revoked: false,
+                expires_at: now + self.refresh_ttl_seconds,
+            },
+        );
+
+        Ok(self.issue(&mut state, session_id, now))
+    }
+
+    // Exchange a refresh token for a new one in the same session
+    pub fn rotate(&self, refresh_token: &str) -> Result<(SessionGrant, IssuedRefreshToken), ApiError> {
+        let now = unix_now()?;
+        let mut guard = self.lock()?;
+        let state = &mut *guard;
+
+        let record = state
+            .tokens
+            .get_mut(&hash_token(refresh_token))
+            .ok_or_else(|| ApiError::Unauthorized("Invalid refresh token".to_string()))?;
+
+        let session_id = record.session_id.clone();
+
+        if record.used {
+            // A rotated token came back: assume it was stolen and kill the session
+            if let Some(session) = state.sessions.get_mut(&session_id) {
+                session.revoked = true;
+            }
+            log::warn!("Refresh token reuse detected, revoked session {}", session_id);
+            return Err(ApiError::Unauthorized("Refresh token reuse detected".to_string()));
+        }
+
+        if record.expires_at <= now {
+            return Err(ApiError::Unauthorized("Refresh token expired".to_string()));
+        }
+
+        record.used = true;
+
+        let grant = match state.sessions.get(&session_id) {
+            Some(session) if !session.revoked => SessionGrant {
+                wallet: session.wallet.clone(),
+                scopes: session.scopes.clone(),
+            },
+            _ => return Err(ApiError::Unauthorized("Session revoked".to_string())),
+        };
+
+        let issued = self.issue(state, session_id, now);
+
+        Ok((grant, issued))
+    }
+
+    pub fn revoke_session(&self, session_id: &str) -> Result<(), ApiError> {
+        let mut state = self.lock()?;
+
+        if let Some(session) = state.sessions.get_mut(session_id) {
+            session.revoked = true;
+        }
+
+        Ok(())
+    }
+
+    // Returns the ids of the revoked sessions
+    pub fn revoke_wallet_sessions(&self, wallet: &str) -> Result<Vec<String>, ApiError> {
+        let mut state = self.lock()?;
+        let mut revoked = Vec::new();
+
+        for (session_id, session) in state.sessions.iter_mut() {
+            if session.wallet == wallet {
+                session.revoked = true;
+                revoked.push(session_id.clone());
+            }
+        }
+
+        Ok(revoked)
+    }
+
+    fn issue(&self, state: &mut SessionState, session_id: String, now: u64) -> IssuedRefreshToken {
+        let token = random_token(REFRESH_TOKEN_BYTES);
+        let expires_at = now + self.refresh_ttl_seconds;
+
+        state.tokens.insert(
+            hash_token(&token),
+            RefreshTokenRecord {
+                session_id: session_id.clone(),
+                used: false,
+                expires_at,
+            },
+        );
+
+        if let Some(session) = state.sessions.get_mut(&session_id) {
+            session.expires_at = expires_at;
+        }
+
+        IssuedRefreshToken { token, session_id }
+    }
+
+    fn lock(&self) -> Result<MutexGuard<'_, SessionState>, ApiError> {
+        self.state
+            .lock()
+            .map_err(|_| ApiError::InternalServerError("Session store poisoned".to_string()))
+    }
+}
@@ -0,0 +1,272 @@
+// src/auth/siws.rs
+use crate::errors::ApiError;
+use chrono::{DateTime, Duration, SecondsFormat, Utc};
+use std::fmt;
+
+const HEADER_SUFFIX: &str = " wants you to sign in with your Solana account:";
+const SUPPORTED_VERSION: &str = "1";
+
+// Allowed clock skew between the wallet and the server
+const MAX_CLOCK_SKEW_SECONDS: i64 = 60;
+
+// A Sign-In-With-Solana message as produced by wallet adapters:
+//
+// ${domain} wants you to sign in with your Solana account:
+// ${address}
+//
+// ${statement}
+//
+// URI: ${uri}
+// Version: ${version}
+// Chain ID: ${chain_id}
+// Nonce: ${nonce}
+// Issued At: ${issued_at}
+// Expiration Time: ${expiration_time}
+// Not Before: ${not_before}
+// Request ID: ${request_id}
+// Resources:
+// - ${resources[0]}
+#[derive(Debug, Clone, PartialEq)]
+pub struct SiwsMessage {
+    pub domain: String,
+    pub address: String,
+    pub statement: Option<String>,
+    pub uri: Option<String>,
+    pub version: Option<String>,
+    pub chain_id: Option<String>,
+    pub nonce: Option<String>,
+    pub issued_at: Option<DateTime<Utc>>,
+    pub expiration_time: Option<DateTime<Utc>>,
+    pub not_before: Option<DateTime<Utc>>,
+    pub request_id: Option<String>,
+    pub resources: Vec<String>,
+}
+
+// Values a SIWS message must match to be accepted by this server
+pub struct SiwsExpectations<'a> {
+    pub domain: &'a str,
+    pub uri: &'a str,
+    pub chain_id: &'a str,
+}
+
+impl SiwsMessage {
+    pub fn parse(message: &str) -> Result<Self, ApiError> {
+        let mut lines = message.lines();
+
+        let domain = lines
+            .next()
+            .and_then(|line| line.strip_suffix(HEADER_SUFFIX))
+            .filter(|domain| !domain.is_empty())
+            .ok_or_else(|| malformed("missing sign-in header"))?
+            .to_string();
+
+        let address = lines
+            .next()
+            .map(str::trim)
+            .filter(|address| !address.is_empty())
+            .ok_or_else(|| malformed("missing address"))?
+            .to_string();
+
+        let mut parsed = SiwsMessage {
+            domain,
+            address,
+            statement: None,
+            uri: None,
+            version: None,
+            chain_id: None,
+            nonce: None,
+            issued_at: None,
+            expiration_time: None,
+            not_before: None,
+            request_id: None,
+            resources: Vec::new(),
+        };
+
+        let mut in_fields = false;
+        let mut in_resources = false;
+
+        for line in lines {
+            if line.is_empty() {
+                continue;
+            }
+
+            if in_resources {
+                let resource = line
+                    .strip_prefix("- ")
+                    .ok_or_else(|| malformed("invalid resource entry"))?;
+                parsed.resources.push(resource.to_string());
+                continue;
+            }
+
+            if let Some(value) = line.strip_prefix("URI: ") {
+                parsed.uri = Some(value.to_string());
+            } else if let Some(value) = line.strip_prefix("Version: ") {
+                parsed.version = Some(value.to_string());
+            } else if let Some(value) = line.strip_prefix("Chain ID: ") {
+                parsed.chain_id = Some(value.to_string());
+            } else if let Some(value) = line.strip_prefix("Nonce: ") {
+                parsed.nonce = Some(value.to_string());
+            } else if let Some(value) = line.strip_prefix("Issued At: ") {
+                parsed.issued_at = Some(parse_timestamp("Issued At", value)?);
+            } else if let Some(value) = line.strip_prefix("Expiration Time: ") {
+                parsed.expiration_time = Some(parse_timestamp("Expiration Time", value)?);
+            } else if let Some(value) = line.strip_prefix("Not Before: ") {
+                parsed.not_before = Some(parse_timestamp("Not Before", value)?);
+            } else if let Some(value) = line.strip_prefix("Request ID: ") {
+                parsed.request_id = Some(value.to_string());
+            } else if line == "Resources:" {
+                in_resources = true;
+            } else if !in_fields && parsed.statement.is_none() {
+                // The only free-form line allowed is the statement before the fields
+                parsed.statement = Some(line.to_string());
+                continue;
+            } else {
+                return Err(malformed(&format!("unexpected line '{}'", line)));
+            }
+
+            in_fields = true;
+        }
+
+        Ok(parsed)
+    }
+
+    // Check the message against the server's expectations and the signing wallet.
+    // The nonce itself is checked against the nonce store by the caller.
+    pub fn verify(
+        &self,
+        expected: &SiwsExpectations,
+        public_key: &str,
+        now: DateTime<Utc>,
+    ) -> Result<(), ApiError> {
+        if self.domain != expected.domain {
+            return Err(rejected(&format!(
+                "domain '{}' does not match '{}'",
+                self.domain, expected.domain
+            )));
+        }
+
+        if self.address != public_key {
+            return Err(rejected("address does not match public_key"));
+        }
+
+        match &self.uri {
+            Some(uri) if uri == expected.uri => {}
+            Some(uri) => {
+                return Err(rejected(&format!(
+                    "URI '{}' does not match '{}'",
+                    uri, expected.uri
+                )))
+            }
+            None => return Err(rejected("URI is required")),
+        }
+
+        if self.version.as_deref() != Some(SUPPORTED_VERSION) {
+            return Err(rejected("unsupported version"));
+        }
+
+        match &self.chain_id {
+            Some(chain_id) if chain_id == expected.chain_id => {}
+            Some(chain_id) => {
+                return Err(rejected(&format!(
+                    "chain ID '{}' does not match '{}'",
+                    chain_id, expected.chain_id
+                )))
+            }
+            None => return Err(rejected("chain ID is required")),
+        }
+
+        if self.nonce.is_none() {
+            return Err(rejected("nonce is required"));
+        }
+
+        let skew = Duration::seconds(MAX_CLOCK_SKEW_SECONDS);
+
+        match self.issued_at {
+            Some(issued_at) if issued_at > now + skew => {
+                return Err(rejected("issued-at is in the future"))
+            }
+            Some(_) => {}
+            None => return Err(rejected("issued-at is required")),
+        }
+
+        if let Some(expiration_time) = self.expiration_time {
+            if expiration_time <= now {
+                return Err(rejected("message has expired"));
+            }
+        }
+
+        if let Some(not_before) = self.not_before {
+            if not_before > now + skew {
+                return Err(rejected("message is not yet valid"));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl fmt::Display for SiwsMessage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}{}\n{}", self.domain, HEADER_SUFFIX, self.address)?;
+
+        if let Some(statement) = &self.statement {
+            write!(f, "\n\n{}", statement)?;
+        }
+
+        let mut fields = Vec::new();
+        if let Some(uri) = &self.uri {
+            fields.push(format!("URI: {}", uri));
+        }
+        if let Some(version) = &self.version {
+            fields.push(format!("Version: {}", version));
+        }
+        if let Some(chain_id) = &self.chain_id {
+            fields.push(format!("Chain ID: {}", chain_id));
+        }
+        if let Some(nonce) = &self.nonce {
+            fields.push(format!("Nonce: {}", nonce));
+        }
+        if let Some(issued_at) = &self.issued_at {
+            fields.push(format!("Issued At: {}", format_timestamp(issued_at)));
+        }
+        if let Some(expiration_time) = &self.expiration_time {
+            fields.push(format!("Expiration Time: {}", format_timestamp(expiration_time)));
+        }
+        if let Some(not_before) = &self.not_before {
+            fields.push(format!("Not Before: {}", format_timestamp(not_before)));
+        }
+        if let Some(request_id) = &self.request_id {
+            fields.push(format!("Request ID: {}", request_id));
+        }
+        if !self.resources.is_empty() {
+            fields.push("Resources:".to_string());
+            for resource in &self.resources {
+                fields.push(format!("- {}", resource));
+            }
+        }
+
+        if !fields.is_empty() {
+            write!(f, "\n\n{}", fields.join("\n"))?;
+        }
+
+        Ok(())
+    }
+}
+
+pub fn format_timestamp(timestamp: &DateTime<Utc>) -> String {
+    timestamp.to_rfc3339_opts(SecondsFormat::Millis, true)
+}
+
+fn parse_timestamp(field: &str, value: &str) -> Result<DateTime<Utc>, ApiError> {
+    DateTime::parse_from_rfc3339(value)
+        .map(|timestamp| timestamp.with_timezone(&Utc))
+        .map_err(|_| malformed(&format!("invalid {} timestamp", field)))
+}
+
+fn malformed(reason: &str) -> ApiError {
+    ApiError::BadRequest(format!("Malformed SIWS message: {}", reason))
+}
+
+fn rejected(reason: &str) -> ApiError {
+    ApiError::Unauthorized(format!("SIWS message rejected: {}", reason))
+}
@@ -0,0 +1,92 @@
+// src/bin/remote_signer_stub.rs
+//
+// Local stand-in for a remote signing service, speaking the protocol expected by
+// `signer::remote::RemoteSigner`. Keys are kept as plain keypair files.
+//
+// REMOTE_SIGNER_STUB_DIR (default ./remote-signer-keys), REMOTE_SIGNER_STUB_PORT (default 8090)
+use actix_web::{web, App, HttpResponse, HttpServer};
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use std::env;
+use std::path::PathBuf;
+use todo_api::errors::ApiError;
+use todo_api::signer::file::FileSigner;
+use todo_api::signer::remote::{
+    CreateWalletRequest, ExportResponse, PubkeyResponse, SignRequest, SignResponse,
+};
+use todo_api::signer::TransactionSigner;
+
+async fn create(
+    req: web::Json<CreateWalletRequest>,
+    signer: web::Data<FileSigner>,
+) -> Result<HttpResponse, ApiError> {
+    let pubkey = signer.create(&req.wallet).await?;
+    Ok(HttpResponse::Created().json(PubkeyResponse { pubkey: pubkey.to_string() }))
+}
+
+async fn pubkey(
+    path: web::Path<String>,
+    signer: web::Data<FileSigner>,
+) -> Result<HttpResponse, ApiError> {
+    let pubkey = signer.pubkey(&path).await?;
+    Ok(HttpResponse::Ok().json(PubkeyResponse { pubkey: pubkey.to_string() }))
+}
+
+async fn sign(
+    path: web::Path<String>,
+    req: web::Json<SignRequest>,
+    signer: web::Data<FileSigner>,
+) -> Result<HttpResponse, ApiError> {
+    let message = BASE64
+        .decode(&req.message)
+        .map_err(|_| ApiError::BadRequest("Message is not valid base64".to_string()))?;
+    let signature = signer.sign_message(&path, &message).await?;
+    Ok(HttpResponse::Ok().json(SignResponse { signature: signature.to_string() }))
+}
+
+async fn export(
+    path: web::Path<String>,
+    signer: web::Data<FileSigner>,
+) -> Result<HttpResponse, ApiError> {
+    let secret_key = signer.export(&path).await?;
+    Ok(HttpResponse::Ok().json(ExportResponse { secret_key }))
+}
+
+async fn delete(
+    path: web::Path<String>,
+    signer: web::Data<FileSigner>,
+) -> Result<HttpResponse, ApiError> {
+    signer.delete(&path).await?;
+    Ok(HttpResponse::NoContent().finish())
+}
+
+#[actix_web::main]
+async fn main() -> std::io::Result<()> {
+    env_logger::init_from_env(env_logger::Env::new().default_filter_or("info"));
+
+    let dir = env::var("REMOTE_SIGNER_STUB_DIR").unwrap_or_else(|_| "./remote-signer-keys".to_string());
+    let port: u16 = env::var("REMOTE_SIGNER_STUB_PORT")
+        .unwrap_or_else(|_| "8090".to_string())
+        .parse()
+        .expect("REMOTE_SIGNER_STUB_PORT must be a number");
+
+    let signer = web::Data::new(
+        FileSigner::new(PathBuf::from(dir))
+            .map_err(|e| std::io::Error::other(e.to_string()))?,
+    );
+
+    log::info!("Remote signer stub listening on 127.0.0.1:{}", port);
+
+    HttpServer::new(move || {
+        App::new()
+            .app_data(signer.clone())
+            .route("/wallets", web::post().to(create))
+            .route("/wallets/{wallet}", web::get().to(pubkey))
+            .route("/wallets/{wallet}", web::delete().to(delete))
+            .route("/wallets/{wallet}/sign", web::post().to(sign))
+            .route("/wallets/{wallet}/export", web::post().to(export))
+    })
+    .bind(("127.0.0.1", port))?
+    .run()
+    .await
+}
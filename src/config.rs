@@ -7,10 +7,54 @@ use std::env;
 pub struct Config {
     pub host: String,
     pub port: u16,
-    pub solana_rpc_url: String,
+    pub solana_rpc_urls: Vec<String>,
+    pub rpc_health_interval_seconds: u64,
+    pub rpc_max_slot_lag: u64,
+    pub rpc_hedge_delay_ms: u64,
+    pub tx_max_attempts: u32,
+    pub tx_retry_base_delay_ms: u64,
+    pub tx_retry_max_delay_ms: u64,
+    pub compute_unit_limit: u32,
+    pub priority_fee_mode: String,
+    pub priority_fee_micro_lamports: u64,
+    pub priority_fee_max_micro_lamports: u64,
+    pub priority_fee_percentile: u8,
+    pub priority_fee_urgent_percentile: u8,
+    pub batch_compute_units_per_operation: u32,
+    pub job_workers: usize,
+    pub job_queue_capacity: usize,
+    pub job_retention_seconds: u64,
+    pub indexer_enabled: bool,
+    pub solana_ws_url: Option<String>,
+    pub indexer_resync_seconds: u64,
+    pub indexer_max_slot_lag: u64,
+    pub database_path: String,
     pub program_id: String,
+    pub program_idl_path: Option<String>,
     pub commitment: CommitmentConfig,
-    pub jwt_secret: String,
+    pub signer_backend: String,
+    pub keystore_dir: String,
+    pub keystore_password: Option<String>,
+    pub remote_signer_url: Option<String>,
+    pub remote_signer_token: Option<String>,
+    pub fee_payer_keypair_path: Option<String>,
+    pub sponsorship_max_transactions_per_day: u32,
+    pub sponsorship_max_lamports_per_day: u64,
+    pub jwt_secret: Option<String>,
+    pub jwt_algorithm: String,
+    pub jwt_key_id: String,
+    pub jwt_private_key_path: Option<String>,
+    pub jwt_public_key_path: Option<String>,
+    pub jwt_additional_public_keys: Vec<String>,
+    pub access_token_ttl_seconds: u64,
+    pub refresh_token_ttl_seconds: u64,
+    pub revocation_store_path: Option<String>,
+    pub admin_wallets: Vec<String>,
+    pub nonce_ttl_seconds: u64,
+    pub siws_domain: String,
+    pub siws_uri: String,
+    pub siws_chain_id: String,
+    pub siws_statement: String,
 }
 
 pub fn load_config() -> Config {
@@ -20,21 +64,278 @@ pub fn load_config() -> Config {
         .parse()
         .expect("PORT must be a number");
     
-    let solana_rpc_url = env::var("SOLANA_RPC_URL")
-        .unwrap_or_else(|_| "https://api.devnet.solana.com".to_string());
+    // RPC endpoints as `url` or `url|weight`, comma separated; SOLANA_RPC_URL is a single endpoint
+    let solana_rpc_urls = env::var("SOLANA_RPC_URLS")
+        .or_else(|_| env::var("SOLANA_RPC_URL"))
+        .unwrap_or_else(|_| "https://api.devnet.solana.com".to_string())
+        .split(',')
+        .map(str::trim)
+        .filter(|url| !url.is_empty())
+        .map(String::from)
+        .collect();
+    
+    let rpc_health_interval_seconds = env::var("RPC_HEALTH_INTERVAL_SECONDS")
+        .unwrap_or_else(|_| "10".to_string())
+        .parse()
+        .expect("RPC_HEALTH_INTERVAL_SECONDS must be a number");
+    
+    let rpc_max_slot_lag = env::var("RPC_MAX_SLOT_LAG")
+        .unwrap_or_else(|_| "50".to_string())
+        .parse()
+        .expect("RPC_MAX_SLOT_LAG must be a number");
+    
+    // How long a read waits on one endpoint before also asking the next
+    let rpc_hedge_delay_ms = env::var("RPC_HEDGE_DELAY_MS")
+        .unwrap_or_else(|_| "300".to_string())
+        .parse()
+        .expect("RPC_HEDGE_DELAY_MS must be a number");
+    
+    // Default commitment for reads and confirmations; requests may ask for another
+    let commitment = env::var("SOLANA_COMMITMENT")
+        .ok()
+        .map(|value| {
+            crate::solana::commitment::parse(&value)
+                .expect("SOLANA_COMMITMENT must be processed, confirmed or finalized")
+        })
+        .unwrap_or_else(CommitmentConfig::confirmed);
+    
+    // Transaction send retries; the delay doubles per attempt up to the max
+    let tx_max_attempts = env::var("TX_MAX_ATTEMPTS")
+        .unwrap_or_else(|_| "4".to_string())
+        .parse()
+        .expect("TX_MAX_ATTEMPTS must be a number");
+    
+    let tx_retry_base_delay_ms = env::var("TX_RETRY_BASE_DELAY_MS")
+        .unwrap_or_else(|_| "500".to_string())
+        .parse()
+        .expect("TX_RETRY_BASE_DELAY_MS must be a number");
+    
+    let tx_retry_max_delay_ms = env::var("TX_RETRY_MAX_DELAY_MS")
+        .unwrap_or_else(|_| "4000".to_string())
+        .parse()
+        .expect("TX_RETRY_MAX_DELAY_MS must be a number");
+    
+    let compute_unit_limit = env::var("COMPUTE_UNIT_LIMIT")
+        .unwrap_or_else(|_| "200000".to_string())
+        .parse()
+        .expect("COMPUTE_UNIT_LIMIT must be a number");
+    
+    // static: always PRIORITY_FEE_MICRO_LAMPORTS; dynamic: a percentile of recent fees, at least that much
+    let priority_fee_mode = env::var("PRIORITY_FEE_MODE")
+        .unwrap_or_else(|_| "dynamic".to_string());
+    
+    let priority_fee_micro_lamports = env::var("PRIORITY_FEE_MICRO_LAMPORTS")
+        .unwrap_or_else(|_| "0".to_string())
+        .parse()
+        .expect("PRIORITY_FEE_MICRO_LAMPORTS must be a number");
+    
+    let priority_fee_max_micro_lamports = env::var("PRIORITY_FEE_MAX_MICRO_LAMPORTS")
+        .unwrap_or_else(|_| "1000000".to_string())
+        .parse()
+        .expect("PRIORITY_FEE_MAX_MICRO_LAMPORTS must be a number");
+    
+    let priority_fee_percentile = env::var("PRIORITY_FEE_PERCENTILE")
+        .unwrap_or_else(|_| "50".to_string())
+        .parse()
+        .expect("PRIORITY_FEE_PERCENTILE must be a number");
+    
+    let priority_fee_urgent_percentile = env::var("PRIORITY_FEE_URGENT_PERCENTILE")
+        .unwrap_or_else(|_| "90".to_string())
+        .parse()
+        .expect("PRIORITY_FEE_URGENT_PERCENTILE must be a number");
+    
+    // Compute units budgeted per operation when packing batches into transactions
+    let batch_compute_units_per_operation = env::var("BATCH_COMPUTE_UNITS_PER_OPERATION")
+        .unwrap_or_else(|_| "30000".to_string())
+        .parse()
+        .expect("BATCH_COMPUTE_UNITS_PER_OPERATION must be a number");
+    
+    // Background workers for `Prefer: respond-async` writes
+    let job_workers = env::var("JOB_WORKERS")
+        .unwrap_or_else(|_| "4".to_string())
+        .parse()
+        .expect("JOB_WORKERS must be a number");
+    
+    let job_queue_capacity = env::var("JOB_QUEUE_CAPACITY")
+        .unwrap_or_else(|_| "1000".to_string())
+        .parse()
+        .expect("JOB_QUEUE_CAPACITY must be a number");
+    
+    // How long finished jobs can still be looked up
+    let job_retention_seconds = env::var("JOB_RETENTION_SECONDS")
+        .unwrap_or_else(|_| "3600".to_string())
+        .parse()
+        .expect("JOB_RETENTION_SECONDS must be a number");
+    
+    // Local index of todo accounts that list reads are served from
+    let indexer_enabled = env::var("INDEXER_ENABLED")
+        .unwrap_or_else(|_| "true".to_string())
+        .parse()
+        .expect("INDEXER_ENABLED must be true or false");
+    
+    // Websocket endpoint for subscriptions; derived from the first RPC endpoint when unset
+    let solana_ws_url = env::var("SOLANA_WS_URL").ok();
+    
+    // Full rescans catch closed accounts, which subscriptions don't report
+    let indexer_resync_seconds = env::var("INDEXER_RESYNC_SECONDS")
+        .unwrap_or_else(|_| "600".to_string())
+        .parse()
+        .expect("INDEXER_RESYNC_SECONDS must be a number");
+    
+    let indexer_max_slot_lag = env::var("INDEXER_MAX_SLOT_LAG")
+        .unwrap_or_else(|_| "150".to_string())
+        .parse()
+        .expect("INDEXER_MAX_SLOT_LAG must be a number");
+    
+    // SQLite database for job records, the indexed todos and off-chain metadata
+    let database_path = env::var("DATABASE_PATH")
+        .unwrap_or_else(|_| "./todo_api.db".to_string());
     
     let program_id = env::var("PROGRAM_ID")
         .unwrap_or_else(|_| "hS4TFJW9MdMsCS3c7QWfvjfjEJBnm1pc6wfVAiBnzar".to_string());
     
-    let jwt_secret = env::var("JWT_SECRET")
-        .expect("JWT_SECRET must be set");
+    // Anchor IDL JSON for the program; fetched from its on-chain IDL account when unset
+    let program_idl_path = env::var("PROGRAM_IDL_PATH").ok();
+    
+    // Custodial key backend: none (custodial mode off), keystore (encrypted),
+    // file (plaintext, dev only) or remote
+    let signer_backend = env::var("SIGNER_BACKEND")
+        .unwrap_or_else(|_| "none".to_string());
+    
+    let keystore_dir = env::var("KEYSTORE_DIR")
+        .unwrap_or_else(|_| "./keystore".to_string());
+    
+    let keystore_password = env::var("KEYSTORE_PASSWORD").ok();
+    let remote_signer_url = env::var("REMOTE_SIGNER_URL").ok();
+    let remote_signer_token = env::var("REMOTE_SIGNER_TOKEN").ok();
+    
+    // Optional relayer keypair that pays fees and rent on behalf of wallets
+    let fee_payer_keypair_path = env::var("FEE_PAYER_KEYPAIR_PATH").ok();
+    
+    let sponsorship_max_transactions_per_day = env::var("SPONSORSHIP_MAX_TRANSACTIONS_PER_DAY")
+        .unwrap_or_else(|_| "50".to_string())
+        .parse()
+        .expect("SPONSORSHIP_MAX_TRANSACTIONS_PER_DAY must be a number");
+    
+    let sponsorship_max_lamports_per_day = env::var("SPONSORSHIP_MAX_LAMPORTS_PER_DAY")
+        .unwrap_or_else(|_| "10000000".to_string())
+        .parse()
+        .expect("SPONSORSHIP_MAX_LAMPORTS_PER_DAY must be a number");
+    
+    // Shared HS256 secret; only required when signing with HS256
+    let jwt_secret = env::var("JWT_SECRET").ok();
+    
+    let jwt_algorithm = env::var("JWT_ALGORITHM")
+        .unwrap_or_else(|_| "HS256".to_string());
+    
+    let jwt_key_id = env::var("JWT_KEY_ID")
+        .unwrap_or_else(|_| "primary".to_string());
+    
+    let jwt_private_key_path = env::var("JWT_PRIVATE_KEY_PATH").ok();
+    let jwt_public_key_path = env::var("JWT_PUBLIC_KEY_PATH").ok();
+    
+    // Retired public keys still accepted for verification, as `kid=ALG:path,...`
+    let jwt_additional_public_keys = env::var("JWT_ADDITIONAL_PUBLIC_KEYS")
+        .map(|keys| {
+            keys.split(',')
+                .map(str::trim)
+                .filter(|key| !key.is_empty())
+                .map(String::from)
+                .collect()
+        })
+        .unwrap_or_default();
+    
+    let access_token_ttl_seconds = env::var("JWT_ACCESS_TTL_SECONDS")
+        .unwrap_or_else(|_| "900".to_string())
+        .parse()
+        .expect("JWT_ACCESS_TTL_SECONDS must be a number");
+    
+    let refresh_token_ttl_seconds = env::var("JWT_REFRESH_TTL_SECONDS")
+        .unwrap_or_else(|_| "2592000".to_string())
+        .parse()
+        .expect("JWT_REFRESH_TTL_SECONDS must be a number");
+    
+    // Optional file the token revocation list is persisted to
+    let revocation_store_path = env::var("REVOCATION_STORE_PATH").ok();
+    
+    // Wallets granted the admin role at sign-in, comma separated
+    let admin_wallets = env::var("ADMIN_WALLETS")
+        .map(|wallets| {
+            wallets.split(',')
+                .map(str::trim)
+                .filter(|wallet| !wallet.is_empty())
+                .map(String::from)
+                .collect()
+        })
+        .unwrap_or_default();
+
+    let nonce_ttl_seconds = env::var("AUTH_NONCE_TTL_SECONDS")
+        .unwrap_or_else(|_| "300".to_string())
+        .parse()
+        .expect("AUTH_NONCE_TTL_SECONDS must be a number");
+    
+    let siws_domain = env::var("SIWS_DOMAIN")
+        .unwrap_or_else(|_| "localhost:3000".to_string());
+    
+    let siws_uri = env::var("SIWS_URI")
+        .unwrap_or_else(|_| "http://localhost:3000".to_string());
+    
+    let siws_chain_id = env::var("SIWS_CHAIN_ID")
+        .unwrap_or_else(|_| "devnet".to_string());
+    
+    let siws_statement = env::var("SIWS_STATEMENT")
+        .unwrap_or_else(|_| "Sign in to the Solana Todo API".to_string());
 
     Config {
         host,
         port,
-        solana_rpc_url,
+        solana_rpc_urls,
+        rpc_health_interval_seconds,
+        rpc_max_slot_lag,
+        rpc_hedge_delay_ms,
+        tx_max_attempts,
+        tx_retry_base_delay_ms,
+        tx_retry_max_delay_ms,
+        compute_unit_limit,
+        priority_fee_mode,
+        priority_fee_micro_lamports,
+        priority_fee_max_micro_lamports,
+        priority_fee_percentile,
+        priority_fee_urgent_percentile,
+        batch_compute_units_per_operation,
+        job_workers,
+        job_queue_capacity,
+        job_retention_seconds,
+        indexer_enabled,
+        solana_ws_url,
+        indexer_resync_seconds,
+        indexer_max_slot_lag,
+        database_path,
         program_id,
-        commitment: CommitmentConfig::confirmed(),
+        program_idl_path,
+        commitment,
+        signer_backend,
+        keystore_dir,
+        keystore_password,
+        remote_signer_url,
+        remote_signer_token,
+        fee_payer_keypair_path,
+        sponsorship_max_transactions_per_day,
+        sponsorship_max_lamports_per_day,
         jwt_secret,
+        jwt_algorithm,
+        jwt_key_id,
+        jwt_private_key_path,
+        jwt_public_key_path,
+        jwt_additional_public_keys,
+        access_token_ttl_seconds,
+        refresh_token_ttl_seconds,
+        revocation_store_path,
+        admin_wallets,
+        nonce_ttl_seconds,
+        siws_domain,
+        siws_uri,
+        siws_chain_id,
+        siws_statement,
     }
 }
\ No newline at end of file
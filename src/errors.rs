@@ -2,8 +2,10 @@
 use actix_web::{error::ResponseError, http::StatusCode, HttpResponse};
 use derive_more::Display;
 use serde::{Deserialize, Serialize};
+use crate::models::transaction::SendAttempt;
 use solana_client::client_error::ClientError;
 use std::convert::From;
+use utoipa::ToSchema;
 
 #[derive(Debug, Display, PartialEq)]
 pub enum ApiError {
@@ -16,23 +18,41 @@ pub enum ApiError {
     #[display(fmt = "Forbidden: {}", _0)]
     Forbidden(String),
     
+    #[display(fmt = "Payment Required: {}", _0)]
+    PaymentRequired(String),
+    
     #[display(fmt = "Not Found: {}", _0)]
     NotFound(String),
     
+    #[display(fmt = "Conflict: {}", _0)]
+    Conflict(String),
+    
     #[display(fmt = "Internal Server Error: {}", _0)]
     InternalServerError(String),
     
     #[display(fmt = "Solana Error: {}", _0)]
     SolanaError(String),
     
+    // A transaction send that failed for good, with every attempt made
+    #[display(fmt = "Transaction Failed: {}", message)]
+    TransactionFailed {
+        message: String,
+        attempts: Vec<SendAttempt>,
+    },
+    
+    #[display(fmt = "Service Unavailable: {}", _0)]
+    ServiceUnavailable(String),
+    
     #[display(fmt = "Rate Limit Exceeded")]
     RateLimitExceeded,
 }
 
-#[derive(Serialize, Deserialize)]
+#[derive(Serialize, Deserialize, ToSchema)]
 pub struct ErrorResponse {
     pub status: String,
     pub message: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub details: Option<serde_json::Value>,
 }
 
 impl ResponseError for ApiError {
@@ -41,6 +61,7 @@ impl ResponseError for ApiError {
         let error_response = ErrorResponse {
             status: status_code.to_string(),
             message: self.to_string(),
+            details: self.details(),
         };
         
         HttpResponse::build(status_code).json(error_response)
@@ -51,14 +72,28 @@ impl ResponseError for ApiError {
             ApiError::BadRequest(_) => StatusCode::BAD_REQUEST,
             ApiError::Unauthorized(_) => StatusCode::UNAUTHORIZED,
             ApiError::Forbidden(_) => StatusCode::FORBIDDEN,
+            ApiError::PaymentRequired(_) => StatusCode::PAYMENT_REQUIRED,
             ApiError::NotFound(_) => StatusCode::NOT_FOUND,
+            ApiError::Conflict(_) => StatusCode::CONFLICT,
             ApiError::InternalServerError(_) => StatusCode::INTERNAL_SERVER_ERROR,
             ApiError::SolanaError(_) => StatusCode::BAD_GATEWAY,
+            ApiError::TransactionFailed { .. } => StatusCode::BAD_GATEWAY,
+            ApiError::ServiceUnavailable(_) => StatusCode::SERVICE_UNAVAILABLE,
             ApiError::RateLimitExceeded => StatusCode::TOO_MANY_REQUESTS,
         }
     }
 }
 
+impl ApiError {
+    // Structured context for the error response, beyond the message
+    fn details(&self) -> Option<serde_json::Value> {
+        match self {
+            ApiError::TransactionFailed { attempts, .. } => serde_json::to_value(attempts).ok(),
+            _ => None,
+        }
+    }
+}
+
 impl From<ClientError> for ApiError {
     fn from(error: ClientError) -> Self {
         ApiError::SolanaError(error.to_string())
@@ -71,6 +106,12 @@ impl From<jsonwebtoken::errors::Error> for ApiError {
     }
 }
 
+impl From<rusqlite::Error> for ApiError {
+    fn from(error: rusqlite::Error) -> Self {
+        ApiError::InternalServerError(format!("Database error: {}", error))
+    }
+}
+
 impl From<std::io::Error> for ApiError {
     fn from(error: std::io::Error) -> Self {
         ApiError::InternalServerError(error.to_string())
@@ -1,16 +1,102 @@
 // src/handlers/auth.rs
+use crate::auth::api_keys::ApiKeyStore;
+use crate::auth::keys::JwtKeys;
+use crate::auth::nonce::NonceStore;
+use crate::auth::revocation::RevocationStore;
+use crate::auth::scopes::{ADMIN_ROLE, WALLET_SCOPES};
+use crate::auth::session::{IssuedRefreshToken, SessionStore};
+use crate::auth::siws::{format_timestamp, SiwsExpectations, SiwsMessage};
 use crate::config::Config;
 use crate::errors::ApiError;
-use crate::models::auth::{Claims, TokenResponse, WalletAuth};
+use crate::auth::{random_token, unix_now};
+use crate::models::auth::{
+    ChallengeQuery, ChallengeResponse, Claims, CreateApiKeyRequest, CreatedApiKeyResponse,
+    Principal, RefreshRequest, TokenResponse, WalletAuth,
+};
 use crate::utils::wallet::verify_wallet_signature;
 use actix_web::{web, HttpResponse};
-use jsonwebtoken::{encode, EncodingKey, Header};
-use std::time::{SystemTime, UNIX_EPOCH};
+use chrono::Utc;
+use validator::Validate;
 
+#[utoipa::path(
+    get,
+    path = "/api/auth/challenge",
+    tag = "auth",
+    params(("public_key" = String, Query, description = "Wallet public key")),
+    responses(
+        (status = 200, description = "Message for the wallet to sign", body = ChallengeResponse),
+        (status = 400, description = "Invalid request", body = ErrorResponse),
+    )
+)]
+pub async fn challenge(
+    query: web::Query<ChallengeQuery>,
+    config: web::Data<Config>,
+    nonce_store: web::Data<NonceStore>,
+) -> Result<HttpResponse, ApiError> {
+    // Issue a single-use nonce bound to the wallet
+    let issued = nonce_store.issue(&query.public_key)?;
+    
+    // Build the SIWS message the wallet is expected to sign
+    let message = SiwsMessage {
+        domain: config.siws_domain.clone(),
+        address: query.public_key.clone(),
+        statement: Some(config.siws_statement.clone()),
+        uri: Some(config.siws_uri.clone()),
+        version: Some("1".to_string()),
+        chain_id: Some(config.siws_chain_id.clone()),
+        nonce: Some(issued.nonce.clone()),
+        issued_at: Some(issued.issued_at),
+        expiration_time: Some(issued.expires_at),
+        not_before: None,
+        request_id: None,
+        resources: Vec::new(),
+    };
+    
+    Ok(HttpResponse::Ok().json(ChallengeResponse {
+        domain: config.siws_domain.clone(),
+        address: query.public_key.clone(),
+        statement: config.siws_statement.clone(),
+        uri: config.siws_uri.clone(),
+        version: "1".to_string(),
+        chain_id: config.siws_chain_id.clone(),
+        nonce: issued.nonce,
+        issued_at: format_timestamp(&issued.issued_at),
+        expiration_time: format_timestamp(&issued.expires_at),
+        message: message.to_string(),
+    }))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/auth/login",
+    tag = "auth",
+    request_body = WalletAuth,
+    responses(
+        (status = 200, description = "Access and refresh tokens", body = TokenResponse),
+        (status = 400, description = "Invalid request", body = ErrorResponse),
+        (status = 401, description = "Missing or invalid credentials", body = ErrorResponse),
+    )
+)]
 pub async fn authenticate(
     req: web::Json<WalletAuth>,
     config: web::Data<Config>,
+    jwt_keys: web::Data<JwtKeys>,
+    nonce_store: web::Data<NonceStore>,
+    session_store: web::Data<SessionStore>,
 ) -> Result<HttpResponse, ApiError> {
+    // Parse the SIWS message and check it was meant for this server and wallet
+    let message = SiwsMessage::parse(&req.message)?;
+    
+    message.verify(
+        &SiwsExpectations {
+            domain: &config.siws_domain,
+            uri: &config.siws_uri,
+            chain_id: &config.siws_chain_id,
+        },
+        &req.public_key,
+        Utc::now(),
+    )?;
+    
     // Verify the wallet signature
     let is_valid = verify_wallet_signature(
         &req.public_key,
@@ -22,30 +108,210 @@ pub async fn authenticate(
         return Err(ApiError::Unauthorized("Invalid signature".to_string()));
     }
     
-    // Calculate expiration (24 hours from now)
-    let now = SystemTime::now()
-        .duration_since(UNIX_EPOCH)
-        .map_err(|_| ApiError::InternalServerError("Clock error".to_string()))?
-        .as_secs() as usize;
+    // Only accept signatures over an outstanding nonce, consuming it
+    let nonce = message.nonce.as_deref().unwrap_or_default();
+    nonce_store.consume(&req.public_key, nonce)?;
+    
+    // Grant every wallet scope unless the client asked for fewer
+    let scopes = match &req.scopes {
+        Some(requested) => {
+            if requested.is_empty() {
+                return Err(ApiError::BadRequest("At least one scope is required".to_string()));
+            }
+            if let Some(scope) = requested.iter().find(|scope| !WALLET_SCOPES.contains(&scope.as_str())) {
+                return Err(ApiError::BadRequest(format!("Unknown scope: {}", scope)));
+            }
+            requested.clone()
+        }
+        None => WALLET_SCOPES.iter().map(|scope| scope.to_string()).collect(),
+    };
+    
+    // Start a new session with its own refresh token family
+    let refresh = session_store.start_session(&req.public_key, &scopes)?;
+    
+    let tokens = issue_tokens(&config, &jwt_keys, &req.public_key, scopes, refresh)?;
+    
+    Ok(HttpResponse::Ok().json(tokens))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/auth/refresh",
+    tag = "auth",
+    request_body = RefreshRequest,
+    responses(
+        (status = 200, description = "Rotated tokens", body = TokenResponse),
+        (status = 401, description = "Missing or invalid credentials", body = ErrorResponse),
+    )
+)]
+pub async fn refresh(
+    req: web::Json<RefreshRequest>,
+    config: web::Data<Config>,
+    jwt_keys: web::Data<JwtKeys>,
+    session_store: web::Data<SessionStore>,
+) -> Result<HttpResponse, ApiError> {
+    // Rotate the refresh token; reusing an old one revokes the session
+    let (grant, refresh) = session_store.rotate(&req.refresh_token)?;
+    
+    let tokens = issue_tokens(&config, &jwt_keys, &grant.wallet, grant.scopes, refresh)?;
+    
+    Ok(HttpResponse::Ok().json(tokens))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/auth/logout",
+    tag = "auth",
+    responses(
+        (status = 204, description = "Token and session revoked"),
+        (status = 401, description = "Missing or invalid credentials", body = ErrorResponse),
+    )
+)]
+pub async fn logout(
+    claims: web::ReqData<Claims>,
+    revocation_store: web::Data<RevocationStore>,
+    session_store: web::Data<SessionStore>,
+) -> Result<HttpResponse, ApiError> {
+    // Revoke this access token and end the session it belongs to
+    revocation_store.revoke_token(&claims.jti, claims.exp as u64)?;
+    session_store.revoke_session(&claims.sid)?;
+    
+    Ok(HttpResponse::NoContent().finish())
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/auth/logout-all",
+    tag = "auth",
+    responses(
+        (status = 204, description = "Every token and session revoked"),
+        (status = 401, description = "Missing or invalid credentials", body = ErrorResponse),
+    )
+)]
+pub async fn logout_all(
+    claims: web::ReqData<Claims>,
+    revocation_store: web::Data<RevocationStore>,
+    session_store: web::Data<SessionStore>,
+) -> Result<HttpResponse, ApiError> {
+    // Revoke every token and session issued to this wallet so far
+    let session_ids = session_store.revoke_wallet_sessions(&claims.sub)?;
+    revocation_store.revoke_wallet(&claims.sub, &session_ids)?;
     
-    let exp = now + 86400; // 24 hours
+    Ok(HttpResponse::NoContent().finish())
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/auth/api-keys",
+    tag = "auth",
+    request_body = CreateApiKeyRequest,
+    responses(
+        (status = 201, description = "API key created", body = CreatedApiKeyResponse),
+        (status = 400, description = "Invalid request", body = ErrorResponse),
+        (status = 401, description = "Missing or invalid credentials", body = ErrorResponse),
+    )
+)]
+pub async fn create_api_key(
+    req: web::Json<CreateApiKeyRequest>,
+    claims: web::ReqData<Claims>,
+    api_keys: web::Data<ApiKeyStore>,
+) -> Result<HttpResponse, ApiError> {
+    // Validate the request
+    req.validate().map_err(|e| ApiError::BadRequest(e.to_string()))?;
+    
+    // Bind the key to the calling wallet
+    let (key, api_key) = api_keys.create(&claims.sub, &req.name, &req.scopes).await?;
+    
+    Ok(HttpResponse::Created().json(CreatedApiKeyResponse { key, api_key }))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/auth/api-keys",
+    tag = "auth",
+    responses(
+        (status = 200, description = "The wallet's API keys", body = [ApiKeyResponse]),
+        (status = 401, description = "Missing or invalid credentials", body = ErrorResponse),
+    )
+)]
+pub async fn list_api_keys(
+    claims: web::ReqData<Claims>,
+    api_keys: web::Data<ApiKeyStore>,
+) -> Result<HttpResponse, ApiError> {
+    let keys = api_keys.list(&claims.sub)?;
+    
+    Ok(HttpResponse::Ok().json(keys))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/auth/api-keys/{id}",
+    tag = "auth",
+    params(("id" = String, Path, description = "API key id")),
+    responses(
+        (status = 204, description = "API key revoked"),
+        (status = 401, description = "Missing or invalid credentials", body = ErrorResponse),
+        (status = 404, description = "Not found", body = ErrorResponse),
+    )
+)]
+pub async fn revoke_api_key(
+    path: web::Path<String>,
+    claims: web::ReqData<Claims>,
+    api_keys: web::Data<ApiKeyStore>,
+) -> Result<HttpResponse, ApiError> {
+    api_keys.revoke(&claims.sub, &path.into_inner()).await?;
+    
+    Ok(HttpResponse::NoContent().finish())
+}
+
+#[utoipa::path(
+    get,
+    path = "/.well-known/jwks.json",
+    tag = "auth",
+    responses(
+        (status = 200, description = "JSON Web Key Set of the token verification keys"),
+    )
+)]
+pub async fn jwks(jwt_keys: web::Data<JwtKeys>) -> Result<HttpResponse, ApiError> {
+    // Publish the public keys tokens can be verified with
+    Ok(HttpResponse::Ok().json(jwt_keys.jwks()))
+}
+
+fn issue_tokens(
+    config: &Config,
+    jwt_keys: &JwtKeys,
+    wallet: &str,
+    scopes: Vec<String>,
+    refresh: IssuedRefreshToken,
+) -> Result<TokenResponse, ApiError> {
+    // Roles come from configuration, so they are re-evaluated on every refresh
+    let mut roles = Vec::new();
+    if config.admin_wallets.iter().any(|admin| admin == wallet) {
+        roles.push(ADMIN_ROLE.to_string());
+    }
+    
+    let now = unix_now()? as usize;
+    let exp = now + config.access_token_ttl_seconds as usize;
     
     // Create JWT claims
     let claims = Claims {
-        sub: req.public_key.clone(),
+        sub: wallet.to_string(),
         exp,
+        iat: now,
+        jti: random_token(16),
+        sid: refresh.session_id,
+        scopes,
+        roles,
+        principal: Principal::WalletSession,
     };
     
-    // Generate JWT token
-    let token = encode(
-        &Header::default(),
-        &claims,
-        &EncodingKey::from_secret(config.jwt_secret.as_bytes()),
-    )?;
+    // Generate JWT token, signed with the current key
+    let token = jwt_keys.encode(&claims)?;
     
-    // Return the token
-    Ok(HttpResponse::Ok().json(TokenResponse {
+    Ok(TokenResponse {
         token,
         token_type: "Bearer".to_string(),
-    }))
-}
\ No newline at end of file
+        expires_in: config.access_token_ttl_seconds,
+        refresh_token: refresh.token,
+    })
+}
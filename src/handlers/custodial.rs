@@ -0,0 +1,99 @@
+// src/handlers/custodial.rs
+use crate::errors::ApiError;
+use crate::models::auth::Claims;
+use crate::models::custodial::{CustodialExportResponse, CustodialWalletResponse};
+use crate::signer::TransactionSigner;
+use actix_web::{web, HttpResponse};
+
+#[utoipa::path(
+    post,
+    path = "/api/custodial/wallet",
+    tag = "custodial",
+    responses(
+        (status = 201, description = "Custodial key created", body = CustodialWalletResponse),
+        (status = 401, description = "Missing or invalid credentials", body = ErrorResponse),
+        (status = 409, description = "The wallet already has a custodial key", body = ErrorResponse),
+        (status = 503, description = "Custodial wallets are disabled", body = ErrorResponse),
+    )
+)]
+pub async fn create_wallet(
+    signer: web::Data<dyn TransactionSigner>,
+    claims: web::ReqData<Claims>,
+) -> Result<HttpResponse, ApiError> {
+    let custodial_pubkey = signer.create(&claims.sub).await?;
+    
+    log::info!("Created custodial wallet {} for {}", custodial_pubkey, claims.sub);
+    
+    Ok(HttpResponse::Created().json(CustodialWalletResponse {
+        wallet: claims.sub.clone(),
+        custodial_pubkey: custodial_pubkey.to_string(),
+    }))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/custodial/wallet",
+    tag = "custodial",
+    responses(
+        (status = 200, description = "The wallet's custodial key", body = CustodialWalletResponse),
+        (status = 401, description = "Missing or invalid credentials", body = ErrorResponse),
+        (status = 404, description = "Not found", body = ErrorResponse),
+    )
+)]
+pub async fn get_wallet(
+    signer: web::Data<dyn TransactionSigner>,
+    claims: web::ReqData<Claims>,
+) -> Result<HttpResponse, ApiError> {
+    let custodial_pubkey = signer.pubkey(&claims.sub).await?;
+    
+    Ok(HttpResponse::Ok().json(CustodialWalletResponse {
+        wallet: claims.sub.clone(),
+        custodial_pubkey: custodial_pubkey.to_string(),
+    }))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/custodial/wallet/export",
+    tag = "custodial",
+    responses(
+        (status = 200, description = "The custodial secret key", body = CustodialExportResponse),
+        (status = 401, description = "Missing or invalid credentials", body = ErrorResponse),
+        (status = 404, description = "Not found", body = ErrorResponse),
+    )
+)]
+pub async fn export_wallet(
+    signer: web::Data<dyn TransactionSigner>,
+    claims: web::ReqData<Claims>,
+) -> Result<HttpResponse, ApiError> {
+    let custodial_pubkey = signer.pubkey(&claims.sub).await?;
+    let secret_key = signer.export(&claims.sub).await?;
+    
+    log::warn!("Exported custodial wallet {} for {}", custodial_pubkey, claims.sub);
+    
+    Ok(HttpResponse::Ok().json(CustodialExportResponse {
+        custodial_pubkey: custodial_pubkey.to_string(),
+        secret_key,
+    }))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/custodial/wallet",
+    tag = "custodial",
+    responses(
+        (status = 204, description = "Custodial key deleted"),
+        (status = 401, description = "Missing or invalid credentials", body = ErrorResponse),
+        (status = 404, description = "Not found", body = ErrorResponse),
+    )
+)]
+pub async fn delete_wallet(
+    signer: web::Data<dyn TransactionSigner>,
+    claims: web::ReqData<Claims>,
+) -> Result<HttpResponse, ApiError> {
+    signer.delete(&claims.sub).await?;
+    
+    log::info!("Deleted custodial wallet for {}", claims.sub);
+    
+    Ok(HttpResponse::NoContent().finish())
+}
@@ -0,0 +1,26 @@
+// src/handlers/job.rs
+use crate::errors::ApiError;
+use crate::models::auth::Claims;
+use crate::solana::jobs::JobQueue;
+use actix_web::{web, HttpResponse};
+
+#[utoipa::path(
+    get,
+    path = "/api/jobs/{id}",
+    tag = "jobs",
+    params(("id" = String, Path, description = "Job id")),
+    responses(
+        (status = 200, description = "Job status", body = JobResponse),
+        (status = 401, description = "Missing or invalid credentials", body = ErrorResponse),
+        (status = 404, description = "Not found", body = ErrorResponse),
+    )
+)]
+pub async fn get_job(
+    path: web::Path<String>,
+    jobs: web::Data<JobQueue>,
+    claims: web::ReqData<Claims>,
+) -> Result<HttpResponse, ApiError> {
+    let job = jobs.get(&claims.sub, &path.into_inner()).await?;
+    
+    Ok(HttpResponse::Ok().json(job))
+}
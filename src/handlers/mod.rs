@@ -1,3 +1,8 @@
 // src/handlers/mod.rs
 pub mod todo;
 pub mod auth;
+pub mod custodial;
+pub mod transaction;
+pub mod sponsorship;
+pub mod rpc;
+pub mod job;
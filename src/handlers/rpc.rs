@@ -0,0 +1,18 @@
+// src/handlers/rpc.rs
+use crate::errors::ApiError;
+use crate::solana::rpc_pool::RpcPool;
+use actix_web::{web, HttpResponse};
+
+#[utoipa::path(
+    get,
+    path = "/api/admin/rpc",
+    tag = "admin",
+    responses(
+        (status = 200, description = "Health and metrics per RPC endpoint", body = [RpcEndpointMetrics]),
+        (status = 401, description = "Missing or invalid credentials", body = ErrorResponse),
+        (status = 403, description = "Not an admin", body = ErrorResponse),
+    )
+)]
+pub async fn get_rpc_metrics(rpc_pool: web::Data<RpcPool>) -> Result<HttpResponse, ApiError> {
+    Ok(HttpResponse::Ok().json(rpc_pool.metrics()))
+}
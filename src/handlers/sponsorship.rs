@@ -0,0 +1,37 @@
+// src/handlers/sponsorship.rs
+use crate::errors::ApiError;
+use crate::models::auth::Claims;
+use crate::models::sponsorship::SponsorshipResponse;
+use crate::solana::relayer::Relayer;
+use actix_web::{web, HttpResponse};
+
+#[utoipa::path(
+    get,
+    path = "/api/sponsorship",
+    tag = "sponsorship",
+    responses(
+        (status = 200, description = "Remaining sponsorship for today", body = SponsorshipResponse),
+        (status = 401, description = "Missing or invalid credentials", body = ErrorResponse),
+        (status = 404, description = "The relayer is disabled", body = ErrorResponse),
+    )
+)]
+pub async fn get_sponsorship(
+    relayer: Option<web::Data<Relayer>>,
+    claims: web::ReqData<Claims>,
+) -> Result<HttpResponse, ApiError> {
+    let sponsorship = match relayer {
+        Some(relayer) => relayer.remaining(&claims.sub)?,
+        // Without a fee payer every wallet pays its own fees and rent
+        None => SponsorshipResponse {
+            enabled: false,
+            fee_payer: None,
+            max_transactions_per_day: 0,
+            max_lamports_per_day: 0,
+            remaining_transactions: 0,
+            remaining_lamports: 0,
+            resets_at: 0,
+        },
+    };
+    
+    Ok(HttpResponse::Ok().json(sponsorship))
+}
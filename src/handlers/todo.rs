@@ -1,88 +1,407 @@
 // src/handlers/todo.rs
-use crate::config::Config;
 use crate::errors::ApiError;
 use crate::models::auth::Claims;
-use crate::models::todo::{CreateTodoRequest, TodoResponse, UpdateTodoRequest};
+use crate::models::job::JobResponse;
+use crate::models::todo::{BatchOperation, BatchRequest, CreateTodoRequest, UpdateTodoRequest};
+use crate::repository::{self, TodoRepository};
+use crate::signer::TransactionSigner;
+use crate::solana::commitment;
+use crate::solana::indexer::INDEXED_SLOT_HEADER;
+use crate::solana::jobs::{prefers_async, JobQueue, PREFERENCE_APPLIED_HEADER, RESPOND_ASYNC};
+use crate::solana::priority_fee::PriorityOverride;
+use crate::solana::simulation;
 use crate::solana::SolanaService;
-use crate::utils::wallet::get_keypair_from_signature;
-use actix_web::{web, HttpResponse};
+use crate::utils::wallet::parse_pubkey;
+use actix_web::{web, HttpRequest, HttpResponse};
+use serde_json::{Map, Value};
+use solana_sdk::commitment_config::CommitmentConfig;
+use solana_sdk::pubkey::Pubkey;
 use validator::Validate;
 
+#[utoipa::path(
+    post,
+    path = "/api/todos",
+    tag = "todos",
+    request_body = CreateTodoRequest,
+    responses(
+        (status = 201, description = "Todo created", body = TodoResponse),
+        (status = 200, description = "Dry run result", body = SimulationResponse),
+        (status = 202, description = "Write queued", body = JobResponse),
+        (status = 400, description = "Invalid request", body = ErrorResponse),
+        (status = 401, description = "Missing or invalid credentials", body = ErrorResponse),
+        (status = 502, description = "Solana RPC or transaction failure", body = ErrorResponse),
+    )
+)]
 pub async fn create_todo(
     req: web::Json<CreateTodoRequest>,
-    config: web::Data<Config>,
+    solana_service: web::Data<SolanaService>,
+    signer: web::Data<dyn TransactionSigner>,
+    jobs: web::Data<JobQueue>,
     claims: web::ReqData<Claims>,
+    http_req: HttpRequest,
 ) -> Result<HttpResponse, ApiError> {
     // Validate the request
     req.validate().map_err(|e| ApiError::BadRequest(e.to_string()))?;
+    let priority = PriorityOverride::from_headers(http_req.headers())?;
+    let commitment = commitment::from_request(&http_req)?;
     
-    // Get the keypair from the JWT claims
-    let keypair = get_keypair_from_signature(&claims.sub)?;
+    if simulation::requested(&http_req)? {
+        let operation = BatchOperation::Create(req.into_inner());
+        return custodial_dry_run(&solana_service, signer.get_ref(), &claims.sub, &operation, priority, commitment)
+            .await;
+    }
     
-    // Create a Solana service instance
-    let solana_service = SolanaService::new(&config)?;
+    if prefers_async(http_req.headers()) {
+        let job = jobs.enqueue(&claims.sub, BatchOperation::Create(req.into_inner()), priority, commitment)?;
+        return Ok(accepted(job));
+    }
     
-    // Call the Solana program
-    let todo = solana_service.create_todo(&keypair, req.into_inner())?;
+    // Call the Solana program, signing with the wallet's custodial key
+    let todo = solana_service
+        .with_commitment(commitment)
+        .create_todo(signer.get_ref(), &claims.sub, req.into_inner(), priority)
+        .await?;
     
     // Return the created todo
     Ok(HttpResponse::Created().json(todo))
 }
 
+#[utoipa::path(
+    get,
+    path = "/api/todos",
+    tag = "todos",
+    responses(
+        (status = 200, description = "The wallet's todos", body = [TodoResponse]),
+        (status = 401, description = "Missing or invalid credentials", body = ErrorResponse),
+        (status = 502, description = "Solana RPC or transaction failure", body = ErrorResponse),
+    )
+)]
 pub async fn get_todos(
-    config: web::Data<Config>,
+    solana_service: web::Data<SolanaService>,
+    signer: web::Data<dyn TransactionSigner>,
     claims: web::ReqData<Claims>,
+    http_req: HttpRequest,
 ) -> Result<HttpResponse, ApiError> {
-    // Create a Solana service instance
-    let solana_service = SolanaService::new(&config)?;
+    let commitment = commitment::from_request(&http_req)?;
+    let owner = todo_owner(signer.get_ref(), &claims.sub).await?;
     
     // Get todos for the wallet
-    let todos = solana_service.get_todos_for_wallet(&claims.sub)?;
+    let (todos, indexed_slot) = solana_service
+        .with_commitment(commitment)
+        .get_todos_for_wallet(&owner.to_string())
+        .await?;
     
-    // Return the todos
-    Ok(HttpResponse::Ok().json(todos))
+    // Return the todos, with how current they are when served from the index
+    let mut response = HttpResponse::Ok();
+    if let Some(slot) = indexed_slot {
+        response.insert_header((INDEXED_SLOT_HEADER, slot.to_string()));
+    }
+    Ok(response.json(todos))
 }
 
+#[utoipa::path(
+    put,
+    path = "/api/todos/{id}",
+    tag = "todos",
+    params(("id" = u64, Path, description = "Todo task id")),
+    request_body = UpdateTodoRequest,
+    responses(
+        (status = 200, description = "Todo updated", body = TodoResponse),
+        (status = 202, description = "Write queued", body = JobResponse),
+        (status = 400, description = "Invalid request", body = ErrorResponse),
+        (status = 401, description = "Missing or invalid credentials", body = ErrorResponse),
+        (status = 404, description = "Not found", body = ErrorResponse),
+        (status = 502, description = "Solana RPC or transaction failure", body = ErrorResponse),
+    )
+)]
 pub async fn update_todo(
     req: web::Json<UpdateTodoRequest>,
     path: web::Path<u64>,
-    config: web::Data<Config>,
+    solana_service: web::Data<SolanaService>,
+    signer: web::Data<dyn TransactionSigner>,
+    jobs: web::Data<JobQueue>,
     claims: web::ReqData<Claims>,
+    http_req: HttpRequest,
 ) -> Result<HttpResponse, ApiError> {
     let task_id = path.into_inner();
     
     // Validate the request
     req.validate().map_err(|e| ApiError::BadRequest(e.to_string()))?;
+    let priority = PriorityOverride::from_headers(http_req.headers())?;
+    let commitment = commitment::from_request(&http_req)?;
     
-    // Get the keypair from the JWT claims
-    let keypair = get_keypair_from_signature(&claims.sub)?;
+    if simulation::requested(&http_req)? {
+        let operation = BatchOperation::Update { task_id, changes: req.into_inner() };
+        return custodial_dry_run(&solana_service, signer.get_ref(), &claims.sub, &operation, priority, commitment)
+            .await;
+    }
     
-    // Create a Solana service instance
-    let solana_service = SolanaService::new(&config)?;
+    if prefers_async(http_req.headers()) {
+        let operation = BatchOperation::Update { task_id, changes: req.into_inner() };
+        let job = jobs.enqueue(&claims.sub, operation, priority, commitment)?;
+        return Ok(accepted(job));
+    }
     
     // Call the Solana program
-    let todo = solana_service.update_todo(&keypair, task_id, req.into_inner())?;
+    let todo = solana_service
+        .with_commitment(commitment)
+        .update_todo(signer.get_ref(), &claims.sub, task_id, req.into_inner(), priority)
+        .await?;
     
     // Return the updated todo
     Ok(HttpResponse::Ok().json(todo))
 }
 
+#[utoipa::path(
+    delete,
+    path = "/api/todos/{id}",
+    tag = "todos",
+    params(("id" = u64, Path, description = "Todo task id")),
+    responses(
+        (status = 204, description = "Todo deleted"),
+        (status = 202, description = "Write queued", body = JobResponse),
+        (status = 401, description = "Missing or invalid credentials", body = ErrorResponse),
+        (status = 404, description = "Not found", body = ErrorResponse),
+        (status = 502, description = "Solana RPC or transaction failure", body = ErrorResponse),
+    )
+)]
 pub async fn delete_todo(
     path: web::Path<u64>,
-    config: web::Data<Config>,
+    solana_service: web::Data<SolanaService>,
+    signer: web::Data<dyn TransactionSigner>,
+    jobs: web::Data<JobQueue>,
     claims: web::ReqData<Claims>,
+    http_req: HttpRequest,
 ) -> Result<HttpResponse, ApiError> {
     let task_id = path.into_inner();
     
-    // Get the keypair from the JWT claims
-    let keypair = get_keypair_from_signature(&claims.sub)?;
+    let priority = PriorityOverride::from_headers(http_req.headers())?;
+    let commitment = commitment::from_request(&http_req)?;
+    
+    if simulation::requested(&http_req)? {
+        let operation = BatchOperation::Delete { task_id };
+        return custodial_dry_run(&solana_service, signer.get_ref(), &claims.sub, &operation, priority, commitment)
+            .await;
+    }
     
-    // Create a Solana service instance
-    let solana_service = SolanaService::new(&config)?;
+    if prefers_async(http_req.headers()) {
+        let job = jobs.enqueue(&claims.sub, BatchOperation::Delete { task_id }, priority, commitment)?;
+        return Ok(accepted(job));
+    }
     
     // Call the Solana program
-    solana_service.delete_todo(&keypair, task_id)?;
+    let receipt = solana_service
+        .with_commitment(commitment)
+        .delete_todo(signer.get_ref(), &claims.sub, task_id, priority)
+        .await?;
+    
+    // Return a success response, with the transaction in headers as there's no body
+    let mut response = HttpResponse::NoContent();
+    response.insert_header(("X-Transaction-Signature", receipt.signature));
+    if let Some(fee) = receipt.fee_lamports {
+        response.insert_header(("X-Transaction-Fee", fee.to_string()));
+    }
+    if let Some(slot) = receipt.slot {
+        response.insert_header(("X-Transaction-Slot", slot.to_string()));
+    }
+    if let Some(commitment) = receipt.commitment {
+        response.insert_header(("X-Transaction-Commitment", commitment));
+    }
+    Ok(response.finish())
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/todos/batch",
+    tag = "todos",
+    request_body = BatchRequest,
+    responses(
+        (status = 200, description = "Result per operation", body = BatchResponse),
+        (status = 400, description = "Invalid request", body = ErrorResponse),
+        (status = 401, description = "Missing or invalid credentials", body = ErrorResponse),
+        (status = 502, description = "Solana RPC or transaction failure", body = ErrorResponse),
+    )
+)]
+pub async fn batch_todos(
+    req: web::Json<BatchRequest>,
+    solana_service: web::Data<SolanaService>,
+    signer: web::Data<dyn TransactionSigner>,
+    claims: web::ReqData<Claims>,
+    http_req: HttpRequest,
+) -> Result<HttpResponse, ApiError> {
+    // Individual operations are validated as they're built, so one bad
+    // operation doesn't reject the whole batch
+    req.validate().map_err(|e| ApiError::BadRequest(e.to_string()))?;
+    let priority = PriorityOverride::from_headers(http_req.headers())?;
+    let commitment = commitment::from_request(&http_req)?;
+    
+    let response = solana_service
+        .with_commitment(commitment)
+        .batch(signer.get_ref(), &claims.sub, req.into_inner().operations, priority)
+        .await?;
+    
+    Ok(HttpResponse::Ok().json(response))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/todos/migrate",
+    tag = "todos",
+    responses(
+        (status = 200, description = "Migrations sent or to sign", body = MigrationResponse),
+        (status = 401, description = "Missing or invalid credentials", body = ErrorResponse),
+        (status = 502, description = "Solana RPC or transaction failure", body = ErrorResponse),
+    )
+)]
+pub async fn migrate_todos(
+    solana_service: web::Data<SolanaService>,
+    signer: web::Data<dyn TransactionSigner>,
+    claims: web::ReqData<Claims>,
+    http_req: HttpRequest,
+) -> Result<HttpResponse, ApiError> {
+    let priority = PriorityOverride::from_headers(http_req.headers())?;
+    let commitment = commitment::from_request(&http_req)?;
+    let solana_service = solana_service.with_commitment(commitment);
+    
+    // Custodial todos are migrated here; a wallet's own come back for it to sign
+    let response = match signer.pubkey(&claims.sub).await {
+        Ok(owner) => {
+            solana_service
+                .migrate_todos(Some(signer.get_ref()), &claims.sub, &owner, priority)
+                .await?
+        }
+        Err(ApiError::NotFound(_)) => {
+            let owner = parse_pubkey(&claims.sub)?;
+            solana_service.migrate_todos(None, &claims.sub, &owner, priority).await?
+        }
+        Err(e) => return Err(e),
+    };
+    
+    Ok(HttpResponse::Ok().json(response))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/todos/{id}/metadata",
+    tag = "todos",
+    params(("id" = u64, Path, description = "Todo task id")),
+    responses(
+        (status = 200, description = "The todo's off-chain metadata", body = Object),
+        (status = 401, description = "Missing or invalid credentials", body = ErrorResponse),
+        (status = 404, description = "Not found", body = ErrorResponse),
+    )
+)]
+pub async fn get_metadata(
+    path: web::Path<u64>,
+    repository: web::Data<dyn TodoRepository>,
+    signer: web::Data<dyn TransactionSigner>,
+    claims: web::ReqData<Claims>,
+) -> Result<HttpResponse, ApiError> {
+    let task_id = path.into_inner();
+    let owner = todo_owner(signer.get_ref(), &claims.sub).await?;
+    
+    let metadata = repository::run_blocking(&repository.into_inner(), move |repository| {
+        repository.metadata(&owner, task_id)
+    })
+    .await?
+    .ok_or_else(|| ApiError::NotFound(format!("No metadata for todo {}", task_id)))?;
+    
+    Ok(HttpResponse::Ok().json(metadata))
+}
+
+#[utoipa::path(
+    put,
+    path = "/api/todos/{id}/metadata",
+    tag = "todos",
+    params(("id" = u64, Path, description = "Todo task id")),
+    request_body = Object,
+    responses(
+        (status = 200, description = "Metadata stored", body = Object),
+        (status = 400, description = "Invalid request", body = ErrorResponse),
+        (status = 401, description = "Missing or invalid credentials", body = ErrorResponse),
+    )
+)]
+pub async fn set_metadata(
+    path: web::Path<u64>,
+    req: web::Json<Map<String, Value>>,
+    repository: web::Data<dyn TodoRepository>,
+    signer: web::Data<dyn TransactionSigner>,
+    claims: web::ReqData<Claims>,
+) -> Result<HttpResponse, ApiError> {
+    let task_id = path.into_inner();
+    let owner = todo_owner(signer.get_ref(), &claims.sub).await?;
+    
+    // Kept off-chain, so it's replaced whole rather than merged
+    let metadata = req.into_inner();
+    let stored = metadata.clone();
+    repository::run_blocking(&repository.into_inner(), move |repository| {
+        repository.set_metadata(&owner, task_id, &stored)
+    })
+    .await?;
+    
+    Ok(HttpResponse::Ok().json(metadata))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/todos/{id}/metadata",
+    tag = "todos",
+    params(("id" = u64, Path, description = "Todo task id")),
+    responses(
+        (status = 204, description = "Metadata deleted"),
+        (status = 401, description = "Missing or invalid credentials", body = ErrorResponse),
+    )
+)]
+pub async fn delete_metadata(
+    path: web::Path<u64>,
+    repository: web::Data<dyn TodoRepository>,
+    signer: web::Data<dyn TransactionSigner>,
+    claims: web::ReqData<Claims>,
+) -> Result<HttpResponse, ApiError> {
+    let task_id = path.into_inner();
+    let owner = todo_owner(signer.get_ref(), &claims.sub).await?;
+    
+    repository::run_blocking(&repository.into_inner(), move |repository| {
+        repository.delete_metadata(&owner, task_id)
+    })
+    .await?;
     
-    // Return a success response
     Ok(HttpResponse::NoContent().finish())
-}
\ No newline at end of file
+}
+
+// Simulate the write under the wallet's custodial key instead of sending it.
+// Custodial routes only; wallet-signed builds simulate with `wallet_dry_run`.
+async fn custodial_dry_run(
+    solana_service: &SolanaService,
+    signer: &dyn TransactionSigner,
+    wallet: &str,
+    operation: &BatchOperation,
+    priority: Option<PriorityOverride>,
+    commitment: Option<CommitmentConfig>,
+) -> Result<HttpResponse, ApiError> {
+    let owner = signer.pubkey(wallet).await?;
+    let simulation = solana_service
+        .with_commitment(commitment)
+        .simulate_operation(&owner, operation, priority)
+        .await?;
+    
+    Ok(HttpResponse::Ok().json(simulation))
+}
+
+// The write was queued; point the caller at the job to poll
+fn accepted(job: JobResponse) -> HttpResponse {
+    HttpResponse::Accepted()
+        .insert_header(("Location", format!("/api/jobs/{}", job.id)))
+        .insert_header((PREFERENCE_APPLIED_HEADER, RESPOND_ASYNC))
+        .json(job)
+}
+
+// Todos live under the custodial key when the wallet has one,
+// otherwise under the wallet itself (client-signed flow)
+async fn todo_owner(signer: &dyn TransactionSigner, wallet: &str) -> Result<Pubkey, ApiError> {
+    match signer.pubkey(wallet).await {
+        Ok(custodial) => Ok(custodial),
+        Err(ApiError::NotFound(_)) => parse_pubkey(wallet),
+        Err(e) => Err(e),
+    }
+}
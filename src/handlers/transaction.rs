@@ -0,0 +1,210 @@
+// src/handlers/transaction.rs
+use crate::errors::ApiError;
+use crate::models::auth::Claims;
+use crate::models::todo::{BatchOperation, CreateTodoRequest, UpdateTodoRequest};
+use crate::models::transaction::{SubmitTransactionRequest, SubmitTransactionResponse};
+use crate::solana::commitment;
+use crate::solana::priority_fee::PriorityOverride;
+use crate::solana::simulation;
+use crate::solana::SolanaService;
+use crate::utils::wallet::parse_pubkey;
+use actix_web::{web, HttpRequest, HttpResponse};
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use solana_sdk::instruction::Instruction;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::transaction::Transaction;
+use validator::Validate;
+
+#[utoipa::path(
+    post,
+    path = "/api/transactions/todos",
+    tag = "transactions",
+    request_body = CreateTodoRequest,
+    responses(
+        (status = 200, description = "Unsigned create transaction", body = UnsignedTransactionResponse),
+        (status = 400, description = "Invalid request", body = ErrorResponse),
+        (status = 401, description = "Missing or invalid credentials", body = ErrorResponse),
+    )
+)]
+pub async fn build_create_todo(
+    req: web::Json<CreateTodoRequest>,
+    solana_service: web::Data<SolanaService>,
+    claims: web::ReqData<Claims>,
+    http_req: HttpRequest,
+) -> Result<HttpResponse, ApiError> {
+    // Validate the request
+    req.validate().map_err(|e| ApiError::BadRequest(e.to_string()))?;
+    
+    let owner = parse_pubkey(&claims.sub)?;
+    let priority = PriorityOverride::from_headers(http_req.headers())?;
+    
+    if simulation::requested(&http_req)? {
+        let operation = BatchOperation::Create(req.into_inner());
+        return wallet_dry_run(&solana_service, &owner, &operation, priority).await;
+    }
+    
+    let instructions = solana_service.create_todo_instructions(&owner, &req).await?;
+    let todo_account = solana_service.todo_address(&owner, req.task_id);
+    
+    unsigned_response(&solana_service, &owner, todo_account, &instructions, priority).await
+}
+
+#[utoipa::path(
+    put,
+    path = "/api/transactions/todos/{id}",
+    tag = "transactions",
+    params(("id" = u64, Path, description = "Todo task id")),
+    request_body = UpdateTodoRequest,
+    responses(
+        (status = 200, description = "Unsigned update transaction", body = UnsignedTransactionResponse),
+        (status = 400, description = "Invalid request", body = ErrorResponse),
+        (status = 401, description = "Missing or invalid credentials", body = ErrorResponse),
+        (status = 404, description = "Not found", body = ErrorResponse),
+    )
+)]
+pub async fn build_update_todo(
+    req: web::Json<UpdateTodoRequest>,
+    path: web::Path<u64>,
+    solana_service: web::Data<SolanaService>,
+    claims: web::ReqData<Claims>,
+    http_req: HttpRequest,
+) -> Result<HttpResponse, ApiError> {
+    let task_id = path.into_inner();
+    
+    // Validate the request
+    req.validate().map_err(|e| ApiError::BadRequest(e.to_string()))?;
+    
+    let owner = parse_pubkey(&claims.sub)?;
+    let priority = PriorityOverride::from_headers(http_req.headers())?;
+    
+    if simulation::requested(&http_req)? {
+        let operation = BatchOperation::Update { task_id, changes: req.into_inner() };
+        return wallet_dry_run(&solana_service, &owner, &operation, priority).await;
+    }
+    
+    let instructions = solana_service.update_todo_instructions(&owner, task_id, &req).await?;
+    if instructions.is_empty() {
+        return Err(ApiError::BadRequest("Nothing to update".to_string()));
+    }
+    let todo_account = solana_service.todo_address(&owner, task_id);
+    
+    unsigned_response(&solana_service, &owner, todo_account, &instructions, priority).await
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/transactions/todos/{id}",
+    tag = "transactions",
+    params(("id" = u64, Path, description = "Todo task id")),
+    responses(
+        (status = 200, description = "Unsigned delete transaction", body = UnsignedTransactionResponse),
+        (status = 401, description = "Missing or invalid credentials", body = ErrorResponse),
+        (status = 404, description = "Not found", body = ErrorResponse),
+    )
+)]
+pub async fn build_delete_todo(
+    path: web::Path<u64>,
+    solana_service: web::Data<SolanaService>,
+    claims: web::ReqData<Claims>,
+    http_req: HttpRequest,
+) -> Result<HttpResponse, ApiError> {
+    let task_id = path.into_inner();
+    
+    let owner = parse_pubkey(&claims.sub)?;
+    let priority = PriorityOverride::from_headers(http_req.headers())?;
+    
+    if simulation::requested(&http_req)? {
+        let operation = BatchOperation::Delete { task_id };
+        return wallet_dry_run(&solana_service, &owner, &operation, priority).await;
+    }
+    
+    let instructions = solana_service.delete_todo_instructions(&owner, task_id).await?;
+    let todo_account = solana_service.todo_address(&owner, task_id);
+    
+    unsigned_response(&solana_service, &owner, todo_account, &instructions, priority).await
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/transactions/submit",
+    tag = "transactions",
+    request_body = SubmitTransactionRequest,
+    responses(
+        (status = 200, description = "Transaction landed", body = SubmitTransactionResponse),
+        (status = 400, description = "Invalid request", body = ErrorResponse),
+        (status = 401, description = "Missing or invalid credentials", body = ErrorResponse),
+        (status = 502, description = "Solana RPC or transaction failure", body = ErrorResponse),
+    )
+)]
+pub async fn submit_transaction(
+    req: web::Json<SubmitTransactionRequest>,
+    solana_service: web::Data<SolanaService>,
+    claims: web::ReqData<Claims>,
+    http_req: HttpRequest,
+) -> Result<HttpResponse, ApiError> {
+    let commitment = commitment::from_request(&http_req)?;
+    let bytes = BASE64
+        .decode(&req.transaction)
+        .map_err(|_| ApiError::BadRequest("Transaction is not valid base64".to_string()))?;
+    let transaction: Transaction = bincode::deserialize(&bytes)
+        .map_err(|_| ApiError::BadRequest("Transaction could not be decoded".to_string()))?;
+    
+    // Verify it only acts on the caller's todos, co-sign as fee payer if relaying, and send
+    let receipt = solana_service
+        .with_commitment(commitment)
+        .submit_signed_transaction(&claims.sub, transaction)
+        .await?;
+    
+    log::info!("Submitted wallet-signed transaction with signature: {}", receipt.signature);
+    
+    Ok(HttpResponse::Ok().json(SubmitTransactionResponse { receipt }))
+}
+
+// Simulate the transaction the wallet would be asked to sign, owned by the wallet
+// itself, without building it for signing
+async fn wallet_dry_run(
+    solana_service: &SolanaService,
+    owner: &Pubkey,
+    operation: &BatchOperation,
+    priority: Option<PriorityOverride>,
+) -> Result<HttpResponse, ApiError> {
+    let simulation = solana_service.simulate_operation(owner, operation, priority).await?;
+    
+    Ok(HttpResponse::Ok().json(simulation))
+}
+
+async fn unsigned_response(
+    solana_service: &SolanaService,
+    owner: &Pubkey,
+    todo_account: Pubkey,
+    instructions: &[Instruction],
+    priority: Option<PriorityOverride>,
+) -> Result<HttpResponse, ApiError> {
+    let unsigned = solana_service
+        .unsigned_transaction(owner, todo_account, instructions, priority)
+        .await?;
+    
+    Ok(HttpResponse::Ok().json(unsigned))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/transactions/{signature}",
+    tag = "transactions",
+    params(("signature" = String, Path, description = "Transaction signature")),
+    responses(
+        (status = 200, description = "Transaction status", body = TransactionStatusResponse),
+        (status = 400, description = "Invalid request", body = ErrorResponse),
+        (status = 401, description = "Missing or invalid credentials", body = ErrorResponse),
+        (status = 404, description = "Not found", body = ErrorResponse),
+    )
+)]
+pub async fn get_transaction_status(
+    path: web::Path<String>,
+    solana_service: web::Data<SolanaService>,
+) -> Result<HttpResponse, ApiError> {
+    let status = solana_service.transaction_status(&path.into_inner()).await?;
+    
+    Ok(HttpResponse::Ok().json(status))
+}
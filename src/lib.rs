@@ -1,4 +1,5 @@
 // src/lib.rs
+pub mod auth;
 pub mod config;
 pub mod errors;
 pub mod handlers;
@@ -6,5 +7,6 @@ pub mod middleware;
 pub mod models;
 pub mod repository;
 pub mod routes;
+pub mod signer;
 pub mod solana;
 pub mod utils;
\ No newline at end of file
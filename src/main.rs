@@ -1,8 +1,23 @@
 // src/main.rs
 use actix_web::{web, App, HttpServer, middleware};
 use dotenv::dotenv;
-use std::env;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+use todo_api::auth::api_keys::ApiKeyStore;
+use todo_api::auth::keys::JwtKeys;
+use todo_api::auth::nonce::NonceStore;
+use todo_api::auth::revocation::RevocationStore;
+use todo_api::auth::session::SessionStore;
 use todo_api::config;
+use todo_api::repository;
+use todo_api::signer;
+use todo_api::solana::idl::Idl;
+use todo_api::solana::indexer::TodoIndexer;
+use todo_api::solana::jobs::JobQueue;
+use todo_api::solana::relayer::Relayer;
+use todo_api::solana::rpc_pool::RpcPool;
+use todo_api::solana::SolanaService;
 use todo_api::routes;
 
 #[actix_web::main]
@@ -11,9 +26,90 @@ async fn main() -> std::io::Result<()> {
     env_logger::init_from_env(env_logger::Env::new().default_filter_or("info"));
 
     let config = config::load_config();
+    let bind_addr = format!("{}:{}", config.host, config.port);
+    let jwt_keys = web::Data::new(
+        JwtKeys::from_config(&config)
+            .map_err(|e| std::io::Error::other(e.to_string()))?,
+    );
+    let nonce_store = web::Data::new(NonceStore::new(config.nonce_ttl_seconds));
+    let session_store = web::Data::new(SessionStore::new(config.refresh_token_ttl_seconds));
+    let revocation_store = web::Data::new(
+        RevocationStore::new(
+            config.revocation_store_path.as_ref().map(PathBuf::from),
+            config.access_token_ttl_seconds,
+        )
+        .map_err(|e| std::io::Error::other(e.to_string()))?,
+    );
+    // Opening the database applies any pending schema migrations
+    let repository = repository::from_config(&config)
+        .map_err(|e| std::io::Error::other(e.to_string()))?;
+    let api_key_store = web::Data::new(
+        ApiKeyStore::new()
+            .with_repository(repository.clone())
+            .map_err(|e| std::io::Error::other(e.to_string()))?,
+    );
+    let transaction_signer = web::Data::from(
+        signer::from_config(&config)
+            .map_err(|e| std::io::Error::other(e.to_string()))?,
+    );
+    let relayer = config
+        .fee_payer_keypair_path
+        .as_deref()
+        .map(|path| {
+            Relayer::from_keypair_file(
+                path,
+                config.sponsorship_max_transactions_per_day,
+                config.sponsorship_max_lamports_per_day,
+            )
+            .and_then(|relayer| relayer.with_repository(repository.clone()))
+            .map(web::Data::new)
+        })
+        .transpose()
+        .map_err(|e| std::io::Error::other(e.to_string()))?;
+    let rpc_pool = Arc::new(
+        RpcPool::from_config(&config)
+            .map_err(|e| std::io::Error::other(e.to_string()))?,
+    );
+    rpc_pool
+        .clone()
+        .spawn_health_checks(Duration::from_secs(config.rpc_health_interval_seconds));
+    let idl = Arc::new(
+        Idl::load(&config, &rpc_pool)
+            .await
+            .map_err(|e| std::io::Error::other(e.to_string()))?,
+    );
+    // One shared service so every worker reuses the same RPC endpoints
+    let solana_service = SolanaService::new(
+        &config,
+        rpc_pool.clone(),
+        idl,
+        relayer.clone().map(|relayer| relayer.into_inner()),
+    )
+    .map_err(|e| std::io::Error::other(e.to_string()))?;
+    // List reads are served from the index once it has backfilled
+    let solana_service = if config.indexer_enabled {
+        let indexer = Arc::new(
+            TodoIndexer::from_config(&config)
+                .map_err(|e| std::io::Error::other(e.to_string()))?
+                .with_repository(repository.clone()),
+        );
+        let solana_service = solana_service.with_indexer(indexer.clone());
+        indexer.spawn(solana_service.clone());
+        solana_service
+    } else {
+        solana_service
+    };
+    let solana_service = web::Data::new(solana_service);
+    let rpc_pool = web::Data::from(rpc_pool);
+    let job_queue = Arc::new(JobQueue::from_config(&config).with_repository(repository.clone()));
+    job_queue
+        .clone()
+        .spawn_workers(config.job_workers, solana_service.clone(), transaction_signer.clone());
+    let job_queue = web::Data::from(job_queue);
+    let repository = web::Data::from(repository);
     let app_data = web::Data::new(config);
 
-    log::info!("Starting server at http://{}:{}", config.host, config.port);
+    log::info!("Starting server at http://{}", bind_addr);
 
     HttpServer::new(move || {
         App::new()
@@ -21,14 +117,36 @@ async fn main() -> std::io::Result<()> {
             .wrap(middleware::NormalizePath::trim())
             .wrap(todo_api::middleware::rate_limiter::RateLimiter::new(100, 60)) // 100 requests per minute
             .app_data(app_data.clone())
+            .app_data(jwt_keys.clone())
+            .app_data(nonce_store.clone())
+            .app_data(session_store.clone())
+            .app_data(revocation_store.clone())
+            .app_data(api_key_store.clone())
+            .app_data(transaction_signer.clone())
+            .app_data(solana_service.clone())
+            .app_data(rpc_pool.clone())
+            .app_data(job_queue.clone())
+            .app_data(repository.clone())
+            .configure(|cfg| {
+                // The relayer is optional; handlers fall back to wallet-paid fees without it
+                if let Some(relayer) = &relayer {
+                    cfg.app_data(relayer.clone());
+                }
+            })
             .service(
                 web::scope("/api")
                     .configure(routes::todo_routes::config)
                     .configure(routes::auth_routes::config)
+                    .configure(routes::transaction_routes::config)
+                    .configure(routes::custodial_routes::config)
+                    .configure(routes::sponsorship_routes::config)
+                    .configure(routes::admin_routes::config)
+                    .configure(routes::job_routes::config)
             )
+            .configure(routes::auth_routes::well_known)
             .service(routes::swagger::swagger_ui())
     })
-    .bind(format!("{}:{}", config.host, config.port))?
+    .bind(bind_addr)?
     .run()
     .await
 }
\ No newline at end of file
@@ -1,25 +1,79 @@
 // src/middleware/auth.rs
-use crate::config::Config;
+use crate::auth::api_keys::ApiKeyStore;
+use crate::auth::keys::JwtKeys;
+use crate::auth::revocation::RevocationStore;
 use crate::errors::ApiError;
-use crate::models::auth::Claims;
+use crate::models::auth::{Claims, Principal};
 use actix_web::dev::{forward_ready, Service, ServiceRequest, ServiceResponse, Transform};
-use actix_web::{Error, HttpMessage};
+use actix_web::http::Method;
+use actix_web::{web, Error, HttpMessage};
 use futures::future::{ready, LocalBoxFuture, Ready};
-use jsonwebtoken::{decode, DecodingKey, Validation};
-use std::future::Future;
-use std::pin::Pin;
 use std::rc::Rc;
-use std::task::{Context, Poll};
 
+// What a route or scope demands of the caller beyond being authenticated
+#[derive(Clone, Default)]
+struct Requirements {
+    // Scopes, optionally limited to one HTTP method
+    scopes: Vec<(Option<Method>, &'static str)>,
+    roles: Vec<&'static str>,
+    wallet_session: bool,
+}
+
+impl Requirements {
+    fn check(&self, method: &Method, claims: &Claims) -> Result<(), ApiError> {
+        let missing_scope = self.scopes.iter().find(|(required_method, scope)| {
+            required_method.as_ref().is_none_or(|m| m == method) && !claims.has_scope(scope)
+        });
+
+        if let Some((_, scope)) = missing_scope {
+            return Err(ApiError::Forbidden(format!("Missing required scope: {}", scope)));
+        }
+
+        if let Some(role) = self.roles.iter().find(|role| !claims.has_role(role)) {
+            return Err(ApiError::Forbidden(format!("Missing required role: {}", role)));
+        }
+
+        if self.wallet_session && claims.principal != Principal::WalletSession {
+            return Err(ApiError::Forbidden("Requires a wallet sign-in, not an API key".to_string()));
+        }
+
+        Ok(())
+    }
+}
+
+// Authenticates requests with either `Bearer <jwt>` or `ApiKey <key>` and
+// enforces the scopes and roles declared where it wraps a route or scope.
+#[derive(Default)]
 pub struct AuthMiddleware {
-    config: Rc<Config>,
+    requirements: Rc<Requirements>,
 }
 
 impl AuthMiddleware {
-    pub fn new(config: Config) -> Self {
-        AuthMiddleware {
-            config: Rc::new(config),
-        }
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // Require `scope` for every request
+    pub fn require_scope(mut self, scope: &'static str) -> Self {
+        Rc::make_mut(&mut self.requirements).scopes.push((None, scope));
+        self
+    }
+
+    // Require `scope` only for requests with the given method
+    pub fn require_method_scope(mut self, method: Method, scope: &'static str) -> Self {
+        Rc::make_mut(&mut self.requirements).scopes.push((Some(method), scope));
+        self
+    }
+
+    pub fn require_role(mut self, role: &'static str) -> Self {
+        Rc::make_mut(&mut self.requirements).roles.push(role);
+        self
+    }
+
+    // Refuse API keys, whatever their scopes
+    pub fn require_wallet_session(mut self) -> Self {
+        Rc::make_mut(&mut self.requirements).wallet_session = true;
+        self
     }
 }
 
@@ -38,14 +92,14 @@ where
     fn new_transform(&self, service: S) -> Self::Future {
         ready(Ok(AuthMiddlewareService {
             service,
-            config: self.config.clone(),
+            requirements: self.requirements.clone(),
         }))
     }
 }
 
 pub struct AuthMiddlewareService<S> {
     service: S,
-    config: Rc<Config>,
+    requirements: Rc<Requirements>,
 }
 
 impl<S, B> Service<ServiceRequest> for AuthMiddlewareService<S>
@@ -61,35 +115,61 @@ where
     forward_ready!(service);
 
     fn call(&self, req: ServiceRequest) -> Self::Future {
-        let auth_header = req.headers().get("Authorization");
-        let config = self.config.clone();
-        let service = self.service.clone();
-
-        Box::pin(async move {
-            match auth_header {
-                Some(header) => {
-                    let auth_str = header.to_str().map_err(|_| {
-                        ApiError::Unauthorized("Invalid authorization header".to_string())
-                    })?;
-
-                    if !auth_str.starts_with("Bearer ") {
-                        return Err(ApiError::Unauthorized("Invalid token format".to_string()).into());
-                    }
-
-                    let token = auth_str[7..].trim();
-                    
-                    let token_data = decode::<Claims>(
-                        token,
-                        &DecodingKey::from_secret(config.jwt_secret.as_bytes()),
-                        &Validation::default(),
-                    )
-                    .map_err(|_| ApiError::Unauthorized("Invalid token".to_string()))?;
-
-                    req.extensions_mut().insert(token_data.claims);
-                    service.call(req).await
-                }
-                None => Err(ApiError::Unauthorized("Missing authorization header".to_string()).into()),
-            }
-        })
+        let claims = match authenticate(&req) {
+            Ok(claims) => claims,
+            Err(e) => return Box::pin(async move { Err(e.into()) }),
+        };
+
+        if let Err(e) = self.requirements.check(req.method(), &claims) {
+            return Box::pin(async move { Err(e.into()) });
+        }
+
+        req.extensions_mut().insert(claims);
+
+        let fut = self.service.call(req);
+        Box::pin(fut)
     }
-}
\ No newline at end of file
+}
+
+fn authenticate(req: &ServiceRequest) -> Result<Claims, ApiError> {
+    let header = req
+        .headers()
+        .get("Authorization")
+        .ok_or_else(|| ApiError::Unauthorized("Missing authorization header".to_string()))?;
+
+    let auth_str = header
+        .to_str()
+        .map_err(|_| ApiError::Unauthorized("Invalid authorization header".to_string()))?;
+
+    if let Some(token) = auth_str.strip_prefix("Bearer ") {
+        let jwt_keys = req
+            .app_data::<web::Data<JwtKeys>>()
+            .ok_or_else(|| ApiError::InternalServerError("JWT keys not configured".to_string()))?;
+
+        // Verify against the key named by the token's kid
+        let claims = jwt_keys
+            .decode(token.trim())
+            .map_err(|_| ApiError::Unauthorized("Invalid token".to_string()))?;
+
+        // Reject tokens revoked by logout before they expire. Without the store
+        // revoked tokens would pass, so fail closed.
+        let revocations = req
+            .app_data::<web::Data<RevocationStore>>()
+            .ok_or_else(|| ApiError::InternalServerError("Token revocation not configured".to_string()))?;
+        if revocations.is_revoked(&claims)? {
+            return Err(ApiError::Unauthorized("Token revoked".to_string()));
+        }
+
+        return Ok(claims);
+    }
+
+    if let Some(key) = auth_str.strip_prefix("ApiKey ") {
+        let api_keys = req
+            .app_data::<web::Data<ApiKeyStore>>()
+            .ok_or_else(|| ApiError::Unauthorized("API keys are not enabled".to_string()))?;
+
+        return api_keys.authenticate(key.trim());
+    }
+
+    Err(ApiError::Unauthorized("Invalid token format".to_string()))
+}
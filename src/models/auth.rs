@@ -1,21 +1,104 @@
 // src/models/auth.rs
 use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use validator::Validate;
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct WalletAuth {
     pub public_key: String,
     pub signature: String,
     pub message: String,
+    pub scopes: Option<Vec<String>>, // request a narrower token, defaults to all wallet scopes
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+// What a request authenticated with
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Principal {
+    #[default]
+    WalletSession,
+    ApiKey,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Claims {
     pub sub: String, // wallet public key
     pub exp: usize,  // expiration time
+    pub iat: usize,  // issued at
+    pub jti: String, // unique token id
+    pub sid: String, // session the token was issued for
+    #[serde(default)]
+    pub scopes: Vec<String>,
+    #[serde(default)]
+    pub roles: Vec<String>,
+    // Not part of the token: signed tokens are always wallet sessions
+    #[serde(skip)]
+    pub principal: Principal,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+impl Claims {
+    pub fn has_scope(&self, scope: &str) -> bool {
+        self.scopes.iter().any(|granted| granted == scope)
+    }
+    
+    pub fn has_role(&self, role: &str) -> bool {
+        self.roles.iter().any(|granted| granted == role)
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct TokenResponse {
     pub token: String,
     pub token_type: String,
-}
\ No newline at end of file
+    pub expires_in: u64, // access token lifetime in seconds
+    pub refresh_token: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct RefreshRequest {
+    pub refresh_token: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ChallengeQuery {
+    pub public_key: String,
+}
+
+// SIWS input for the wallet, plus the same fields rendered as the message to sign
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct ChallengeResponse {
+    pub domain: String,
+    pub address: String,
+    pub statement: String,
+    pub uri: String,
+    pub version: String,
+    pub chain_id: String,
+    pub nonce: String,
+    pub issued_at: String,
+    pub expiration_time: String,
+    pub message: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema, Validate)]
+pub struct CreateApiKeyRequest {
+    #[validate(length(min = 1, max = 64))]
+    pub name: String,
+    
+    pub scopes: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct ApiKeyResponse {
+    pub id: String,
+    pub name: String,
+    pub scopes: Vec<String>,
+    pub created_at: u64,
+    pub last_used_at: Option<u64>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct CreatedApiKeyResponse {
+    pub key: String, // only returned once, at creation
+    #[serde(flatten)]
+    pub api_key: ApiKeyResponse,
+}
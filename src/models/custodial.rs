@@ -0,0 +1,15 @@
+// src/models/custodial.rs
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct CustodialWalletResponse {
+    pub wallet: String,           // the signed-in wallet
+    pub custodial_pubkey: String, // the server-held key that owns its todos
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct CustodialExportResponse {
+    pub custodial_pubkey: String,
+    pub secret_key: String, // base58, importable into Solana wallets
+}
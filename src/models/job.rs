@@ -0,0 +1,32 @@
+// src/models/job.rs
+use crate::models::todo::TodoResponse;
+use crate::models::transaction::TransactionReceipt;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+// queued -> processing -> confirmed | failed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum JobStatus {
+    Queued,
+    Processing,
+    Confirmed,
+    Failed,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct JobResponse {
+    pub id: String,
+    pub status: JobStatus,
+    pub operation: String, // create, update or delete
+    pub task_id: u64,
+    pub created_at: i64,
+    pub updated_at: i64,
+    // The todo as written, for confirmed creates and updates
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub todo: Option<TodoResponse>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub transaction: Option<TransactionReceipt>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
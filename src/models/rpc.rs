@@ -0,0 +1,15 @@
+// src/models/rpc.rs
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct RpcEndpointMetrics {
+    pub url: String, // query string stripped
+    pub weight: u32,
+    pub healthy: bool,
+    pub slot: u64, // as of the last health check
+    pub requests: u64,
+    pub failures: u64,
+    pub average_latency_ms: u64,
+    pub last_error: Option<String>,
+}
@@ -0,0 +1,14 @@
+// src/models/sponsorship.rs
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct SponsorshipResponse {
+    pub enabled: bool,
+    pub fee_payer: Option<String>,
+    pub max_transactions_per_day: u32,
+    pub max_lamports_per_day: u64,
+    pub remaining_transactions: u32,
+    pub remaining_lamports: u64,
+    pub resets_at: u64, // unix timestamp of the next budget reset
+}
@@ -1,8 +1,10 @@
 // src/models/todo.rs
+use crate::models::transaction::{TransactionReceipt, UnsignedTransactionResponse};
 use serde::{Deserialize, Serialize};
-use validator::Validate;
+use utoipa::ToSchema;
+use validator::{Validate, ValidationErrors};
 
-#[derive(Debug, Serialize, Deserialize, Validate)]
+#[derive(Debug, Serialize, Deserialize, ToSchema, Validate)]
 pub struct CreateTodoRequest {
     #[validate(range(min = 1))]
     pub task_id: u64,
@@ -13,7 +15,7 @@ pub struct CreateTodoRequest {
     pub due_date: i64,
 }
 
-#[derive(Debug, Serialize, Deserialize, Validate)]
+#[derive(Debug, Serialize, Deserialize, ToSchema, Validate)]
 pub struct UpdateTodoRequest {
     #[validate(length(max = 280))]
     pub description: Option<String>,
@@ -21,11 +23,89 @@ pub struct UpdateTodoRequest {
     pub completed: Option<bool>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct TodoResponse {
     pub task_id: u64,
     pub description: String,
     pub completed: bool,
     pub due_date: i64,
     pub owner: String,
-}
\ No newline at end of file
+    // The on-chain account layout; older ones can be brought up to date with /api/todos/migrate
+    pub layout_version: u8,
+    // The transaction that made the change, on writes
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub transaction: Option<TransactionReceipt>,
+}
+// One operation in a batch, tagged by `op`
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(tag = "op", rename_all = "snake_case")]
+pub enum BatchOperation {
+    Create(CreateTodoRequest),
+    Update {
+        task_id: u64,
+        #[serde(flatten)]
+        changes: UpdateTodoRequest,
+    },
+    Delete {
+        task_id: u64,
+    },
+}
+
+impl BatchOperation {
+    pub fn task_id(&self) -> u64 {
+        match self {
+            BatchOperation::Create(request) => request.task_id,
+            BatchOperation::Update { task_id, .. } | BatchOperation::Delete { task_id } => *task_id,
+        }
+    }
+
+    pub fn validate(&self) -> Result<(), ValidationErrors> {
+        match self {
+            BatchOperation::Create(request) => request.validate(),
+            BatchOperation::Update { changes, .. } => changes.validate(),
+            BatchOperation::Delete { .. } => Ok(()),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema, Validate)]
+pub struct BatchRequest {
+    // utoipa can't describe the flattened update variant, so the schema leaves the operations open
+    #[validate(length(min = 1, max = 100))]
+    #[schema(value_type = Vec<Object>)]
+    pub operations: Vec<BatchOperation>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct BatchOperationResult {
+    pub index: usize, // position in the request
+    pub task_id: u64,
+    pub success: bool,
+    pub signature: Option<String>, // the transaction the operation was packed into
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct BatchResponse {
+    pub results: Vec<BatchOperationResult>,
+    pub transactions: Vec<TransactionReceipt>,
+}
+
+// One outdated todo account and its migration to the current layout
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct TodoMigration {
+    pub task_id: u64,
+    pub todo_account: String,
+    pub layout_version: u8, // the account's layout after this request
+    // Custodial wallets: the migration that was sent
+    pub transaction: Option<TransactionReceipt>,
+    // Other wallets: the migration to sign and submit to /api/transactions/submit
+    pub unsigned_transaction: Option<UnsignedTransactionResponse>,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct MigrationResponse {
+    pub layout_version: u8, // the layout the program creates accounts with now
+    pub migrations: Vec<TodoMigration>,
+}
@@ -0,0 +1,79 @@
+// src/models/transaction.rs
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct UnsignedTransactionResponse {
+    pub transaction: String, // base64 bincode-serialized, unsigned
+    pub todo_account: String,
+    pub recent_blockhash: String,
+    pub last_valid_block_height: u64,
+    pub compute_unit_limit: u32,
+    pub compute_unit_price: u64, // micro-lamports per compute unit
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct SubmitTransactionRequest {
+    pub transaction: String, // base64 bincode-serialized, signed by the wallet
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct SubmitTransactionResponse {
+    #[serde(flatten)]
+    pub receipt: TransactionReceipt,
+}
+
+// What a landed transaction cost
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct TransactionReceipt {
+    pub signature: String,
+    pub fee_lamports: Option<u64>, // None if the fee couldn't be looked up
+    pub slot: Option<u64>,
+    pub commitment: Option<String>, // processed, confirmed or finalized
+    pub compute_unit_limit: Option<u32>,
+    pub compute_unit_price: Option<u64>, // micro-lamports per compute unit
+}
+
+// One try at sending a transaction, reported when every try failed
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SendAttempt {
+    pub attempt: u32,
+    pub signature: String,
+    pub recent_blockhash: String,
+    pub failure: String, // blockhash_expired, transient or permanent
+    pub error: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct TransactionStatusResponse {
+    pub signature: String,
+    pub status: String, // processed, confirmed, finalized or failed
+    pub slot: u64,
+    pub confirmations: Option<usize>, // None once finalized
+    pub fee_lamports: Option<u64>,
+    pub error: Option<String>,
+    pub program_error: Option<ProgramErrorDetails>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct ProgramErrorDetails {
+    pub instruction_index: u8,
+    pub code: Option<u32>, // for custom program errors
+    pub name: Option<String>, // from the program's IDL
+    pub message: String,
+}
+
+// What a write would do, from simulating it against current state
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct SimulationResponse {
+    pub success: bool,
+    pub logs: Vec<String>,
+    pub units_consumed: Option<u64>,
+    pub fee_lamports: u64,
+    // Lamports deposited into the todo account, None if the simulation failed
+    pub rent_lamports: Option<u64>,
+    pub compute_unit_limit: u32,
+    pub compute_unit_price: u64, // micro-lamports per compute unit
+    pub error: Option<String>,
+    pub program_error: Option<ProgramErrorDetails>,
+}
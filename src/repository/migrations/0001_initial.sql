@@ -0,0 +1,51 @@
+-- The indexer's copy of the program's todo accounts
+CREATE TABLE todos (
+    address TEXT PRIMARY KEY,
+    owner TEXT NOT NULL,
+    task_id INTEGER NOT NULL,
+    description TEXT NOT NULL,
+    completed INTEGER NOT NULL,
+    due_date INTEGER NOT NULL,
+    layout_version INTEGER NOT NULL,
+    slot INTEGER NOT NULL
+);
+
+CREATE INDEX todos_owner ON todos (owner, task_id);
+
+CREATE TABLE todo_metadata (
+    owner TEXT NOT NULL,
+    task_id INTEGER NOT NULL,
+    metadata TEXT NOT NULL,
+    updated_at INTEGER NOT NULL,
+    PRIMARY KEY (owner, task_id)
+);
+
+-- Jobs as their JSON response, with the columns they're looked up by
+CREATE TABLE jobs (
+    id TEXT PRIMARY KEY,
+    wallet TEXT NOT NULL,
+    status TEXT NOT NULL,
+    updated_at INTEGER NOT NULL,
+    record TEXT NOT NULL
+);
+
+CREATE INDEX jobs_status ON jobs (status, updated_at);
+
+-- The relayer's budget ledger: each wallet's usage on the last day it was sponsored
+CREATE TABLE sponsorship_usage (
+    wallet TEXT PRIMARY KEY,
+    day INTEGER NOT NULL,
+    transactions INTEGER NOT NULL,
+    lamports INTEGER NOT NULL
+);
+
+-- API keys for machine clients; only a hash of each secret is stored
+CREATE TABLE api_keys (
+    id TEXT PRIMARY KEY,
+    name TEXT NOT NULL,
+    wallet TEXT NOT NULL,
+    scopes TEXT NOT NULL,
+    secret_hash TEXT NOT NULL,
+    created_at INTEGER NOT NULL,
+    last_used_at INTEGER
+);
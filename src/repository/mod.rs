@@ -0,0 +1,124 @@
+// src/repository/mod.rs
+pub mod sqlite;
+
+use crate::auth::api_keys::ApiKeyRecord;
+use crate::config::Config;
+use crate::errors::ApiError;
+use crate::models::job::JobResponse;
+use crate::solana::TodoAccount;
+use serde_json::{Map, Value};
+use solana_sdk::pubkey::Pubkey;
+use std::sync::mpsc;
+use std::sync::Arc;
+use std::thread;
+
+// A todo account as the indexer last saw it
+#[derive(Debug, Clone)]
+pub struct StoredTodo {
+    pub address: Pubkey,
+    pub slot: u64,
+    pub todo: TodoAccount,
+}
+
+// A wallet's sponsored usage on one day (unix days)
+#[derive(Debug, Clone, PartialEq)]
+pub struct SponsorshipUsage {
+    pub wallet: String,
+    pub day: u64,
+    pub transactions: u32,
+    pub lamports: u64,
+}
+
+// Local persistence for state that isn't on-chain, or is a copy of what is.
+// Calls block on the database, so async code goes through `run_blocking` for
+// reads and a `RepositoryWriter` for writes.
+pub trait TodoRepository: Send + Sync {
+    // Replace the indexer's whole copy of the program's todo accounts
+    fn replace_todos(&self, todos: &[StoredTodo]) -> Result<(), ApiError>;
+
+    // Apply the difference a full scan found, in one transaction
+    fn sync_todos(&self, changed: &[StoredTodo], removed: &[Pubkey]) -> Result<(), ApiError>;
+
+    // Keeps the stored copy if it was seen at a later slot
+    fn upsert_todo(&self, todo: &StoredTodo) -> Result<(), ApiError>;
+
+    fn remove_todo(&self, address: &Pubkey) -> Result<(), ApiError>;
+
+    fn todos(&self) -> Result<Vec<StoredTodo>, ApiError>;
+
+    // Off-chain metadata kept alongside a todo, as a JSON object
+    fn set_metadata(&self, owner: &Pubkey, task_id: u64, metadata: &Map<String, Value>) -> Result<(), ApiError>;
+
+    fn metadata(&self, owner: &Pubkey, task_id: u64) -> Result<Option<Map<String, Value>>, ApiError>;
+
+    fn delete_metadata(&self, owner: &Pubkey, task_id: u64) -> Result<(), ApiError>;
+
+    fn save_job(&self, wallet: &str, job: &JobResponse) -> Result<(), ApiError>;
+
+    // Only the wallet that queued a job can load it
+    fn job(&self, wallet: &str, id: &str) -> Result<Option<JobResponse>, ApiError>;
+
+    // Drop finished jobs last updated before `updated_before` (unix seconds)
+    fn prune_jobs(&self, updated_before: i64) -> Result<usize, ApiError>;
+
+    // Fail jobs still queued or processing, which a previous run will never finish
+    fn fail_unfinished_jobs(&self, error: &str) -> Result<usize, ApiError>;
+
+    // Every wallet's usage on `day`
+    fn sponsorship_usage(&self, day: u64) -> Result<Vec<SponsorshipUsage>, ApiError>;
+
+    // Replaces the wallet's usage, including that of an earlier day
+    fn save_sponsorship_usage(&self, usage: &SponsorshipUsage) -> Result<(), ApiError>;
+
+    // API key records, holding only the hash of each secret
+    fn api_keys(&self) -> Result<Vec<ApiKeyRecord>, ApiError>;
+
+    fn save_api_key(&self, key: &ApiKeyRecord) -> Result<(), ApiError>;
+
+    fn touch_api_key(&self, id: &str, last_used_at: u64) -> Result<(), ApiError>;
+
+    fn delete_api_key(&self, id: &str) -> Result<(), ApiError>;
+}
+
+// Run repository reads off the async workers
+pub async fn run_blocking<T, F>(repository: &Arc<dyn TodoRepository>, f: F) -> Result<T, ApiError>
+where
+    F: FnOnce(&dyn TodoRepository) -> Result<T, ApiError> + Send + 'static,
+    T: Send + 'static,
+{
+    let repository = repository.clone();
+    tokio::task::spawn_blocking(move || f(repository.as_ref()))
+        .await
+        .map_err(|e| ApiError::InternalServerError(format!("Repository task failed: {}", e)))?
+}
+
+type Write = Box<dyn FnOnce(&dyn TodoRepository) + Send>;
+
+// Writes applied in order on a thread of their own, so the caller never waits on
+// the database or holds its own locks across I/O. Failures are the caller's to
+// handle inside the write; the thread ends once the writer is dropped.
+pub struct RepositoryWriter {
+    sender: mpsc::Sender<Write>,
+}
+
+impl RepositoryWriter {
+    pub fn spawn(repository: Arc<dyn TodoRepository>) -> Self {
+        let (sender, receiver) = mpsc::channel::<Write>();
+        thread::spawn(move || {
+            for write in receiver {
+                write(repository.as_ref());
+            }
+        });
+
+        Self { sender }
+    }
+
+    pub fn write(&self, write: impl FnOnce(&dyn TodoRepository) + Send + 'static) {
+        // The thread only stops when the sender is dropped, so this can't fail
+        let _ = self.sender.send(Box::new(write));
+    }
+}
+
+pub fn from_config(config: &Config) -> Result<Arc<dyn TodoRepository>, ApiError> {
+    Ok(Arc::new(sqlite::SqliteRepository::open(&config.database_path)?))
+}
@@ -0,0 +1,363 @@
+// src/repository/sqlite.rs
+use super::{SponsorshipUsage, StoredTodo, TodoRepository};
+use crate::auth::api_keys::ApiKeyRecord;
+use crate::errors::ApiError;
+use crate::models::job::{JobResponse, JobStatus};
+use crate::solana::TodoAccount;
+use chrono::Utc;
+use rusqlite::{params, Connection, OptionalExtension, Row};
+use serde_json::{Map, Value};
+use solana_sdk::pubkey::Pubkey;
+use std::str::FromStr;
+use std::sync::{Mutex, MutexGuard};
+
+// Schema changes in order. The database's `user_version` is the number applied,
+// so add new files at the end and never edit one that has shipped.
+const MIGRATIONS: &[&str] = &[
+    include_str!("migrations/0001_initial.sql"),
+];
+
+const TODO_COLUMNS: &str = "address, owner, task_id, description, completed, due_date, layout_version, slot";
+
+const API_KEY_COLUMNS: &str = "id, name, wallet, scopes, secret_hash, created_at, last_used_at";
+
+// A single SQLite connection behind a mutex. Calls block, so async callers run
+// them through `run_blocking` or a `RepositoryWriter`.
+pub struct SqliteRepository {
+    connection: Mutex<Connection>,
+}
+
+impl SqliteRepository {
+    // Open or create the database at `path` (`:memory:` for a private in-memory one)
+    // and bring its schema up to date
+    pub fn open(path: &str) -> Result<Self, ApiError> {
+        let mut connection = Connection::open(path)
+            .map_err(|e| ApiError::InternalServerError(format!("Failed to open database {}: {}", path, e)))?;
+        connection.execute_batch("PRAGMA journal_mode = WAL; PRAGMA busy_timeout = 5000;")?;
+        migrate(&mut connection)?;
+
+        Ok(Self {
+            connection: Mutex::new(connection),
+        })
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Connection> {
+        // SQLite rolls back anything a panicking holder left unfinished
+        self.connection.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+// Apply the migrations the database hasn't had yet, each in its own transaction
+fn migrate(connection: &mut Connection) -> Result<(), ApiError> {
+    let applied: usize = connection.query_row("PRAGMA user_version", [], |row| row.get(0))?;
+    if applied > MIGRATIONS.len() {
+        return Err(ApiError::InternalServerError(format!(
+            "Database schema version {} is newer than this build supports ({})",
+            applied,
+            MIGRATIONS.len()
+        )));
+    }
+
+    for (index, migration) in MIGRATIONS.iter().enumerate().skip(applied) {
+        let transaction = connection.transaction()?;
+        transaction.execute_batch(migration)?;
+        transaction.pragma_update(None, "user_version", index + 1)?;
+        transaction.commit()?;
+        log::info!("Applied database migration {}", index + 1);
+    }
+
+    Ok(())
+}
+
+fn status_name(status: JobStatus) -> &'static str {
+    match status {
+        JobStatus::Queued => "queued",
+        JobStatus::Processing => "processing",
+        JobStatus::Confirmed => "confirmed",
+        JobStatus::Failed => "failed",
+    }
+}
+
+fn insert_todo(connection: &Connection, stored: &StoredTodo) -> rusqlite::Result<usize> {
+    connection.execute(
+        &format!(
+            "INSERT INTO todos ({}) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)
+             ON CONFLICT (address) DO UPDATE SET
+                 owner = excluded.owner, task_id = excluded.task_id, description = excluded.description,
+                 completed = excluded.completed, due_date = excluded.due_date,
+                 layout_version = excluded.layout_version, slot = excluded.slot
+             WHERE excluded.slot >= todos.slot",
+            TODO_COLUMNS
+        ),
+        params![
+            stored.address.to_string(),
+            stored.todo.owner.to_string(),
+            stored.todo.task_id,
+            stored.todo.description,
+            stored.todo.completed,
+            stored.todo.due_date,
+            stored.todo.layout_version,
+            stored.slot,
+        ],
+    )
+}
+
+fn stored_todo(row: &Row<'_>) -> rusqlite::Result<StoredTodo> {
+    let pubkey = |index: usize| -> rusqlite::Result<Pubkey> {
+        let value: String = row.get(index)?;
+        Pubkey::from_str(&value).map_err(|e| {
+            rusqlite::Error::FromSqlConversionFailure(index, rusqlite::types::Type::Text, Box::new(e))
+        })
+    };
+
+    Ok(StoredTodo {
+        address: pubkey(0)?,
+        slot: row.get(7)?,
+        todo: TodoAccount {
+            owner: pubkey(1)?,
+            task_id: row.get(2)?,
+            description: row.get(3)?,
+            completed: row.get(4)?,
+            due_date: row.get(5)?,
+            layout_version: row.get(6)?,
+        },
+    })
+}
+
+fn api_key(row: &Row<'_>) -> rusqlite::Result<ApiKeyRecord> {
+    let scopes: String = row.get(3)?;
+    let scopes = serde_json::from_str(&scopes).map_err(|e| {
+        rusqlite::Error::FromSqlConversionFailure(3, rusqlite::types::Type::Text, Box::new(e))
+    })?;
+
+    Ok(ApiKeyRecord {
+        id: row.get(0)?,
+        name: row.get(1)?,
+        wallet: row.get(2)?,
+        scopes,
+        secret_hash: row.get(4)?,
+        created_at: row.get(5)?,
+        last_used_at: row.get(6)?,
+    })
+}
+
+fn to_json<T: serde::Serialize>(value: &T) -> Result<String, ApiError> {
+    serde_json::to_string(value)
+        .map_err(|e| ApiError::InternalServerError(format!("Failed to encode record: {}", e)))
+}
+
+fn from_json<T: serde::de::DeserializeOwned>(json: &str) -> Result<T, ApiError> {
+    serde_json::from_str(json)
+        .map_err(|e| ApiError::InternalServerError(format!("Invalid stored record: {}", e)))
+}
+
+impl TodoRepository for SqliteRepository {
+    fn replace_todos(&self, todos: &[StoredTodo]) -> Result<(), ApiError> {
+        let mut connection = self.lock();
+        let transaction = connection.transaction()?;
+
+        transaction.execute("DELETE FROM todos", [])?;
+        for stored in todos {
+            insert_todo(&transaction, stored)?;
+        }
+
+        transaction.commit()?;
+        Ok(())
+    }
+
+    fn sync_todos(&self, changed: &[StoredTodo], removed: &[Pubkey]) -> Result<(), ApiError> {
+        let mut connection = self.lock();
+        let transaction = connection.transaction()?;
+
+        for stored in changed {
+            insert_todo(&transaction, stored)?;
+        }
+        for address in removed {
+            transaction.execute("DELETE FROM todos WHERE address = ?1", params![address.to_string()])?;
+        }
+
+        transaction.commit()?;
+        Ok(())
+    }
+
+    fn upsert_todo(&self, todo: &StoredTodo) -> Result<(), ApiError> {
+        insert_todo(&self.lock(), todo)?;
+        Ok(())
+    }
+
+    fn remove_todo(&self, address: &Pubkey) -> Result<(), ApiError> {
+        self.lock()
+            .execute("DELETE FROM todos WHERE address = ?1", params![address.to_string()])?;
+        Ok(())
+    }
+
+    fn todos(&self) -> Result<Vec<StoredTodo>, ApiError> {
+        let connection = self.lock();
+        let mut statement = connection.prepare(&format!("SELECT {} FROM todos", TODO_COLUMNS))?;
+        let todos = statement
+            .query_map([], stored_todo)?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+
+        Ok(todos)
+    }
+
+    fn set_metadata(&self, owner: &Pubkey, task_id: u64, metadata: &Map<String, Value>) -> Result<(), ApiError> {
+        self.lock().execute(
+            "INSERT INTO todo_metadata (owner, task_id, metadata, updated_at) VALUES (?1, ?2, ?3, ?4)
+             ON CONFLICT (owner, task_id) DO UPDATE SET
+                 metadata = excluded.metadata, updated_at = excluded.updated_at",
+            params![owner.to_string(), task_id, to_json(metadata)?, Utc::now().timestamp()],
+        )?;
+        Ok(())
+    }
+
+    fn metadata(&self, owner: &Pubkey, task_id: u64) -> Result<Option<Map<String, Value>>, ApiError> {
+        let json: Option<String> = self
+            .lock()
+            .query_row(
+                "SELECT metadata FROM todo_metadata WHERE owner = ?1 AND task_id = ?2",
+                params![owner.to_string(), task_id],
+                |row| row.get(0),
+            )
+            .optional()?;
+
+        json.as_deref().map(from_json).transpose()
+    }
+
+    fn delete_metadata(&self, owner: &Pubkey, task_id: u64) -> Result<(), ApiError> {
+        self.lock().execute(
+            "DELETE FROM todo_metadata WHERE owner = ?1 AND task_id = ?2",
+            params![owner.to_string(), task_id],
+        )?;
+        Ok(())
+    }
+
+    fn save_job(&self, wallet: &str, job: &JobResponse) -> Result<(), ApiError> {
+        self.lock().execute(
+            "INSERT INTO jobs (id, wallet, status, updated_at, record) VALUES (?1, ?2, ?3, ?4, ?5)
+             ON CONFLICT (id) DO UPDATE SET
+                 status = excluded.status, updated_at = excluded.updated_at, record = excluded.record",
+            params![job.id, wallet, status_name(job.status), job.updated_at, to_json(job)?],
+        )?;
+        Ok(())
+    }
+
+    fn job(&self, wallet: &str, id: &str) -> Result<Option<JobResponse>, ApiError> {
+        let json: Option<String> = self
+            .lock()
+            .query_row(
+                "SELECT record FROM jobs WHERE id = ?1 AND wallet = ?2",
+                params![id, wallet],
+                |row| row.get(0),
+            )
+            .optional()?;
+
+        json.as_deref().map(from_json).transpose()
+    }
+
+    fn prune_jobs(&self, updated_before: i64) -> Result<usize, ApiError> {
+        let pruned = self.lock().execute(
+            "DELETE FROM jobs WHERE status IN ('confirmed', 'failed') AND updated_at < ?1",
+            params![updated_before],
+        )?;
+        Ok(pruned)
+    }
+
+    fn fail_unfinished_jobs(&self, error: &str) -> Result<usize, ApiError> {
+        let mut connection = self.lock();
+        let transaction = connection.transaction()?;
+
+        let unfinished = transaction
+            .prepare("SELECT record FROM jobs WHERE status IN ('queued', 'processing')")?
+            .query_map([], |row| row.get::<_, String>(0))?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+
+        let now = Utc::now().timestamp();
+        for record in &unfinished {
+            let mut job: JobResponse = from_json(record)?;
+            job.status = JobStatus::Failed;
+            job.error = Some(error.to_string());
+            job.updated_at = now;
+            transaction.execute(
+                "UPDATE jobs SET status = ?2, updated_at = ?3, record = ?4 WHERE id = ?1",
+                params![job.id, status_name(job.status), job.updated_at, to_json(&job)?],
+            )?;
+        }
+
+        transaction.commit()?;
+        Ok(unfinished.len())
+    }
+
+    fn sponsorship_usage(&self, day: u64) -> Result<Vec<SponsorshipUsage>, ApiError> {
+        let connection = self.lock();
+        let mut statement = connection
+            .prepare("SELECT wallet, day, transactions, lamports FROM sponsorship_usage WHERE day = ?1")?;
+        let usage = statement
+            .query_map(params![day], |row| {
+                Ok(SponsorshipUsage {
+                    wallet: row.get(0)?,
+                    day: row.get(1)?,
+                    transactions: row.get(2)?,
+                    lamports: row.get(3)?,
+                })
+            })?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+
+        Ok(usage)
+    }
+
+    fn save_sponsorship_usage(&self, usage: &SponsorshipUsage) -> Result<(), ApiError> {
+        self.lock().execute(
+            "INSERT INTO sponsorship_usage (wallet, day, transactions, lamports) VALUES (?1, ?2, ?3, ?4)
+             ON CONFLICT (wallet) DO UPDATE SET
+                 day = excluded.day, transactions = excluded.transactions, lamports = excluded.lamports",
+            params![usage.wallet, usage.day, usage.transactions, usage.lamports],
+        )?;
+        Ok(())
+    }
+
+    fn api_keys(&self) -> Result<Vec<ApiKeyRecord>, ApiError> {
+        let connection = self.lock();
+        let mut statement = connection.prepare(&format!("SELECT {} FROM api_keys", API_KEY_COLUMNS))?;
+        let keys = statement
+            .query_map([], api_key)?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+
+        Ok(keys)
+    }
+
+    fn save_api_key(&self, key: &ApiKeyRecord) -> Result<(), ApiError> {
+        self.lock().execute(
+            &format!(
+                "INSERT INTO api_keys ({}) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)
+                 ON CONFLICT (id) DO UPDATE SET
+                     name = excluded.name, scopes = excluded.scopes, last_used_at = excluded.last_used_at",
+                API_KEY_COLUMNS
+            ),
+            params![
+                key.id,
+                key.name,
+                key.wallet,
+                to_json(&key.scopes)?,
+                key.secret_hash,
+                key.created_at,
+                key.last_used_at,
+            ],
+        )?;
+        Ok(())
+    }
+
+    fn touch_api_key(&self, id: &str, last_used_at: u64) -> Result<(), ApiError> {
+        self.lock().execute(
+            "UPDATE api_keys SET last_used_at = ?2 WHERE id = ?1",
+            params![id, last_used_at],
+        )?;
+        Ok(())
+    }
+
+    fn delete_api_key(&self, id: &str) -> Result<(), ApiError> {
+        self.lock()
+            .execute("DELETE FROM api_keys WHERE id = ?1", params![id])?;
+        Ok(())
+    }
+}
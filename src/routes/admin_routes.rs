@@ -0,0 +1,15 @@
+// src/routes/admin_routes.rs
+use crate::auth::scopes;
+use crate::handlers::rpc;
+use crate::middleware::auth::AuthMiddleware;
+use actix_web::web;
+
+pub fn config(cfg: &mut web::ServiceConfig) {
+    let auth = AuthMiddleware::new().require_role(scopes::ADMIN_ROLE);
+    
+    cfg.service(
+        web::scope("/admin")
+            .wrap(auth)
+            .route("/rpc", web::get().to(rpc::get_rpc_metrics))
+    );
+}
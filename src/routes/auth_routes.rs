@@ -1,10 +1,43 @@
 // src/routes/auth_routes.rs
+use crate::auth::scopes;
 use crate::handlers::auth;
+use crate::middleware::auth::AuthMiddleware;
 use actix_web::web;
 
 pub fn config(cfg: &mut web::ServiceConfig) {
     cfg.service(
         web::scope("/auth")
+            .route("/challenge", web::get().to(auth::challenge))
             .route("/login", web::post().to(auth::authenticate))
+            .route("/refresh", web::post().to(auth::refresh))
+            // Any wallet token can end its own sessions, whatever scopes it was narrowed
+            // to. API keys have no session, and mustn't end the wallet's.
+            .service(
+                web::resource("/logout")
+                    .wrap(AuthMiddleware::new().require_wallet_session())
+                    .route(web::post().to(auth::logout))
+            )
+            .service(
+                web::resource("/logout-all")
+                    .wrap(AuthMiddleware::new().require_wallet_session())
+                    .route(web::post().to(auth::logout_all))
+            )
+            .service(
+                web::scope("/api-keys")
+                    .wrap(account_manage())
+                    .route("", web::post().to(auth::create_api_key))
+                    .route("", web::get().to(auth::list_api_keys))
+                    .route("/{id}", web::delete().to(auth::revoke_api_key))
+            )
     );
 }
+
+// API keys can't manage credentials, only wallet sign-ins can
+fn account_manage() -> AuthMiddleware {
+    AuthMiddleware::new().require_scope(scopes::ACCOUNT_MANAGE)
+}
+
+// Served at the root rather than under /api
+pub fn well_known(cfg: &mut web::ServiceConfig) {
+    cfg.route("/.well-known/jwks.json", web::get().to(auth::jwks));
+}
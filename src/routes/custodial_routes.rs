@@ -0,0 +1,19 @@
+// src/routes/custodial_routes.rs
+use crate::auth::scopes;
+use crate::handlers::custodial;
+use crate::middleware::auth::AuthMiddleware;
+use actix_web::web;
+
+pub fn config(cfg: &mut web::ServiceConfig) {
+    // Custody changes are account management, so API keys can't make them
+    let auth = AuthMiddleware::new().require_scope(scopes::ACCOUNT_MANAGE);
+    
+    cfg.service(
+        web::scope("/custodial/wallet")
+            .wrap(auth)
+            .route("", web::post().to(custodial::create_wallet))
+            .route("", web::get().to(custodial::get_wallet))
+            .route("", web::delete().to(custodial::delete_wallet))
+            .route("/export", web::post().to(custodial::export_wallet))
+    );
+}
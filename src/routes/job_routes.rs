@@ -0,0 +1,15 @@
+// src/routes/job_routes.rs
+use crate::auth::scopes;
+use crate::handlers::job;
+use crate::middleware::auth::AuthMiddleware;
+use actix_web::web;
+
+pub fn config(cfg: &mut web::ServiceConfig) {
+    let auth = AuthMiddleware::new().require_scope(scopes::TODOS_READ);
+    
+    cfg.service(
+        web::resource("/jobs/{id}")
+            .wrap(auth)
+            .route(web::get().to(job::get_job))
+    );
+}
@@ -1,4 +1,9 @@
 // src/routes/mod.rs
 pub mod todo_routes;
 pub mod auth_routes;
+pub mod custodial_routes;
+pub mod transaction_routes;
+pub mod sponsorship_routes;
+pub mod admin_routes;
+pub mod job_routes;
 pub mod swagger;
\ No newline at end of file
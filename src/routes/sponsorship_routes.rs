@@ -0,0 +1,15 @@
+// src/routes/sponsorship_routes.rs
+use crate::auth::scopes;
+use crate::handlers::sponsorship;
+use crate::middleware::auth::AuthMiddleware;
+use actix_web::web;
+
+pub fn config(cfg: &mut web::ServiceConfig) {
+    let auth = AuthMiddleware::new().require_scope(scopes::TODOS_READ);
+    
+    cfg.service(
+        web::resource("/sponsorship")
+            .wrap(auth)
+            .route(web::get().to(sponsorship::get_sponsorship))
+    );
+}
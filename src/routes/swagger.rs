@@ -1,8 +1,6 @@
 // src/routes/swagger.rs
-use actix_web::web;
-use actix_web_swagger_ui::SwaggerUi;
 use utoipa::OpenApi;
-use utoipa_swagger_ui::Config;
+use utoipa_swagger_ui::{Config, SwaggerUi};
 
 #[derive(OpenApi)]
 #[openapi(
@@ -11,20 +9,73 @@ use utoipa_swagger_ui::Config;
         crate::handlers::todo::get_todos,
         crate::handlers::todo::update_todo,
         crate::handlers::todo::delete_todo,
+        crate::handlers::todo::batch_todos,
+        crate::handlers::todo::migrate_todos,
+        crate::handlers::todo::get_metadata,
+        crate::handlers::todo::set_metadata,
+        crate::handlers::todo::delete_metadata,
+        crate::handlers::transaction::build_create_todo,
+        crate::handlers::transaction::build_update_todo,
+        crate::handlers::transaction::build_delete_todo,
+        crate::handlers::transaction::submit_transaction,
+        crate::handlers::transaction::get_transaction_status,
+        crate::handlers::sponsorship::get_sponsorship,
+        crate::handlers::rpc::get_rpc_metrics,
+        crate::handlers::job::get_job,
+        crate::handlers::custodial::create_wallet,
+        crate::handlers::custodial::get_wallet,
+        crate::handlers::custodial::export_wallet,
+        crate::handlers::custodial::delete_wallet,
+        crate::handlers::auth::challenge,
         crate::handlers::auth::authenticate,
+        crate::handlers::auth::refresh,
+        crate::handlers::auth::logout,
+        crate::handlers::auth::logout_all,
+        crate::handlers::auth::create_api_key,
+        crate::handlers::auth::list_api_keys,
+        crate::handlers::auth::revoke_api_key,
+        crate::handlers::auth::jwks,
     ),
     components(
         schemas(
             crate::models::todo::CreateTodoRequest,
             crate::models::todo::UpdateTodoRequest,
             crate::models::todo::TodoResponse,
+            crate::models::todo::BatchRequest,
+            crate::models::todo::BatchOperationResult,
+            crate::models::todo::BatchResponse,
+            crate::models::todo::TodoMigration,
+            crate::models::todo::MigrationResponse,
+            crate::models::transaction::TransactionReceipt,
+            crate::models::transaction::UnsignedTransactionResponse,
+            crate::models::transaction::SubmitTransactionRequest,
+            crate::models::transaction::SubmitTransactionResponse,
+            crate::models::transaction::TransactionStatusResponse,
+            crate::models::transaction::ProgramErrorDetails,
+            crate::models::transaction::SimulationResponse,
+            crate::models::sponsorship::SponsorshipResponse,
+            crate::models::rpc::RpcEndpointMetrics,
+            crate::models::job::JobStatus,
+            crate::models::job::JobResponse,
+            crate::models::custodial::CustodialWalletResponse,
+            crate::models::custodial::CustodialExportResponse,
             crate::models::auth::WalletAuth,
             crate::models::auth::TokenResponse,
+            crate::models::auth::ChallengeResponse,
+            crate::models::auth::RefreshRequest,
+            crate::models::auth::CreateApiKeyRequest,
+            crate::models::auth::ApiKeyResponse,
+            crate::models::auth::CreatedApiKeyResponse,
             crate::errors::ErrorResponse,
         )
     ),
     tags(
         (name = "todos", description = "Todo management endpoints"),
+        (name = "transactions", description = "Client-signed transaction endpoints"),
+        (name = "sponsorship", description = "Fee sponsorship endpoints"),
+        (name = "jobs", description = "Asynchronous write endpoints"),
+        (name = "admin", description = "Operator endpoints"),
+        (name = "custodial", description = "Custodial wallet endpoints"),
         (name = "auth", description = "Authentication endpoints"),
     ),
     info(
@@ -1,17 +1,29 @@
 // src/routes/todo_routes.rs
+use crate::auth::scopes;
 use crate::handlers::todo;
 use crate::middleware::auth::AuthMiddleware;
+use actix_web::http::Method;
 use actix_web::web;
 
 pub fn config(cfg: &mut web::ServiceConfig) {
-    let auth = AuthMiddleware::new(crate::config::load_config());
+    // Read-only tokens may list todos but not change them
+    let auth = AuthMiddleware::new()
+        .require_method_scope(Method::GET, scopes::TODOS_READ)
+        .require_method_scope(Method::POST, scopes::TODOS_WRITE)
+        .require_method_scope(Method::PUT, scopes::TODOS_WRITE)
+        .require_method_scope(Method::DELETE, scopes::TODOS_WRITE);
     
     cfg.service(
         web::scope("/todos")
             .wrap(auth)
             .route("", web::post().to(todo::create_todo))
             .route("", web::get().to(todo::get_todos))
+            .route("/batch", web::post().to(todo::batch_todos))
+            .route("/migrate", web::post().to(todo::migrate_todos))
             .route("/{id}", web::put().to(todo::update_todo))
             .route("/{id}", web::delete().to(todo::delete_todo))
+            .route("/{id}/metadata", web::get().to(todo::get_metadata))
+            .route("/{id}/metadata", web::put().to(todo::set_metadata))
+            .route("/{id}/metadata", web::delete().to(todo::delete_metadata))
     );
 }
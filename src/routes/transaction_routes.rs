@@ -0,0 +1,24 @@
+// src/routes/transaction_routes.rs
+use crate::auth::scopes;
+use crate::handlers::transaction;
+use crate::middleware::auth::AuthMiddleware;
+use actix_web::http::Method;
+use actix_web::web;
+
+pub fn config(cfg: &mut web::ServiceConfig) {
+    let auth = AuthMiddleware::new()
+        .require_method_scope(Method::GET, scopes::TODOS_READ)
+        .require_method_scope(Method::POST, scopes::TODOS_WRITE)
+        .require_method_scope(Method::PUT, scopes::TODOS_WRITE)
+        .require_method_scope(Method::DELETE, scopes::TODOS_WRITE);
+    
+    cfg.service(
+        web::scope("/transactions")
+            .wrap(auth)
+            .route("/todos", web::post().to(transaction::build_create_todo))
+            .route("/todos/{id}", web::put().to(transaction::build_update_todo))
+            .route("/todos/{id}", web::delete().to(transaction::build_delete_todo))
+            .route("/submit", web::post().to(transaction::submit_transaction))
+            .route("/{signature}", web::get().to(transaction::get_transaction_status))
+    );
+}
@@ -0,0 +1,35 @@
+// src/signer/disabled.rs
+use crate::errors::ApiError;
+use crate::signer::{no_custodial_wallet, TransactionSigner};
+use async_trait::async_trait;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signature;
+
+// Custodial mode turned off. No wallet has a custodial key, so todo endpoints
+// fall back to the client-signed flow and creating one is refused.
+pub struct DisabledSigner;
+
+#[async_trait]
+impl TransactionSigner for DisabledSigner {
+    async fn create(&self, _wallet: &str) -> Result<Pubkey, ApiError> {
+        Err(ApiError::ServiceUnavailable(
+            "Custodial wallets are disabled; set SIGNER_BACKEND to enable them".to_string(),
+        ))
+    }
+
+    async fn pubkey(&self, wallet: &str) -> Result<Pubkey, ApiError> {
+        Err(no_custodial_wallet(wallet))
+    }
+
+    async fn sign_message(&self, wallet: &str, _message: &[u8]) -> Result<Signature, ApiError> {
+        Err(no_custodial_wallet(wallet))
+    }
+
+    async fn export(&self, wallet: &str) -> Result<String, ApiError> {
+        Err(no_custodial_wallet(wallet))
+    }
+
+    async fn delete(&self, wallet: &str) -> Result<(), ApiError> {
+        Err(no_custodial_wallet(wallet))
+    }
+}
@@ -0,0 +1,84 @@
+// src/signer/file.rs
+use crate::errors::ApiError;
+use crate::signer::{
+    checked_wallet, create_key_file, delete_key_file, no_custodial_wallet, run_blocking, TransactionSigner,
+};
+use async_trait::async_trait;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::{read_keypair_file, write_keypair, Keypair, Signature, Signer};
+use std::fs;
+use std::path::PathBuf;
+
+// Unencrypted keypair files in the Solana CLI format, one per wallet.
+// Meant for local development; use the keystore backend in production.
+pub struct FileSigner {
+    dir: PathBuf,
+}
+
+impl FileSigner {
+    pub fn new(dir: PathBuf) -> Result<Self, ApiError> {
+        fs::create_dir_all(&dir)?;
+        Ok(Self { dir })
+    }
+
+    fn path(&self, wallet: &str) -> Result<PathBuf, ApiError> {
+        let wallet = checked_wallet(wallet)?;
+        Ok(self.dir.join(format!("{}.json", wallet)))
+    }
+
+    async fn load(&self, wallet: &str) -> Result<Keypair, ApiError> {
+        let path = self.path(wallet)?;
+        let wallet = wallet.to_string();
+
+        run_blocking(move || {
+            if !path.exists() {
+                return Err(no_custodial_wallet(&wallet));
+            }
+
+            read_keypair_file(&path)
+                .map_err(|e| ApiError::InternalServerError(format!("Failed to read keypair: {}", e)))
+        })
+        .await
+    }
+}
+
+#[async_trait]
+impl TransactionSigner for FileSigner {
+    async fn create(&self, wallet: &str) -> Result<Pubkey, ApiError> {
+        let path = self.path(wallet)?;
+        let wallet = wallet.to_string();
+
+        run_blocking(move || {
+            let mut key_file = create_key_file(&path, &wallet)?;
+
+            let keypair = Keypair::new();
+            if let Err(e) = write_keypair(&keypair, &mut key_file) {
+                // Don't leave an empty file claiming the wallet
+                let _ = fs::remove_file(&path);
+                return Err(ApiError::InternalServerError(format!("Failed to write keypair: {}", e)));
+            }
+
+            Ok(keypair.pubkey())
+        })
+        .await
+    }
+
+    async fn pubkey(&self, wallet: &str) -> Result<Pubkey, ApiError> {
+        Ok(self.load(wallet).await?.pubkey())
+    }
+
+    async fn sign_message(&self, wallet: &str, message: &[u8]) -> Result<Signature, ApiError> {
+        Ok(self.load(wallet).await?.sign_message(message))
+    }
+
+    async fn export(&self, wallet: &str) -> Result<String, ApiError> {
+        Ok(self.load(wallet).await?.to_base58_string())
+    }
+
+    async fn delete(&self, wallet: &str) -> Result<(), ApiError> {
+        let path = self.path(wallet)?;
+        let wallet = wallet.to_string();
+
+        run_blocking(move || delete_key_file(&path, &wallet)).await
+    }
+}
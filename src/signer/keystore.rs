@@ -0,0 +1,187 @@
+// src/signer/keystore.rs
+use crate::errors::ApiError;
+use crate::signer::{
+    checked_wallet, create_key_file, delete_key_file, no_custodial_wallet, run_blocking, TransactionSigner,
+};
+use argon2::Argon2;
+use async_trait::async_trait;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use chacha20poly1305::aead::{Aead, NewAead};
+use chacha20poly1305::{Key, XChaCha20Poly1305, XNonce};
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::{Keypair, Signature, Signer};
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::sync::Arc;
+
+const KEYSTORE_VERSION: u8 = 1;
+const SALT_BYTES: usize = 16;
+const NONCE_BYTES: usize = 24;
+
+// On-disk format of one encrypted custodial keypair
+#[derive(Serialize, Deserialize)]
+struct KeystoreFile {
+    version: u8,
+    pubkey: String,
+    salt: String,
+    nonce: String,
+    ciphertext: String,
+}
+
+// Custodial keypairs encrypted at rest, one file per wallet.
+// Each file gets its own salt; the key is derived from the keystore password with Argon2id
+// and the keypair sealed with XChaCha20-Poly1305.
+pub struct KeystoreSigner {
+    dir: PathBuf,
+    password: Arc<String>,
+}
+
+impl KeystoreSigner {
+    pub fn new(dir: PathBuf, password: String) -> Result<Self, ApiError> {
+        fs::create_dir_all(&dir)?;
+        Ok(Self {
+            dir,
+            password: Arc::new(password),
+        })
+    }
+
+    fn path(&self, wallet: &str) -> Result<PathBuf, ApiError> {
+        let wallet = checked_wallet(wallet)?;
+        Ok(self.dir.join(format!("{}.json", wallet)))
+    }
+
+    async fn read_file(&self, wallet: &str) -> Result<KeystoreFile, ApiError> {
+        let path = self.path(wallet)?;
+        let wallet = wallet.to_string();
+
+        run_blocking(move || read_keystore_file(&path, &wallet)).await
+    }
+
+    async fn load(&self, wallet: &str) -> Result<Keypair, ApiError> {
+        let path = self.path(wallet)?;
+        let wallet = wallet.to_string();
+        let password = self.password.clone();
+
+        run_blocking(move || decrypt(&password, &read_keystore_file(&path, &wallet)?)).await
+    }
+}
+
+#[async_trait]
+impl TransactionSigner for KeystoreSigner {
+    async fn create(&self, wallet: &str) -> Result<Pubkey, ApiError> {
+        let path = self.path(wallet)?;
+        let wallet = wallet.to_string();
+        let password = self.password.clone();
+
+        run_blocking(move || {
+            // Claimed before the slow key derivation, so a concurrent create fails fast
+            let mut key_file = create_key_file(&path, &wallet)?;
+
+            let keypair = Keypair::new();
+            let written = encrypt(&password, &keypair).and_then(|file| {
+                let contents = serde_json::to_string_pretty(&file)
+                    .map_err(|e| ApiError::InternalServerError(e.to_string()))?;
+                key_file.write_all(contents.as_bytes())?;
+                Ok(())
+            });
+            if let Err(e) = written {
+                // Don't leave an empty file claiming the wallet
+                let _ = fs::remove_file(&path);
+                return Err(e);
+            }
+
+            Ok(keypair.pubkey())
+        })
+        .await
+    }
+
+    async fn pubkey(&self, wallet: &str) -> Result<Pubkey, ApiError> {
+        // The public key is stored in the clear, so no decryption is needed
+        let file = self.read_file(wallet).await?;
+        Pubkey::from_str(&file.pubkey)
+            .map_err(|_| ApiError::InternalServerError("Corrupt keystore file".to_string()))
+    }
+
+    async fn sign_message(&self, wallet: &str, message: &[u8]) -> Result<Signature, ApiError> {
+        Ok(self.load(wallet).await?.sign_message(message))
+    }
+
+    async fn export(&self, wallet: &str) -> Result<String, ApiError> {
+        Ok(self.load(wallet).await?.to_base58_string())
+    }
+
+    async fn delete(&self, wallet: &str) -> Result<(), ApiError> {
+        let path = self.path(wallet)?;
+        let wallet = wallet.to_string();
+
+        run_blocking(move || delete_key_file(&path, &wallet)).await
+    }
+}
+
+fn read_keystore_file(path: &Path, wallet: &str) -> Result<KeystoreFile, ApiError> {
+    if !path.exists() {
+        return Err(no_custodial_wallet(wallet));
+    }
+
+    let contents = fs::read_to_string(path)?;
+    serde_json::from_str(&contents)
+        .map_err(|e| ApiError::InternalServerError(format!("Corrupt keystore file: {}", e)))
+}
+
+fn derive_key(password: &str, salt: &[u8]) -> Result<Key, ApiError> {
+    let mut key = [0u8; 32];
+    Argon2::default()
+        .hash_password_into(password.as_bytes(), salt, &mut key)
+        .map_err(|e| ApiError::InternalServerError(format!("Key derivation failed: {}", e)))?;
+
+    Ok(Key::from(key))
+}
+
+fn encrypt(password: &str, keypair: &Keypair) -> Result<KeystoreFile, ApiError> {
+    let mut salt = [0u8; SALT_BYTES];
+    let mut nonce = [0u8; NONCE_BYTES];
+    rand::rngs::OsRng.fill_bytes(&mut salt);
+    rand::rngs::OsRng.fill_bytes(&mut nonce);
+
+    let cipher = XChaCha20Poly1305::new(&derive_key(password, &salt)?);
+    let ciphertext = cipher
+        .encrypt(&XNonce::from(nonce), keypair.to_bytes().as_ref())
+        .map_err(|_| ApiError::InternalServerError("Keypair encryption failed".to_string()))?;
+
+    Ok(KeystoreFile {
+        version: KEYSTORE_VERSION,
+        pubkey: keypair.pubkey().to_string(),
+        salt: BASE64.encode(salt),
+        nonce: BASE64.encode(nonce),
+        ciphertext: BASE64.encode(ciphertext),
+    })
+}
+
+fn decrypt(password: &str, file: &KeystoreFile) -> Result<Keypair, ApiError> {
+    let corrupt = || ApiError::InternalServerError("Corrupt keystore file".to_string());
+
+    if file.version != KEYSTORE_VERSION {
+        return Err(ApiError::InternalServerError(format!(
+            "Unsupported keystore version {}",
+            file.version
+        )));
+    }
+
+    let salt = BASE64.decode(&file.salt).map_err(|_| corrupt())?;
+    let nonce = BASE64.decode(&file.nonce).map_err(|_| corrupt())?;
+    let ciphertext = BASE64.decode(&file.ciphertext).map_err(|_| corrupt())?;
+
+    let nonce: [u8; NONCE_BYTES] = nonce.try_into().map_err(|_| corrupt())?;
+
+    let cipher = XChaCha20Poly1305::new(&derive_key(password, &salt)?);
+    let plaintext = cipher
+        .decrypt(&XNonce::from(nonce), ciphertext.as_ref())
+        .map_err(|_| ApiError::InternalServerError("Keystore password is wrong or file was tampered with".to_string()))?;
+
+    Keypair::from_bytes(&plaintext).map_err(|_| corrupt())
+}
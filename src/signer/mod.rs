@@ -0,0 +1,122 @@
+// src/signer/mod.rs
+pub mod disabled;
+pub mod file;
+pub mod keystore;
+pub mod remote;
+
+use crate::config::Config;
+use crate::errors::ApiError;
+use async_trait::async_trait;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signature;
+use solana_sdk::transaction::Transaction;
+use std::fs::{self, File, OpenOptions};
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+// Holds custodial keypairs on behalf of signed-in wallets.
+// `wallet` is always the user's own wallet; the custodial key is a separate
+// keypair that owns their todos on-chain and signs server-built transactions.
+#[async_trait]
+pub trait TransactionSigner: Send + Sync {
+    async fn create(&self, wallet: &str) -> Result<Pubkey, ApiError>;
+
+    // Fails with `ApiError::NotFound` if the wallet has no custodial key
+    async fn pubkey(&self, wallet: &str) -> Result<Pubkey, ApiError>;
+
+    async fn sign_message(&self, wallet: &str, message: &[u8]) -> Result<Signature, ApiError>;
+
+    // The custodial secret key, base58 encoded, so the user can take custody
+    async fn export(&self, wallet: &str) -> Result<String, ApiError>;
+
+    async fn delete(&self, wallet: &str) -> Result<(), ApiError>;
+}
+
+pub fn from_config(config: &Config) -> Result<Arc<dyn TransactionSigner>, ApiError> {
+    match config.signer_backend.as_str() {
+        "none" => Ok(Arc::new(disabled::DisabledSigner)),
+        "keystore" => {
+            let password = config.keystore_password.clone().ok_or_else(|| {
+                ApiError::InternalServerError("KEYSTORE_PASSWORD must be set".to_string())
+            })?;
+            Ok(Arc::new(keystore::KeystoreSigner::new(
+                PathBuf::from(&config.keystore_dir),
+                password,
+            )?))
+        }
+        "file" => Ok(Arc::new(file::FileSigner::new(PathBuf::from(&config.keystore_dir))?)),
+        "remote" => {
+            let url = config.remote_signer_url.clone().ok_or_else(|| {
+                ApiError::InternalServerError("REMOTE_SIGNER_URL must be set".to_string())
+            })?;
+            Ok(Arc::new(remote::RemoteSigner::new(url, config.remote_signer_token.clone())))
+        }
+        other => Err(ApiError::InternalServerError(format!("Unknown signer backend: {}", other))),
+    }
+}
+
+// Add the custodial key's signature to a transaction it is a required signer of
+pub async fn sign_transaction(
+    signer: &dyn TransactionSigner,
+    wallet: &str,
+    transaction: &mut Transaction,
+) -> Result<(), ApiError> {
+    let pubkey = signer.pubkey(wallet).await?;
+
+    let position = transaction
+        .get_signing_keypair_positions(&[pubkey])
+        .ok()
+        .and_then(|positions| positions.first().copied().flatten())
+        .ok_or_else(|| {
+            ApiError::InternalServerError("Custodial key is not a signer of the transaction".to_string())
+        })?;
+
+    let signature = signer.sign_message(wallet, &transaction.message_data()).await?;
+    transaction.signatures[position] = signature;
+
+    Ok(())
+}
+
+// Wallet addresses double as file names, so they must be valid pubkeys
+fn checked_wallet(wallet: &str) -> Result<Pubkey, ApiError> {
+    crate::utils::wallet::parse_pubkey(wallet)
+}
+
+fn no_custodial_wallet(wallet: &str) -> ApiError {
+    ApiError::NotFound(format!("No custodial wallet for {}", wallet))
+}
+
+// Create a wallet's key file, failing if it already exists. Checking first and
+// writing later would let two concurrent creates both pass, and the second would
+// overwrite a key that may already own todos or hold funds.
+fn create_key_file(path: &Path, wallet: &str) -> Result<File, ApiError> {
+    let mut options = OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+
+    options.open(path).map_err(|e| match e.kind() {
+        ErrorKind::AlreadyExists => ApiError::Conflict(format!("Custodial wallet already exists for {}", wallet)),
+        _ => e.into(),
+    })
+}
+
+// Remove a wallet's key file, which not existing means it has no custodial key
+fn delete_key_file(path: &Path, wallet: &str) -> Result<(), ApiError> {
+    fs::remove_file(path).map_err(|e| match e.kind() {
+        ErrorKind::NotFound => no_custodial_wallet(wallet),
+        _ => e.into(),
+    })
+}
+
+// Run key derivation and file IO off the async workers
+async fn run_blocking<T, F>(f: F) -> Result<T, ApiError>
+where
+    F: FnOnce() -> Result<T, ApiError> + Send + 'static,
+    T: Send + 'static,
+{
+    tokio::task::spawn_blocking(f)
+        .await
+        .map_err(|e| ApiError::InternalServerError(format!("Signer task failed: {}", e)))?
+}
@@ -0,0 +1,153 @@
+// src/signer/remote.rs
+use crate::errors::ApiError;
+use crate::signer::{checked_wallet, no_custodial_wallet, TransactionSigner};
+use async_trait::async_trait;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use reqwest::{Client, RequestBuilder, Response, StatusCode};
+use serde::{Deserialize, Serialize};
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signature;
+use std::str::FromStr;
+
+// Wire types of the remote signer protocol:
+//
+// POST   /wallets                      {"wallet"}           -> {"pubkey"}
+// GET    /wallets/{wallet}                                  -> {"pubkey"}
+// POST   /wallets/{wallet}/sign        {"message": base64}  -> {"signature": base58}
+// POST   /wallets/{wallet}/export                           -> {"secret_key": base58}
+// DELETE /wallets/{wallet}
+//
+// A missing wallet is reported as 404. `src/bin/remote_signer_stub.rs` implements it locally.
+#[derive(Serialize, Deserialize)]
+pub struct CreateWalletRequest {
+    pub wallet: String,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct PubkeyResponse {
+    pub pubkey: String,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct SignRequest {
+    pub message: String,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct SignResponse {
+    pub signature: String,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct ExportResponse {
+    pub secret_key: String,
+}
+
+// Delegates custody to a separate signing service over HTTP
+pub struct RemoteSigner {
+    base_url: String,
+    token: Option<String>,
+    client: Client,
+}
+
+impl RemoteSigner {
+    pub fn new(base_url: String, token: Option<String>) -> Self {
+        Self {
+            base_url: base_url.trim_end_matches('/').to_string(),
+            token,
+            client: Client::new(),
+        }
+    }
+
+    fn url(&self, wallet: &str, suffix: &str) -> Result<String, ApiError> {
+        let wallet = checked_wallet(wallet)?;
+        Ok(format!("{}/wallets/{}{}", self.base_url, wallet, suffix))
+    }
+
+    async fn send(&self, wallet: &str, request: RequestBuilder) -> Result<Response, ApiError> {
+        let request = match &self.token {
+            Some(token) => request.bearer_auth(token),
+            None => request,
+        };
+
+        let response = request.send().await.map_err(remote_error)?;
+
+        match response.status() {
+            StatusCode::NOT_FOUND => Err(no_custodial_wallet(wallet)),
+            StatusCode::CONFLICT => {
+                let body = response.text().await.unwrap_or_default();
+                Err(ApiError::Conflict(format!("Remote signer rejected the request: {}", body)))
+            }
+            status if status.is_success() => Ok(response),
+            status if status.is_client_error() => {
+                let body = response.text().await.unwrap_or_default();
+                Err(ApiError::BadRequest(format!("Remote signer rejected the request: {}", body)))
+            }
+            status => Err(ApiError::InternalServerError(format!(
+                "Remote signer responded with {}",
+                status
+            ))),
+        }
+    }
+}
+
+#[async_trait]
+impl TransactionSigner for RemoteSigner {
+    async fn create(&self, wallet: &str) -> Result<Pubkey, ApiError> {
+        checked_wallet(wallet)?;
+
+        let request = self
+            .client
+            .post(format!("{}/wallets", self.base_url))
+            .json(&CreateWalletRequest {
+                wallet: wallet.to_string(),
+            });
+
+        let response: PubkeyResponse = self.send(wallet, request).await?.json().await.map_err(remote_error)?;
+        parse_remote_pubkey(&response.pubkey)
+    }
+
+    async fn pubkey(&self, wallet: &str) -> Result<Pubkey, ApiError> {
+        let request = self.client.get(self.url(wallet, "")?);
+
+        let response: PubkeyResponse = self.send(wallet, request).await?.json().await.map_err(remote_error)?;
+        parse_remote_pubkey(&response.pubkey)
+    }
+
+    async fn sign_message(&self, wallet: &str, message: &[u8]) -> Result<Signature, ApiError> {
+        let request = self
+            .client
+            .post(self.url(wallet, "/sign")?)
+            .json(&SignRequest {
+                message: BASE64.encode(message),
+            });
+
+        let response: SignResponse = self.send(wallet, request).await?.json().await.map_err(remote_error)?;
+        Signature::from_str(&response.signature)
+            .map_err(|_| ApiError::InternalServerError("Remote signer returned an invalid signature".to_string()))
+    }
+
+    async fn export(&self, wallet: &str) -> Result<String, ApiError> {
+        let request = self.client.post(self.url(wallet, "/export")?);
+
+        let response: ExportResponse = self.send(wallet, request).await?.json().await.map_err(remote_error)?;
+        Ok(response.secret_key)
+    }
+
+    async fn delete(&self, wallet: &str) -> Result<(), ApiError> {
+        let request = self.client.delete(self.url(wallet, "")?);
+
+        self.send(wallet, request).await?;
+        Ok(())
+    }
+}
+
+fn parse_remote_pubkey(pubkey: &str) -> Result<Pubkey, ApiError> {
+    Pubkey::from_str(pubkey)
+        .map_err(|_| ApiError::InternalServerError("Remote signer returned an invalid pubkey".to_string()))
+}
+
+fn remote_error(error: reqwest::Error) -> ApiError {
+    ApiError::InternalServerError(format!("Remote signer request failed: {}", error))
+}
@@ -0,0 +1,36 @@
+// src/solana/commitment.rs
+use crate::errors::ApiError;
+use actix_web::{web, HttpRequest};
+use solana_sdk::commitment_config::CommitmentConfig;
+use std::collections::HashMap;
+
+pub const COMMITMENT_HEADER: &str = "X-Commitment";
+pub const COMMITMENT_QUERY_PARAM: &str = "commitment";
+
+// Only the three levels the cluster still supports, not the deprecated aliases
+pub fn parse(value: &str) -> Option<CommitmentConfig> {
+    match value.trim().to_ascii_lowercase().as_str() {
+        "processed" => Some(CommitmentConfig::processed()),
+        "confirmed" => Some(CommitmentConfig::confirmed()),
+        "finalized" => Some(CommitmentConfig::finalized()),
+        _ => None,
+    }
+}
+
+// `?commitment=<level>` or `X-Commitment: <level>`, the query parameter winning
+pub fn from_request(req: &HttpRequest) -> Result<Option<CommitmentConfig>, ApiError> {
+    let query = web::Query::<HashMap<String, String>>::from_query(req.query_string())
+        .map_err(|e| ApiError::BadRequest(e.to_string()))?;
+
+    let value = match query.get(COMMITMENT_QUERY_PARAM) {
+        Some(value) => value.as_str(),
+        None => match req.headers().get(COMMITMENT_HEADER) {
+            Some(value) => value.to_str().unwrap_or_default(),
+            None => return Ok(None),
+        },
+    };
+
+    parse(value).map(Some).ok_or_else(|| {
+        ApiError::BadRequest("Commitment must be processed, confirmed or finalized".to_string())
+    })
+}
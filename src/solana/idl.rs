@@ -0,0 +1,693 @@
+// src/solana/idl.rs
+use super::rpc_pool::RpcPool;
+use crate::config::Config;
+use crate::errors::ApiError;
+use anchor_lang::idl::IdlAccount;
+use anchor_lang::AccountDeserialize;
+use flate2::read::ZlibDecoder;
+use serde::Deserialize;
+use serde_json::{Map, Value};
+use sha2::{Digest, Sha256};
+use solana_sdk::instruction::{AccountMeta, Instruction};
+use solana_sdk::pubkey::Pubkey;
+use std::collections::HashMap;
+use std::fmt;
+use std::io::Read;
+use std::str::FromStr;
+
+// The parts of an Anchor IDL (the JSON `anchor build` emits) the API reads
+#[derive(Deserialize)]
+struct RawIdl {
+    #[serde(default)]
+    instructions: Vec<RawInstruction>,
+    #[serde(default)]
+    accounts: Vec<RawTypeDef>,
+    #[serde(default)]
+    types: Vec<RawTypeDef>,
+    #[serde(default)]
+    errors: Vec<IdlError>,
+}
+
+#[derive(Deserialize)]
+struct RawInstruction {
+    name: String,
+    accounts: Vec<RawAccountItem>,
+    args: Vec<RawField>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RawAccountItem {
+    name: String,
+    #[serde(default)]
+    is_mut: bool,
+    #[serde(default)]
+    is_signer: bool,
+}
+
+#[derive(Deserialize)]
+struct RawField {
+    name: String,
+    #[serde(rename = "type")]
+    ty: Value,
+}
+
+#[derive(Deserialize)]
+struct RawTypeDef {
+    name: String,
+    #[serde(rename = "type")]
+    ty: RawTypeDefBody,
+}
+
+#[derive(Deserialize)]
+struct RawTypeDefBody {
+    kind: String,
+    #[serde(default)]
+    fields: Vec<RawField>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct IdlError {
+    pub code: u32,
+    pub name: String,
+    #[serde(default)]
+    pub msg: Option<String>,
+}
+
+// Field and argument types, Borsh-encoded as Anchor encodes them
+#[derive(Debug, Clone, PartialEq)]
+pub enum IdlType {
+    Bool,
+    U8,
+    I8,
+    U16,
+    I16,
+    U32,
+    I32,
+    U64,
+    I64,
+    U128,
+    I128,
+    F32,
+    F64,
+    String,
+    PublicKey,
+    Bytes,
+    Option(Box<IdlType>),
+    Vec(Box<IdlType>),
+    Array(Box<IdlType>, usize),
+    // A struct from the IDL's `types`
+    Defined(String),
+}
+
+impl IdlType {
+    fn parse(value: &Value) -> Result<Self, String> {
+        if let Some(name) = value.as_str() {
+            return Ok(match name {
+                "bool" => IdlType::Bool,
+                "u8" => IdlType::U8,
+                "i8" => IdlType::I8,
+                "u16" => IdlType::U16,
+                "i16" => IdlType::I16,
+                "u32" => IdlType::U32,
+                "i32" => IdlType::I32,
+                "u64" => IdlType::U64,
+                "i64" => IdlType::I64,
+                "u128" => IdlType::U128,
+                "i128" => IdlType::I128,
+                "f32" => IdlType::F32,
+                "f64" => IdlType::F64,
+                "string" => IdlType::String,
+                "publicKey" => IdlType::PublicKey,
+                "bytes" => IdlType::Bytes,
+                other => return Err(format!("unsupported type {}", other)),
+            });
+        }
+
+        if let Some(inner) = value.get("option") {
+            return Ok(IdlType::Option(Box::new(Self::parse(inner)?)));
+        }
+        if let Some(inner) = value.get("vec") {
+            return Ok(IdlType::Vec(Box::new(Self::parse(inner)?)));
+        }
+        if let Some([inner, len]) = value.get("array").and_then(Value::as_array).map(Vec::as_slice) {
+            let len = len.as_u64().ok_or_else(|| format!("invalid array length in {}", value))?;
+            return Ok(IdlType::Array(Box::new(Self::parse(inner)?), len as usize));
+        }
+        if let Some(name) = value.get("defined").and_then(Value::as_str) {
+            return Ok(IdlType::Defined(name.to_string()));
+        }
+
+        Err(format!("unsupported type {}", value))
+    }
+
+    // Bytes a value always takes, None for strings, vecs and other variable-length types.
+    // Options count their value, as space for them is allocated up front.
+    fn fixed_size(&self, types: &HashMap<String, Vec<IdlField>>) -> Option<usize> {
+        Some(match self {
+            IdlType::Bool | IdlType::U8 | IdlType::I8 => 1,
+            IdlType::U16 | IdlType::I16 => 2,
+            IdlType::U32 | IdlType::I32 | IdlType::F32 => 4,
+            IdlType::U64 | IdlType::I64 | IdlType::F64 => 8,
+            IdlType::U128 | IdlType::I128 => 16,
+            IdlType::PublicKey => 32,
+            IdlType::String | IdlType::Bytes | IdlType::Vec(_) => return None,
+            IdlType::Option(inner) => 1 + inner.fixed_size(types)?,
+            IdlType::Array(inner, len) => inner.fixed_size(types)? * len,
+            IdlType::Defined(name) => types
+                .get(name)?
+                .iter()
+                .map(|field| field.ty.fixed_size(types))
+                .sum::<Option<usize>>()?,
+        })
+    }
+}
+
+// Renders types as the IDL spells them, e.g. `u64` or `option<publicKey>`
+impl fmt::Display for IdlType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            IdlType::Bool => write!(f, "bool"),
+            IdlType::U8 => write!(f, "u8"),
+            IdlType::I8 => write!(f, "i8"),
+            IdlType::U16 => write!(f, "u16"),
+            IdlType::I16 => write!(f, "i16"),
+            IdlType::U32 => write!(f, "u32"),
+            IdlType::I32 => write!(f, "i32"),
+            IdlType::U64 => write!(f, "u64"),
+            IdlType::I64 => write!(f, "i64"),
+            IdlType::U128 => write!(f, "u128"),
+            IdlType::I128 => write!(f, "i128"),
+            IdlType::F32 => write!(f, "f32"),
+            IdlType::F64 => write!(f, "f64"),
+            IdlType::String => write!(f, "string"),
+            IdlType::PublicKey => write!(f, "publicKey"),
+            IdlType::Bytes => write!(f, "bytes"),
+            IdlType::Option(inner) => write!(f, "option<{}>", inner),
+            IdlType::Vec(inner) => write!(f, "vec<{}>", inner),
+            IdlType::Array(inner, len) => write!(f, "[{}; {}]", inner, len),
+            IdlType::Defined(name) => write!(f, "{}", name),
+        }
+    }
+}
+
+struct IdlField {
+    name: String,
+    ty: IdlType,
+}
+
+struct IdlAccountItem {
+    name: String,
+    is_mut: bool,
+    is_signer: bool,
+}
+
+struct IdlInstruction {
+    discriminator: [u8; 8],
+    accounts: Vec<IdlAccountItem>,
+    args: Vec<IdlField>,
+}
+
+struct IdlAccountDef {
+    discriminator: [u8; 8],
+    fields: Vec<IdlField>,
+}
+
+// A program's Anchor IDL, used to build instructions and decode accounts without
+// compiled-in copies of the program's types. Instruction, account item, argument
+// and field names are snake_case whatever case the IDL uses; account and type
+// names keep theirs.
+pub struct Idl {
+    instructions: HashMap<String, IdlInstruction>,
+    accounts: HashMap<String, IdlAccountDef>,
+    types: HashMap<String, Vec<IdlField>>,
+    errors: HashMap<u32, IdlError>,
+}
+
+impl Idl {
+    // From `PROGRAM_IDL_PATH` if set, otherwise from the program's on-chain IDL account
+    pub async fn load(config: &Config, rpc: &RpcPool) -> Result<Self, ApiError> {
+        match &config.program_idl_path {
+            Some(path) => Self::from_file(path),
+            None => {
+                let program_id = Pubkey::from_str(&config.program_id)
+                    .map_err(|_| ApiError::BadRequest("Invalid program ID".to_string()))?;
+                Self::fetch(rpc, &program_id).await
+            }
+        }
+    }
+
+    pub fn from_file(path: &str) -> Result<Self, ApiError> {
+        let json = std::fs::read_to_string(path)
+            .map_err(|e| ApiError::InternalServerError(format!("Failed to read IDL {}: {}", path, e)))?;
+
+        Self::from_json(&json)
+    }
+
+    // The IDL account `anchor idl init` writes: authority, length, then zlib-compressed JSON
+    pub async fn fetch(rpc: &RpcPool, program_id: &Pubkey) -> Result<Self, ApiError> {
+        let address = IdlAccount::address(program_id);
+        let data = rpc
+            .read(|rpc| async move { rpc.get_account_data(&address).await })
+            .await
+            .map_err(|e| {
+                ApiError::InternalServerError(format!("Failed to fetch IDL account {}: {}", address, e))
+            })?;
+
+        let invalid = |reason: String| {
+            ApiError::InternalServerError(format!("Invalid IDL account {}: {}", address, reason))
+        };
+        let header = IdlAccount::try_deserialize(&mut &data[..]).map_err(|e| invalid(e.to_string()))?;
+        // Discriminator, authority and length precede the compressed bytes
+        let start = 8 + 32 + 4;
+        let compressed = data
+            .get(start..start + header.data_len as usize)
+            .ok_or_else(|| invalid("data is shorter than its length".to_string()))?;
+
+        let mut json = String::new();
+        ZlibDecoder::new(compressed)
+            .read_to_string(&mut json)
+            .map_err(|e| invalid(e.to_string()))?;
+
+        Self::from_json(&json)
+    }
+
+    pub fn from_json(json: &str) -> Result<Self, ApiError> {
+        let invalid = |reason: String| ApiError::InternalServerError(format!("Invalid IDL: {}", reason));
+        let raw: RawIdl = serde_json::from_str(json).map_err(|e| invalid(e.to_string()))?;
+
+        let fields = |fields: Vec<RawField>| {
+            fields
+                .into_iter()
+                .map(|field| {
+                    let ty = IdlType::parse(&field.ty).map_err(|e| format!("{}: {}", field.name, e))?;
+                    Ok(IdlField { name: snake_case(&field.name), ty })
+                })
+                .collect::<Result<Vec<_>, String>>()
+        };
+
+        let mut instructions = HashMap::new();
+        for instruction in raw.instructions {
+            let name = snake_case(&instruction.name);
+            let args = fields(instruction.args).map_err(|e| invalid(format!("instruction {}: {}", name, e)))?;
+            let accounts = instruction
+                .accounts
+                .into_iter()
+                .map(|item| IdlAccountItem {
+                    name: snake_case(&item.name),
+                    is_mut: item.is_mut,
+                    is_signer: item.is_signer,
+                })
+                .collect();
+
+            instructions.insert(
+                name.clone(),
+                IdlInstruction {
+                    discriminator: discriminator("global", &name),
+                    accounts,
+                    args,
+                },
+            );
+        }
+
+        let mut accounts = HashMap::new();
+        for account in raw.accounts {
+            let account_fields = fields(account.ty.fields).map_err(|e| invalid(format!("account {}: {}", account.name, e)))?;
+            accounts.insert(
+                account.name.clone(),
+                IdlAccountDef {
+                    discriminator: discriminator("account", &account.name),
+                    fields: account_fields,
+                },
+            );
+        }
+
+        // Enums and other non-struct types are skipped; fields using them fail when used
+        let mut types = HashMap::new();
+        for ty in raw.types.into_iter().filter(|ty| ty.ty.kind == "struct") {
+            let type_fields = fields(ty.ty.fields).map_err(|e| invalid(format!("type {}: {}", ty.name, e)))?;
+            types.insert(ty.name, type_fields);
+        }
+
+        let errors = raw.errors.into_iter().map(|error| (error.code, error)).collect();
+
+        Ok(Self {
+            instructions,
+            accounts,
+            types,
+            errors,
+        })
+    }
+
+    // Check an instruction has the accounts and typed arguments the API passes it
+    pub fn require_instruction(&self, name: &str, accounts: &[&str], args: &[(&str, &str)]) -> Result<(), String> {
+        let instruction = self
+            .instructions
+            .get(name)
+            .ok_or_else(|| format!("missing instruction {}", name))?;
+
+        for account in accounts {
+            if !instruction.accounts.iter().any(|item| item.name == *account) {
+                return Err(format!("instruction {} has no account {}", name, account));
+            }
+        }
+        if let Some(extra) = instruction.accounts.iter().find(|item| !accounts.contains(&item.name.as_str())) {
+            return Err(format!("instruction {} takes account {} the API doesn't provide", name, extra.name));
+        }
+
+        require_fields(&format!("instruction {}", name), &instruction.args, args)
+    }
+
+    // Check an account starts with the given typed fields, and return the space taken
+    // by any the program appended after them. Appended fields must have a fixed size
+    // so that older accounts can be reallocated to fit.
+    pub fn appended_size(&self, name: &str, fields: &[(&str, &str)]) -> Result<usize, String> {
+        let account = self
+            .accounts
+            .get(name)
+            .ok_or_else(|| format!("missing account {}", name))?;
+        let prefix = account.fields.get(..fields.len()).unwrap_or(&account.fields);
+        require_fields(&format!("account {}", name), prefix, fields)?;
+
+        account.fields[fields.len()..]
+            .iter()
+            .map(|field| {
+                field
+                    .ty
+                    .fixed_size(&self.types)
+                    .ok_or_else(|| format!("account {} appends variable-size field {}", name, field.name))
+            })
+            .sum()
+    }
+
+    // Build an instruction from account addresses and a JSON object of arguments, both by name
+    pub fn instruction(
+        &self,
+        program_id: &Pubkey,
+        name: &str,
+        accounts: &[(&str, Pubkey)],
+        args: &Value,
+    ) -> Result<Instruction, ApiError> {
+        let invalid = |reason: String| {
+            ApiError::InternalServerError(format!("Failed to build {} instruction: {}", name, reason))
+        };
+        let instruction = self
+            .instructions
+            .get(name)
+            .ok_or_else(|| invalid("not in the IDL".to_string()))?;
+
+        let metas = instruction
+            .accounts
+            .iter()
+            .map(|item| {
+                let pubkey = accounts
+                    .iter()
+                    .find(|(name, _)| *name == item.name)
+                    .map(|(_, pubkey)| *pubkey)
+                    .ok_or_else(|| invalid(format!("no address for account {}", item.name)))?;
+                Ok(if item.is_mut {
+                    AccountMeta::new(pubkey, item.is_signer)
+                } else {
+                    AccountMeta::new_readonly(pubkey, item.is_signer)
+                })
+            })
+            .collect::<Result<Vec<_>, ApiError>>()?;
+
+        let mut data = instruction.discriminator.to_vec();
+        for arg in &instruction.args {
+            let value = args.get(&arg.name).unwrap_or(&Value::Null);
+            self.encode(&arg.ty, value, &mut data)
+                .map_err(|e| invalid(format!("{}: {}", arg.name, e)))?;
+        }
+
+        Ok(Instruction {
+            program_id: *program_id,
+            accounts: metas,
+            data,
+        })
+    }
+
+    pub fn instruction_discriminator(&self, name: &str) -> Result<[u8; 8], ApiError> {
+        self.instructions
+            .get(name)
+            .map(|instruction| instruction.discriminator)
+            .ok_or_else(|| ApiError::InternalServerError(format!("Instruction {} is not in the IDL", name)))
+    }
+
+    pub fn account_discriminator(&self, name: &str) -> Result<[u8; 8], ApiError> {
+        self.accounts
+            .get(name)
+            .map(|account| account.discriminator)
+            .ok_or_else(|| ApiError::InternalServerError(format!("Account {} is not in the IDL", name)))
+    }
+
+    // Decode account data into its fields by name, after checking the discriminator.
+    // Trailing bytes are unused allocated space and ignored.
+    pub fn decode_account(&self, name: &str, data: &[u8]) -> Result<Map<String, Value>, String> {
+        self.decode_account_fields(name, usize::MAX, data)
+    }
+
+    // Decode only the first `count` fields, for accounts written before later ones were added
+    pub fn decode_account_fields(&self, name: &str, count: usize, data: &[u8]) -> Result<Map<String, Value>, String> {
+        let account = self
+            .accounts
+            .get(name)
+            .ok_or_else(|| format!("account {} is not in the IDL", name))?;
+
+        match data.get(..8) {
+            None => return Err("account discriminator not found".to_string()),
+            Some(prefix) if prefix != account.discriminator => {
+                return Err(format!("not a {} account (discriminator mismatch)", name))
+            }
+            Some(_) => {}
+        }
+
+        let fields = account.fields.get(..count).unwrap_or(&account.fields);
+        self.decode_fields(fields, &mut &data[8..])
+    }
+
+    pub fn error(&self, code: u32) -> Option<&IdlError> {
+        self.errors.get(&code)
+    }
+
+    fn encode(&self, ty: &IdlType, value: &Value, out: &mut Vec<u8>) -> Result<(), String> {
+        let mismatch = || format!("expected {}, got {}", ty, value);
+        let unsigned = || value.as_u64().ok_or_else(mismatch);
+        let signed = || value.as_i64().ok_or_else(mismatch);
+
+        match ty {
+            IdlType::Bool => out.push(value.as_bool().ok_or_else(mismatch)? as u8),
+            IdlType::U8 => out.push(u8::try_from(unsigned()?).map_err(|_| mismatch())?),
+            IdlType::I8 => out.extend(i8::try_from(signed()?).map_err(|_| mismatch())?.to_le_bytes()),
+            IdlType::U16 => out.extend(u16::try_from(unsigned()?).map_err(|_| mismatch())?.to_le_bytes()),
+            IdlType::I16 => out.extend(i16::try_from(signed()?).map_err(|_| mismatch())?.to_le_bytes()),
+            IdlType::U32 => out.extend(u32::try_from(unsigned()?).map_err(|_| mismatch())?.to_le_bytes()),
+            IdlType::I32 => out.extend(i32::try_from(signed()?).map_err(|_| mismatch())?.to_le_bytes()),
+            IdlType::U64 => out.extend(unsigned()?.to_le_bytes()),
+            IdlType::I64 => out.extend(signed()?.to_le_bytes()),
+            // Too wide for JSON numbers, so 128-bit integers travel as strings
+            IdlType::U128 => {
+                let number: u128 = wide(value).and_then(|v| v.parse().ok()).ok_or_else(mismatch)?;
+                out.extend(number.to_le_bytes());
+            }
+            IdlType::I128 => {
+                let number: i128 = wide(value).and_then(|v| v.parse().ok()).ok_or_else(mismatch)?;
+                out.extend(number.to_le_bytes());
+            }
+            IdlType::F32 => out.extend((value.as_f64().ok_or_else(mismatch)? as f32).to_le_bytes()),
+            IdlType::F64 => out.extend(value.as_f64().ok_or_else(mismatch)?.to_le_bytes()),
+            IdlType::String => {
+                let string = value.as_str().ok_or_else(mismatch)?;
+                out.extend((string.len() as u32).to_le_bytes());
+                out.extend(string.as_bytes());
+            }
+            IdlType::PublicKey => {
+                let pubkey = value
+                    .as_str()
+                    .and_then(|key| Pubkey::from_str(key).ok())
+                    .ok_or_else(mismatch)?;
+                out.extend(pubkey.to_bytes());
+            }
+            IdlType::Bytes => {
+                let bytes = value
+                    .as_array()
+                    .ok_or_else(mismatch)?
+                    .iter()
+                    .map(|byte| byte.as_u64().and_then(|b| u8::try_from(b).ok()).ok_or_else(mismatch))
+                    .collect::<Result<Vec<u8>, String>>()?;
+                out.extend((bytes.len() as u32).to_le_bytes());
+                out.extend(bytes);
+            }
+            IdlType::Option(inner) => match value {
+                Value::Null => out.push(0),
+                value => {
+                    out.push(1);
+                    self.encode(inner, value, out)?;
+                }
+            },
+            IdlType::Vec(inner) => {
+                let items = value.as_array().ok_or_else(mismatch)?;
+                out.extend((items.len() as u32).to_le_bytes());
+                for item in items {
+                    self.encode(inner, item, out)?;
+                }
+            }
+            IdlType::Array(inner, len) => {
+                let items = value.as_array().filter(|items| items.len() == *len).ok_or_else(mismatch)?;
+                for item in items {
+                    self.encode(inner, item, out)?;
+                }
+            }
+            IdlType::Defined(name) => {
+                let fields = self.types.get(name).ok_or_else(|| format!("unsupported type {}", name))?;
+                for field in fields {
+                    let value = value.get(&field.name).unwrap_or(&Value::Null);
+                    self.encode(&field.ty, value, out)
+                        .map_err(|e| format!("{}.{}: {}", name, field.name, e))?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    fn decode_fields(&self, fields: &[IdlField], data: &mut &[u8]) -> Result<Map<String, Value>, String> {
+        fields
+            .iter()
+            .map(|field| {
+                let value = self
+                    .decode(&field.ty, data)
+                    .map_err(|e| format!("{}: {}", field.name, e))?;
+                Ok((field.name.clone(), value))
+            })
+            .collect()
+    }
+
+    fn decode(&self, ty: &IdlType, data: &mut &[u8]) -> Result<Value, String> {
+        Ok(match ty {
+            IdlType::Bool => match take::<1>(data)?[0] {
+                0 => Value::Bool(false),
+                1 => Value::Bool(true),
+                other => return Err(format!("invalid bool {}", other)),
+            },
+            IdlType::U8 => Value::from(take::<1>(data)?[0]),
+            IdlType::I8 => Value::from(i8::from_le_bytes(take(data)?)),
+            IdlType::U16 => Value::from(u16::from_le_bytes(take(data)?)),
+            IdlType::I16 => Value::from(i16::from_le_bytes(take(data)?)),
+            IdlType::U32 => Value::from(u32::from_le_bytes(take(data)?)),
+            IdlType::I32 => Value::from(i32::from_le_bytes(take(data)?)),
+            IdlType::U64 => Value::from(u64::from_le_bytes(take(data)?)),
+            IdlType::I64 => Value::from(i64::from_le_bytes(take(data)?)),
+            IdlType::U128 => Value::from(u128::from_le_bytes(take(data)?).to_string()),
+            IdlType::I128 => Value::from(i128::from_le_bytes(take(data)?).to_string()),
+            IdlType::F32 => Value::from(f32::from_le_bytes(take(data)?) as f64),
+            IdlType::F64 => Value::from(f64::from_le_bytes(take(data)?)),
+            IdlType::String => {
+                let bytes = take_len_prefixed(data)?;
+                Value::from(String::from_utf8(bytes.to_vec()).map_err(|_| "invalid UTF-8 string".to_string())?)
+            }
+            IdlType::PublicKey => Value::from(Pubkey::new_from_array(take(data)?).to_string()),
+            IdlType::Bytes => Value::from(take_len_prefixed(data)?.to_vec()),
+            IdlType::Option(inner) => match take::<1>(data)?[0] {
+                0 => Value::Null,
+                1 => self.decode(inner, data)?,
+                other => return Err(format!("invalid option tag {}", other)),
+            },
+            IdlType::Vec(inner) => {
+                let len = u32::from_le_bytes(take(data)?) as usize;
+                // Each element takes at least a byte, so a length past the data is corrupt
+                if len > data.len() {
+                    return Err(format!("vec length {} exceeds the data", len));
+                }
+                Value::Array((0..len).map(|_| self.decode(inner, data)).collect::<Result<_, _>>()?)
+            }
+            IdlType::Array(inner, len) => {
+                Value::Array((0..*len).map(|_| self.decode(inner, data)).collect::<Result<_, _>>()?)
+            }
+            IdlType::Defined(name) => {
+                let fields = self.types.get(name).ok_or_else(|| format!("unsupported type {}", name))?;
+                Value::Object(self.decode_fields(fields, data)?)
+            }
+        })
+    }
+}
+
+fn require_fields(context: &str, actual: &[IdlField], expected: &[(&str, &str)]) -> Result<(), String> {
+    let actual: Vec<(&str, String)> = actual
+        .iter()
+        .map(|field| (field.name.as_str(), field.ty.to_string()))
+        .collect();
+    let expected: Vec<(&str, String)> = expected
+        .iter()
+        .map(|(name, ty)| (*name, ty.to_string()))
+        .collect();
+
+    if actual != expected {
+        let render = |fields: &[(&str, String)]| {
+            fields
+                .iter()
+                .map(|(name, ty)| format!("{}: {}", name, ty))
+                .collect::<Vec<_>>()
+                .join(", ")
+        };
+        return Err(format!(
+            "{} has ({}), expected ({})",
+            context,
+            render(&actual),
+            render(&expected)
+        ));
+    }
+
+    Ok(())
+}
+
+// sha256("<namespace>:<name>")[..8], as Anchor derives discriminators
+fn discriminator(namespace: &str, name: &str) -> [u8; 8] {
+    let hash = Sha256::digest(format!("{}:{}", namespace, name).as_bytes());
+    let mut discriminator = [0u8; 8];
+    discriminator.copy_from_slice(&hash[..8]);
+    discriminator
+}
+
+// `createTodo` -> `create_todo`; already snake_case names pass through
+fn snake_case(name: &str) -> String {
+    let mut snake = String::with_capacity(name.len() + 4);
+    for (i, c) in name.chars().enumerate() {
+        if c.is_ascii_uppercase() {
+            if i > 0 {
+                snake.push('_');
+            }
+            snake.push(c.to_ascii_lowercase());
+        } else {
+            snake.push(c);
+        }
+    }
+    snake
+}
+
+fn wide(value: &Value) -> Option<String> {
+    match value {
+        Value::String(string) => Some(string.clone()),
+        Value::Number(number) => Some(number.to_string()),
+        _ => None,
+    }
+}
+
+fn take<const N: usize>(data: &mut &[u8]) -> Result<[u8; N], String> {
+    if data.len() < N {
+        return Err("unexpected end of data".to_string());
+    }
+    let (head, rest) = data.split_at(N);
+    *data = rest;
+    Ok(head.try_into().expect("split at N"))
+}
+
+fn take_len_prefixed<'a>(data: &mut &'a [u8]) -> Result<&'a [u8], String> {
+    let len = u32::from_le_bytes(take(data)?) as usize;
+    if data.len() < len {
+        return Err("unexpected end of data".to_string());
+    }
+    let (head, rest) = data.split_at(len);
+    *data = rest;
+    Ok(head)
+}
@@ -0,0 +1,374 @@
+// src/solana/indexer.rs
+use super::idl::Idl;
+use super::{decode_todo, todo_response, SolanaService, TodoAccount};
+use crate::config::Config;
+use crate::errors::ApiError;
+use crate::models::todo::TodoResponse;
+use crate::repository::{RepositoryWriter, StoredTodo, TodoRepository};
+use futures::StreamExt;
+use solana_account_decoder::UiAccountEncoding;
+use solana_client::nonblocking::pubsub_client::{PubsubClient, PubsubClientError};
+use solana_client::rpc_config::{RpcAccountInfoConfig, RpcProgramAccountsConfig};
+use solana_client::rpc_response::{Response as RpcResponse, RpcKeyedAccount};
+use solana_sdk::account::Account;
+use solana_sdk::commitment_config::CommitmentConfig;
+use solana_sdk::pubkey::Pubkey;
+use std::collections::HashMap;
+use std::str::FromStr;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, RwLock, RwLockReadGuard, RwLockWriteGuard};
+use std::time::Duration;
+
+pub const INDEXED_SLOT_HEADER: &str = "X-Indexed-Slot";
+
+const RECONNECT_DELAY: Duration = Duration::from_secs(5);
+const HEARTBEAT_INTERVAL: Duration = Duration::from_secs(5);
+
+struct IndexedTodo {
+    slot: u64, // the slot the account was last seen at
+    todo: TodoAccount,
+}
+
+#[derive(Default)]
+struct IndexState {
+    todos: HashMap<Pubkey, IndexedTodo>,
+    // The slot the whole index is known current to; None until built and after a gap
+    indexed_slot: Option<u64>,
+    // The cluster slot at the last heartbeat, committed to `indexed_slot` at the next
+    pending_slot: Option<u64>,
+    // The latest slot the websocket reported
+    websocket_slot: u64,
+}
+
+// In-memory mirror of the program's todo accounts. A full scan backfills it, a
+// `programSubscribe` subscription keeps it current, and it's rebuilt whenever the
+// subscription drops or falls behind. List reads are served from it while it's current.
+pub struct TodoIndexer {
+    state: RwLock<IndexState>,
+    websocket_url: String,
+    commitment: CommitmentConfig,
+    resync_interval: Duration,
+    max_slot_lag: u64,
+    // Changes are copied to the repository when set
+    writer: Option<RepositoryWriter>,
+    // Set when a write failed, so the next scan replaces the stored copy whole
+    store_stale: Arc<AtomicBool>,
+}
+
+impl TodoIndexer {
+    pub fn new(
+        websocket_url: String,
+        commitment: CommitmentConfig,
+        resync_interval: Duration,
+        max_slot_lag: u64,
+    ) -> Self {
+        Self {
+            state: RwLock::new(IndexState::default()),
+            websocket_url,
+            commitment,
+            resync_interval,
+            max_slot_lag,
+            writer: None,
+            store_stale: Arc::new(AtomicBool::new(false)),
+        }
+    }
+
+    // Starts from the stored copy, so the first scan only writes what changed
+    // while the server was down. Reads still wait for that scan.
+    pub fn with_repository(self, repository: Arc<dyn TodoRepository>) -> Self {
+        match repository.todos() {
+            Ok(stored) => {
+                log::info!("Loaded {} stored todo accounts", stored.len());
+                self.write().todos = stored
+                    .into_iter()
+                    .map(|stored| (stored.address, IndexedTodo { slot: stored.slot, todo: stored.todo }))
+                    .collect();
+            }
+            Err(e) => {
+                log::warn!("Failed to load stored todos: {}", e);
+                self.store_stale.store(true, Ordering::Relaxed);
+            }
+        }
+
+        Self {
+            writer: Some(RepositoryWriter::spawn(repository)),
+            ..self
+        }
+    }
+
+    pub fn from_config(config: &Config) -> Result<Self, ApiError> {
+        let websocket_url = match &config.solana_ws_url {
+            Some(url) => url.clone(),
+            None => config
+                .solana_rpc_urls
+                .first()
+                .and_then(|url| websocket_url(url))
+                .ok_or_else(|| {
+                    ApiError::InternalServerError("SOLANA_WS_URL is required for the indexer".to_string())
+                })?,
+        };
+
+        Ok(Self::new(
+            websocket_url,
+            config.commitment,
+            Duration::from_secs(config.indexer_resync_seconds.max(1)),
+            config.indexer_max_slot_lag,
+        ))
+    }
+
+    // Only reads at this commitment are served from the index
+    pub fn commitment(&self) -> CommitmentConfig {
+        self.commitment
+    }
+
+    // Every update leaves the index whole, so a poisoned lock is still consistent
+    fn read(&self) -> RwLockReadGuard<'_, IndexState> {
+        self.state.read().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    fn write(&self) -> RwLockWriteGuard<'_, IndexState> {
+        self.state.write().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    // The owner's todos and the slot the index is current to, None while it isn't
+    pub fn todos_for(&self, owner: &Pubkey) -> Option<(Vec<TodoResponse>, u64)> {
+        let state = self.read();
+        let slot = state.indexed_slot?;
+
+        let mut todos: Vec<TodoResponse> = state
+            .todos
+            .values()
+            .filter(|indexed| indexed.todo.owner == *owner)
+            .map(|indexed| todo_response(indexed.todo.clone(), None))
+            .collect();
+        todos.sort_by_key(|todo| todo.task_id);
+
+        Some((todos, slot))
+    }
+
+    // Swap in the result of a full scan taken at `slot`
+    pub fn replace(&self, slot: u64, todos: Vec<(Pubkey, TodoAccount)>) {
+        let todos: HashMap<Pubkey, IndexedTodo> = todos
+            .into_iter()
+            .map(|(address, todo)| (address, IndexedTodo { slot, todo }))
+            .collect();
+
+        // Writes are queued under the lock, so they reach the store in the index's order
+        let mut state = self.write();
+
+        if let Some(writer) = &self.writer {
+            let stored = |address: &Pubkey, indexed: &IndexedTodo| StoredTodo {
+                address: *address,
+                slot,
+                todo: indexed.todo.clone(),
+            };
+            let store_stale = self.store_stale.clone();
+
+            if self.store_stale.swap(false, Ordering::Relaxed) {
+                let all: Vec<StoredTodo> = todos.iter().map(|(address, indexed)| stored(address, indexed)).collect();
+                writer.write(move |repository| persist(&store_stale, repository.replace_todos(&all)));
+            } else {
+                // Most accounts are unchanged between scans, so only the difference is written
+                let changed: Vec<StoredTodo> = todos
+                    .iter()
+                    .filter(|(address, indexed)| {
+                        state.todos.get(address).is_none_or(|current| current.todo != indexed.todo)
+                    })
+                    .map(|(address, indexed)| stored(address, indexed))
+                    .collect();
+                let removed: Vec<Pubkey> = state
+                    .todos
+                    .keys()
+                    .filter(|address| !todos.contains_key(address))
+                    .copied()
+                    .collect();
+
+                if !changed.is_empty() || !removed.is_empty() {
+                    writer.write(move |repository| persist(&store_stale, repository.sync_todos(&changed, &removed)));
+                }
+            }
+        }
+
+        state.todos = todos;
+        state.indexed_slot = Some(state.indexed_slot.map_or(slot, |indexed| indexed.max(slot)));
+        state.websocket_slot = state.websocket_slot.max(slot);
+    }
+
+    // Record an account as seen at `slot`. Anything that no longer decodes as a
+    // todo, like a closed account, leaves the index.
+    pub fn update(&self, idl: &Idl, address: &Pubkey, slot: u64, data: &[u8]) {
+        let mut state = self.write();
+
+        // Queued notifications can trail a scan that already saw a newer state
+        if state.todos.get(address).is_some_and(|indexed| indexed.slot > slot) {
+            return;
+        }
+
+        match decode_todo(idl, address, data) {
+            Ok(todo) => {
+                let stored = StoredTodo {
+                    address: *address,
+                    slot,
+                    todo: todo.clone(),
+                };
+                self.store(move |repository| repository.upsert_todo(&stored));
+                state.todos.insert(*address, IndexedTodo { slot, todo });
+            }
+            Err(_) => {
+                let address = *address;
+                self.store(move |repository| repository.remove_todo(&address));
+                state.todos.remove(&address);
+            }
+        }
+        state.indexed_slot = state.indexed_slot.map(|indexed| indexed.max(slot));
+    }
+
+    // Closed accounts aren't notified, so deletes made here are applied directly
+    pub fn forget(&self, address: &Pubkey) {
+        let mut state = self.write();
+        let forgotten = *address;
+        self.store(move |repository| repository.remove_todo(&forgotten));
+        state.todos.remove(address);
+    }
+
+    // Queue a write of one account's change, if there's a repository
+    fn store(&self, write: impl FnOnce(&dyn TodoRepository) -> Result<(), ApiError> + Send + 'static) {
+        if let Some(writer) = &self.writer {
+            let store_stale = self.store_stale.clone();
+            writer.write(move |repository| persist(&store_stale, write(repository)));
+        }
+    }
+
+    // Stop serving reads until the next full scan
+    pub fn invalidate(&self) {
+        let mut state = self.write();
+        state.indexed_slot = None;
+        state.pending_slot = None;
+    }
+
+    pub fn spawn(self: Arc<Self>, solana_service: SolanaService) {
+        tokio::spawn(async move {
+            loop {
+                if let Err(e) = self.follow(&solana_service).await {
+                    log::warn!("Todo indexer lost sync: {}", e);
+                }
+                // Reads go to the RPC until the index is rebuilt
+                self.invalidate();
+                tokio::time::sleep(RECONNECT_DELAY).await;
+            }
+        });
+    }
+
+    // Subscribe, backfill, then apply notifications until the subscription fails
+    async fn follow(&self, solana_service: &SolanaService) -> Result<(), ApiError> {
+        let failed = |e: PubsubClientError| ApiError::SolanaError(format!("Subscription failed: {}", e));
+        let client = PubsubClient::new(&self.websocket_url).await.map_err(failed)?;
+
+        let config = RpcProgramAccountsConfig {
+            // Unfiltered, so an account that stops being a todo is seen too
+            filters: None,
+            account_config: RpcAccountInfoConfig {
+                encoding: Some(UiAccountEncoding::Base64),
+                commitment: Some(self.commitment),
+                ..RpcAccountInfoConfig::default()
+            },
+            ..RpcProgramAccountsConfig::default()
+        };
+        let (mut accounts, _) = client
+            .program_subscribe(&solana_service.program_id, Some(config))
+            .await
+            .map_err(failed)?;
+        let (mut slots, _) = client.slot_subscribe().await.map_err(failed)?;
+
+        // Subscribed first, so changes made during the scan are queued rather than missed
+        self.backfill(solana_service).await?;
+
+        let mut heartbeat = tokio::time::interval(HEARTBEAT_INTERVAL);
+        let mut resync = tokio::time::interval(self.resync_interval);
+        // Both tick immediately, and the scan has just run
+        heartbeat.tick().await;
+        resync.tick().await;
+
+        loop {
+            tokio::select! {
+                notification = accounts.next() => {
+                    let notification = notification
+                        .ok_or_else(|| ApiError::SolanaError("Account subscription closed".to_string()))?;
+                    self.apply(&solana_service.idl, notification);
+                }
+                slot = slots.next() => {
+                    let slot = slot
+                        .ok_or_else(|| ApiError::SolanaError("Slot subscription closed".to_string()))?;
+                    self.write().websocket_slot = slot.slot;
+                }
+                _ = heartbeat.tick() => self.heartbeat(solana_service).await?,
+                _ = resync.tick() => self.backfill(solana_service).await?,
+            }
+        }
+    }
+
+    async fn backfill(&self, solana_service: &SolanaService) -> Result<(), ApiError> {
+        // Taken before the scan, so the scan is at least as new as this slot
+        let slot = solana_service.slot().await?;
+        let todos = solana_service.program_todos().await?;
+
+        log::info!("Indexed {} todo accounts at slot {}", todos.len(), slot);
+        self.replace(slot, todos.into_iter().map(|(address, _, todo)| (address, todo)).collect());
+
+        Ok(())
+    }
+
+    fn apply(&self, idl: &Idl, notification: RpcResponse<RpcKeyedAccount>) {
+        let address = match Pubkey::from_str(&notification.value.pubkey) {
+            Ok(address) => address,
+            Err(_) => return,
+        };
+        let data = notification
+            .value
+            .account
+            .decode::<Account>()
+            .map(|account| account.data)
+            .unwrap_or_default();
+
+        self.update(idl, &address, notification.context.slot, &data);
+    }
+
+    // Commit the slot seen at the last heartbeat, whose notifications have had a full
+    // interval to arrive, after checking the websocket is keeping up with the cluster.
+    // A websocket that falls behind has missed notifications, so it's a gap.
+    async fn heartbeat(&self, solana_service: &SolanaService) -> Result<(), ApiError> {
+        let slot = solana_service.slot().await?;
+        let mut state = self.write();
+
+        let lag = slot.saturating_sub(state.websocket_slot);
+        if lag > self.max_slot_lag {
+            return Err(ApiError::SolanaError(format!("Subscription is {} slots behind", lag)));
+        }
+
+        if let (Some(indexed), Some(pending)) = (state.indexed_slot, state.pending_slot) {
+            state.indexed_slot = Some(indexed.max(pending));
+        }
+        state.pending_slot = Some(slot);
+
+        Ok(())
+    }
+}
+
+// The stored copy is replaced whole by the next full scan, so failures only log
+fn persist(store_stale: &AtomicBool, result: Result<(), ApiError>) {
+    if let Err(e) = result {
+        log::warn!("Failed to store indexed todos: {}", e);
+        store_stale.store(true, Ordering::Relaxed);
+    }
+}
+
+// The websocket URL of an RPC endpoint (`url` or `url|weight`), which Solana nodes
+// serve on the same host
+pub fn websocket_url(rpc_url: &str) -> Option<String> {
+    let url = rpc_url.rsplit_once('|').map_or(rpc_url, |(url, _)| url).trim();
+
+    if let Some(rest) = url.strip_prefix("https://") {
+        return Some(format!("wss://{}", rest));
+    }
+    url.strip_prefix("http://").map(|rest| format!("ws://{}", rest))
+}
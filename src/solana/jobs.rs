@@ -0,0 +1,301 @@
+// src/solana/jobs.rs
+use super::priority_fee::PriorityOverride;
+use super::SolanaService;
+use crate::auth::random_token;
+use crate::config::Config;
+use crate::errors::ApiError;
+use crate::models::job::{JobResponse, JobStatus};
+use crate::models::todo::BatchOperation;
+use crate::repository::{self, RepositoryWriter, TodoRepository};
+use crate::signer::TransactionSigner;
+use actix_web::http::header::HeaderMap;
+use actix_web::web;
+use chrono::Utc;
+use solana_sdk::commitment_config::CommitmentConfig;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::{Duration, Instant};
+use tokio::sync::mpsc;
+
+pub const PREFER_HEADER: &str = "Prefer";
+pub const PREFERENCE_APPLIED_HEADER: &str = "Preference-Applied";
+pub const RESPOND_ASYNC: &str = "respond-async";
+
+const JOB_ID_BYTES: usize = 16;
+
+// `Prefer: respond-async`, possibly alongside other preferences
+pub fn prefers_async(headers: &HeaderMap) -> bool {
+    headers
+        .get_all(PREFER_HEADER)
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(','))
+        .any(|preference| {
+            let token = preference.split([';', '=']).next().unwrap_or_default();
+            token.trim().eq_ignore_ascii_case(RESPOND_ASYNC)
+        })
+}
+
+struct QueuedJob {
+    id: String,
+    wallet: String,
+    operation: BatchOperation,
+    priority: Option<PriorityOverride>,
+    commitment: Option<CommitmentConfig>,
+}
+
+struct JobRecord {
+    wallet: String,
+    job: JobResponse,
+    finished_at: Option<Instant>,
+}
+
+// Writes accepted with `202 Accepted` and sent by background workers. Queued
+// operations are held in memory; with a repository, job records are also saved
+// so they can still be looked up after a restart.
+pub struct JobQueue {
+    jobs: Mutex<HashMap<String, JobRecord>>,
+    sender: mpsc::Sender<QueuedJob>,
+    // Taken by the workers when they start
+    receiver: Mutex<Option<mpsc::Receiver<QueuedJob>>>,
+    retention: Duration,
+    repository: Option<Arc<dyn TodoRepository>>,
+    // Saves go through here, in order and off the async workers
+    writer: Option<RepositoryWriter>,
+}
+
+impl JobQueue {
+    pub fn new(capacity: usize, retention: Duration) -> Self {
+        let (sender, receiver) = mpsc::channel(capacity.max(1));
+
+        Self {
+            jobs: Mutex::new(HashMap::new()),
+            sender,
+            receiver: Mutex::new(Some(receiver)),
+            retention,
+            repository: None,
+            writer: None,
+        }
+    }
+
+    pub fn with_repository(self, repository: Arc<dyn TodoRepository>) -> Self {
+        // Operations queued by a previous run were lost with it, so their jobs never finish
+        match repository.fail_unfinished_jobs("Interrupted by a server restart") {
+            Ok(0) => {}
+            Ok(count) => log::warn!("Marked {} unfinished jobs from a previous run as failed", count),
+            Err(e) => log::warn!("Failed to check for unfinished jobs: {}", e),
+        }
+
+        Self {
+            writer: Some(RepositoryWriter::spawn(repository.clone())),
+            repository: Some(repository),
+            ..self
+        }
+    }
+
+    pub fn from_config(config: &Config) -> Self {
+        Self::new(
+            config.job_queue_capacity,
+            Duration::from_secs(config.job_retention_seconds),
+        )
+    }
+
+    fn lock(&self) -> MutexGuard<'_, HashMap<String, JobRecord>> {
+        // Each update leaves a record whole, so a poisoned map is still consistent
+        self.jobs.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    pub fn enqueue(
+        &self,
+        wallet: &str,
+        operation: BatchOperation,
+        priority: Option<PriorityOverride>,
+        commitment: Option<CommitmentConfig>,
+    ) -> Result<JobResponse, ApiError> {
+        let now = Utc::now().timestamp();
+        let job = JobResponse {
+            id: random_token(JOB_ID_BYTES),
+            status: JobStatus::Queued,
+            operation: operation_name(&operation).to_string(),
+            task_id: operation.task_id(),
+            created_at: now,
+            updated_at: now,
+            todo: None,
+            transaction: None,
+            error: None,
+        };
+
+        {
+            let mut jobs = self.lock();
+            // Drop finished jobs past retention so the map doesn't grow without bound
+            jobs.retain(|_, record| {
+                record.finished_at.is_none_or(|finished| finished.elapsed() < self.retention)
+            });
+            jobs.insert(
+                job.id.clone(),
+                JobRecord {
+                    wallet: wallet.to_string(),
+                    job: job.clone(),
+                    finished_at: None,
+                },
+            );
+        }
+        if let Some(writer) = &self.writer {
+            let cutoff = now - self.retention.as_secs() as i64;
+            writer.write(move |repository| {
+                if let Err(e) = repository.prune_jobs(cutoff) {
+                    log::warn!("Failed to prune saved jobs: {}", e);
+                }
+            });
+        }
+        // Saved before a worker can pick it up, so a later update isn't overwritten
+        self.save(wallet, &job);
+
+        let queued = QueuedJob {
+            id: job.id.clone(),
+            wallet: wallet.to_string(),
+            operation,
+            priority,
+            commitment,
+        };
+        if let Err(e) = self.sender.try_send(queued) {
+            self.lock().remove(&job.id);
+            let error = match e {
+                mpsc::error::TrySendError::Full(_) => {
+                    ApiError::ServiceUnavailable("Job queue is full, try again later".to_string())
+                }
+                mpsc::error::TrySendError::Closed(_) => {
+                    ApiError::InternalServerError("Job workers are not running".to_string())
+                }
+            };
+            self.save(wallet, &JobResponse {
+                status: JobStatus::Failed,
+                error: Some(error.to_string()),
+                ..job
+            });
+            return Err(error);
+        }
+
+        Ok(job)
+    }
+
+    // Jobs are only visible to the wallet that queued them
+    pub async fn get(&self, wallet: &str, id: &str) -> Result<JobResponse, ApiError> {
+        let job = self
+            .lock()
+            .get(id)
+            .filter(|record| record.wallet == wallet)
+            .map(|record| record.job.clone());
+
+        // Saved jobs cover those from before a restart
+        let job = match (job, &self.repository) {
+            (Some(job), _) => Some(job),
+            (None, Some(repository)) => {
+                let (wallet, id) = (wallet.to_string(), id.to_string());
+                repository::run_blocking(repository, move |repository| repository.job(&wallet, &id)).await?
+            }
+            (None, None) => None,
+        };
+
+        job.ok_or_else(|| ApiError::NotFound(format!("Job {} not found", id)))
+    }
+
+    fn update(&self, id: &str, apply: impl FnOnce(&mut JobResponse)) {
+        let updated = self.lock().get_mut(id).map(|record| {
+            apply(&mut record.job);
+            record.job.updated_at = Utc::now().timestamp();
+            if matches!(record.job.status, JobStatus::Confirmed | JobStatus::Failed) {
+                record.finished_at = Some(Instant::now());
+            }
+            (record.wallet.clone(), record.job.clone())
+        });
+
+        if let Some((wallet, job)) = updated {
+            self.save(&wallet, &job);
+        }
+    }
+
+    // A job that can't be saved is still tracked in memory, so this only logs
+    fn save(&self, wallet: &str, job: &JobResponse) {
+        if let Some(writer) = &self.writer {
+            let (wallet, job) = (wallet.to_string(), job.clone());
+            writer.write(move |repository| {
+                if let Err(e) = repository.save_job(&wallet, &job) {
+                    log::warn!("Failed to save job {}: {}", job.id, e);
+                }
+            });
+        }
+    }
+
+    pub fn spawn_workers(
+        self: Arc<Self>,
+        workers: usize,
+        solana_service: web::Data<SolanaService>,
+        signer: web::Data<dyn TransactionSigner>,
+    ) {
+        let receiver = match self.receiver.lock().unwrap_or_else(|poisoned| poisoned.into_inner()).take() {
+            Some(receiver) => Arc::new(tokio::sync::Mutex::new(receiver)),
+            None => {
+                log::warn!("Job workers already started");
+                return;
+            }
+        };
+
+        for _ in 0..workers.max(1) {
+            let queue = self.clone();
+            let receiver = receiver.clone();
+            let solana_service = solana_service.clone();
+            let signer = signer.clone();
+
+            tokio::spawn(async move {
+                loop {
+                    let next = receiver.lock().await.recv().await;
+                    match next {
+                        Some(job) => queue.run(&solana_service, signer.get_ref(), job).await,
+                        None => break,
+                    }
+                }
+            });
+        }
+    }
+
+    async fn run(&self, solana_service: &SolanaService, signer: &dyn TransactionSigner, job: QueuedJob) {
+        self.update(&job.id, |record| record.status = JobStatus::Processing);
+        let solana_service = solana_service.with_commitment(job.commitment);
+
+        let result = match job.operation {
+            BatchOperation::Create(request) => solana_service
+                .create_todo(signer, &job.wallet, request, job.priority)
+                .await
+                .map(|mut todo| (todo.transaction.take(), Some(todo))),
+            BatchOperation::Update { task_id, changes } => solana_service
+                .update_todo(signer, &job.wallet, task_id, changes, job.priority)
+                .await
+                .map(|mut todo| (todo.transaction.take(), Some(todo))),
+            BatchOperation::Delete { task_id } => solana_service
+                .delete_todo(signer, &job.wallet, task_id, job.priority)
+                .await
+                .map(|receipt| (Some(receipt), None)),
+        };
+
+        self.update(&job.id, |record| match result {
+            Ok((transaction, todo)) => {
+                record.status = JobStatus::Confirmed;
+                record.transaction = transaction;
+                record.todo = todo;
+            }
+            Err(e) => {
+                log::warn!("Job {} failed: {}", record.id, e);
+                record.status = JobStatus::Failed;
+                record.error = Some(e.to_string());
+            }
+        });
+    }
+}
+
+fn operation_name(operation: &BatchOperation) -> &'static str {
+    match operation {
+        BatchOperation::Create(_) => "create",
+        BatchOperation::Update { .. } => "update",
+        BatchOperation::Delete { .. } => "delete",
+    }
+}
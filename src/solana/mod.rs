@@ -1,304 +1,1480 @@
 // src/solana/mod.rs
 use crate::config::Config;
 use crate::errors::ApiError;
-use crate::models::todo::{CreateTodoRequest, TodoResponse, UpdateTodoRequest};
-use anchor_client::{
-    solana_sdk::{
-        commitment_config::CommitmentConfig,
-        pubkey::Pubkey,
-        signature::{Keypair, Signer},
-        system_program,
-    },
-    Client, Cluster, Program,
+use crate::models::todo::{
+    BatchOperation, BatchOperationResult, BatchResponse, CreateTodoRequest, MigrationResponse,
+    TodoMigration, TodoResponse, UpdateTodoRequest,
+};
+use crate::models::transaction::{
+    SendAttempt, SimulationResponse, TransactionReceipt, TransactionStatusResponse,
+    UnsignedTransactionResponse,
+};
+use crate::signer::{sign_transaction, TransactionSigner};
+use idl::Idl;
+use indexer::TodoIndexer;
+use priority_fee::{
+    requested_budget, ComputeBudget, PriorityFees, PriorityOverride, MAX_COMPUTE_UNIT_LIMIT,
+};
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use relayer::Relayer;
+use serde_json::{json, Map, Value};
+use retry::{RetryPolicy, SendFailure};
+use rpc_pool::RpcPool;
+use solana_account_decoder::UiAccountEncoding;
+use solana_client::client_error::ClientError;
+use solana_client::rpc_request::RpcError;
+use solana_client::rpc_config::{
+    RpcAccountInfoConfig, RpcProgramAccountsConfig, RpcSimulateTransactionAccountsConfig,
+    RpcSimulateTransactionConfig, RpcTransactionConfig,
+};
+use solana_client::rpc_filter::{Memcmp, RpcFilterType};
+use solana_sdk::{
+    commitment_config::CommitmentConfig,
+    compute_budget,
+    instruction::{CompiledInstruction, Instruction},
+    hash::Hash,
+    message::Message,
+    packet::PACKET_DATA_SIZE,
+    pubkey::Pubkey,
+    signature::Signature,
+    system_instruction::{self, SystemInstruction},
+    system_program,
+    transaction::Transaction,
+};
+use solana_transaction_status::{
+    TransactionConfirmationStatus, TransactionStatus, UiTransactionEncoding,
 };
 use std::str::FromStr;
 use std::sync::Arc;
+use std::time::Duration;
+
+pub mod relayer;
+pub mod commitment;
+pub mod idl;
+pub mod indexer;
+pub mod jobs;
+pub mod priority_fee;
+pub mod program_error;
+pub mod retry;
+pub mod rpc_pool;
+pub mod simulation;
+
+pub const MAX_DESCRIPTION_LEN: usize = 280;
+
+// Space allocated for a todo account in the original layout: discriminator, then
+// each field at its largest
+pub const TODO_ACCOUNT_SPACE: usize = 8 + 8 + (4 + MAX_DESCRIPTION_LEN) + 1 + 8 + 32;
+
+// The todo account type in the program's IDL
+pub const TODO_ACCOUNT: &str = "TodoAccount";
+
+// An instruction the API builds: name, accounts, and arguments with their IDL types
+type InstructionSpec = (&'static str, &'static [&'static str], &'static [(&'static str, &'static str)]);
+
+const REQUIRED_INSTRUCTIONS: &[InstructionSpec] = &[
+    (
+        "create_todo",
+        &["todo", "owner", "system_program"],
+        &[("task_id", "u64"), ("description", "string"), ("due_date", "i64")],
+    ),
+    ("update_description", &["todo", "owner"], &[("description", "string")]),
+    ("toggle_completed", &["todo", "owner"], &[]),
+    ("delete_todo", &["todo", "owner"], &[]),
+];
+
+// Reallocates a todo account to the current layout. Only needed once the program
+// has appended fields, so it's checked when a migration is asked for, not at startup.
+const MIGRATE_INSTRUCTION: InstructionSpec =
+    ("migrate_todo", &["todo", "owner", "system_program"], &[]);
+
+// Todo account layout versions. The original layout is the fields below in
+// `TODO_ACCOUNT_SPACE`; the extended one is an IDL layout that appends fields to
+// them. Accounts keep the space they were created with until migrated, so their
+// length tells the two apart.
+pub const ORIGINAL_LAYOUT: u8 = 1;
+pub const EXTENDED_LAYOUT: u8 = 2;
 
+// Fields of the original todo account, in layout order
+const TODO_ACCOUNT_FIELDS: &[(&str, &str)] = &[
+    ("task_id", "u64"),
+    ("description", "string"),
+    ("completed", "bool"),
+    ("due_date", "i64"),
+    ("owner", "publicKey"),
+];
+
+const CONFIRMATION_POLL_INTERVAL: Duration = Duration::from_millis(500);
+// How often a failed sponsored send is checked for whether it can still land
+const REFUND_POLL_INTERVAL: Duration = Duration::from_secs(5);
+
+// Built once at startup and shared by every handler. All RPC goes through the
+// pool's nonblocking clients so slow calls never hold up an actix worker thread.
+#[derive(Clone)]
 pub struct SolanaService {
-    program: Program,
+    rpc: Arc<RpcPool>,
+    idl: Arc<Idl>,
+    // What reads see and how far sends wait; the pool's unless a request chose another
+    commitment: CommitmentConfig,
     program_id: Pubkey,
+    // Layout version and space of todo accounts the program creates now
+    layout_version: u8,
+    todo_space: usize,
+    relayer: Option<Arc<Relayer>>,
+    // Serves list reads when running
+    indexer: Option<Arc<TodoIndexer>>,
+    retry: RetryPolicy,
+    priority_fees: PriorityFees,
+    batch_units_per_operation: u32,
+}
+
+// An unsigned transaction ready for signing
+pub struct BuiltTransaction {
+    pub transaction: Transaction,
+    // The last block height at which the blockhash is still valid
+    pub last_valid_block_height: u64,
+    pub compute_budget: ComputeBudget,
+}
+
+// What's needed to rebuild and re-sign a custodial operation with a fresh blockhash
+struct CustodialSigning<'a> {
+    signer: &'a dyn TransactionSigner,
+    wallet: &'a str,
+    owner: &'a Pubkey,
+    instructions: &'a [Instruction],
+    priority: Option<PriorityOverride>,
+    // Overrides the configured compute unit limit
+    compute_unit_limit: Option<u32>,
 }
 
 impl SolanaService {
-    pub fn new(config: &Config) -> Result<Self, ApiError> {
+    pub fn new(
+        config: &Config,
+        rpc: Arc<RpcPool>,
+        idl: Arc<Idl>,
+        relayer: Option<Arc<Relayer>>,
+    ) -> Result<Self, ApiError> {
         let program_id = Pubkey::from_str(&config.program_id)
             .map_err(|_| ApiError::BadRequest("Invalid program ID".to_string()))?;
+        check_idl(&idl)?;
+        let (layout_version, todo_space) = current_layout(&idl).map_err(ApiError::InternalServerError)?;
 
-        // Use a dummy keypair just for reading data
-        // For write operations, we'll use the user's keypair
-        let payer = Arc::new(Keypair::new());
+        // Transactions are signed separately by the caller, a custodial signer or the relayer
+        Ok(SolanaService {
+            commitment: rpc.commitment(),
+            rpc,
+            idl,
+            program_id,
+            layout_version,
+            todo_space,
+            relayer,
+            indexer: None,
+            retry: RetryPolicy::from_config(config),
+            priority_fees: PriorityFees::from_config(config)?,
+            batch_units_per_operation: config.batch_compute_units_per_operation,
+        })
+    }
+    
+    // The same service reading and confirming at a commitment a request asked for
+    pub fn with_commitment(&self, commitment: Option<CommitmentConfig>) -> Self {
+        SolanaService {
+            commitment: commitment.unwrap_or(self.commitment),
+            ..self.clone()
+        }
+    }
+    
+    pub fn with_indexer(self, indexer: Arc<TodoIndexer>) -> Self {
+        SolanaService {
+            indexer: Some(indexer),
+            ..self
+        }
+    }
+    
+    // Find the PDA for a todo account
+    pub fn todo_address(&self, owner: &Pubkey, task_id: u64) -> Pubkey {
+        let seeds = [
+            b"todo".as_ref(),
+            owner.as_ref(),
+            &task_id.to_le_bytes(),
+        ];
         
-        let cluster = Cluster::Custom(
-            config.solana_rpc_url.clone(),
-            config.solana_rpc_url.clone(),
+        let (todo_account, _) = Pubkey::find_program_address(
+            &seeds,
+            &self.program_id,
         );
         
-        let client = Client::new_with_options(
-            cluster,
-            payer,
-            CommitmentConfig::confirmed(),
+        todo_account
+    }
+    
+    // An instruction for the todo program, built from its IDL
+    fn instruction(&self, name: &str, accounts: &[(&str, Pubkey)], args: Value) -> Result<Instruction, ApiError> {
+        self.idl.instruction(&self.program_id, name, accounts, &args)
+    }
+    
+    async fn fetch_todo(
+        &self,
+        todo_account: &Pubkey,
+        task_id: u64,
+    ) -> Result<TodoAccount, ApiError> {
+        let commitment = self.commitment;
+        let account = self.rpc
+            .read(|rpc| async move { rpc.get_account_with_commitment(todo_account, commitment).await })
+            .await?
+            .value
+            .ok_or_else(|| ApiError::NotFound(format!("Todo with ID {} not found", task_id)))?;
+        
+        decode_todo(&self.idl, todo_account, &account.data)
+    }
+    
+    // The wallet's todos, and the indexed slot when they came from the indexer
+    pub async fn get_todos_for_wallet(&self, wallet_pubkey: &str) -> Result<(Vec<TodoResponse>, Option<u64>), ApiError> {
+        let wallet = Pubkey::from_str(wallet_pubkey)
+            .map_err(|_| ApiError::BadRequest("Invalid wallet address".to_string()))?;
+        
+        // The index follows the configured commitment; reads at another level scan the RPC
+        if let Some(indexer) = self.indexer.as_ref().filter(|indexer| indexer.commitment() == self.commitment) {
+            if let Some((todos, slot)) = indexer.todos_for(&wallet) {
+                return Ok((todos, Some(slot)));
+            }
+        }
+        
+        let todos = self
+            .todo_accounts(&wallet)
+            .await?
+            .into_iter()
+            .map(|(_, _, todo)| todo_response(todo, None))
+            .collect();
+        
+        Ok((todos, None))
+    }
+    
+    // The owner's todo accounts: address, lamports held and the decoded todo
+    async fn todo_accounts(&self, owner: &Pubkey) -> Result<Vec<(Pubkey, u64, TodoAccount)>, ApiError> {
+        let mut todos = self.scan_todos(Some(owner)).await?;
+        todos.retain(|(_, _, todo)| todo.owner == *owner);
+        
+        Ok(todos)
+    }
+    
+    // Every todo account of the program
+    async fn program_todos(&self) -> Result<Vec<(Pubkey, u64, TodoAccount)>, ApiError> {
+        self.scan_todos(None).await
+    }
+    
+    // Accounts that fail to decode are skipped and logged, so one bad account
+    // doesn't hide everyone's todos. The exception is an account of `owner`,
+    // whose todos would silently go missing.
+    async fn scan_todos(&self, owner: Option<&Pubkey>) -> Result<Vec<(Pubkey, u64, TodoAccount)>, ApiError> {
+        // There is no owner memcmp filter: Anchor stores the description as a length
+        // prefix plus its bytes, unpadded, so the owner's offset (8 + 8 + 4 + len + 1 + 8)
+        // differs per account. A filter at the offset of a full-length description
+        // only matched todos with exactly MAX_DESCRIPTION_LEN bytes of description.
+        // Every todo account is fetched by discriminator and the owner matched once
+        // decoded; the indexer keeps this scan off the read path.
+        let config = RpcProgramAccountsConfig {
+            filters: Some(vec![RpcFilterType::Memcmp(Memcmp::new_raw_bytes(
+                0,
+                self.idl.account_discriminator(TODO_ACCOUNT)?.to_vec(),
+            ))]),
+            account_config: RpcAccountInfoConfig {
+                encoding: Some(UiAccountEncoding::Base64),
+                commitment: Some(self.commitment),
+                ..RpcAccountInfoConfig::default()
+            },
+            ..RpcProgramAccountsConfig::default()
+        };
+        
+        let accounts = self.rpc
+            .read(|rpc| {
+                let config = config.clone();
+                async move { rpc.get_program_accounts_with_config(&self.program_id, config).await }
+            })
+            .await?;
+        
+        let mut todos = Vec::with_capacity(accounts.len());
+        for (address, account) in accounts {
+            match decode_todo(&self.idl, &address, &account.data) {
+                Ok(todo) => todos.push((address, account.lamports, todo)),
+                Err(e) if owner.is_some() && raw_owner(&account.data).as_ref() == owner => return Err(e),
+                Err(e) => log::warn!("Skipping todo account: {}", e),
+            }
+        }
+        
+        Ok(todos)
+    }
+    
+    async fn slot(&self) -> Result<u64, ApiError> {
+        let commitment = self.commitment;
+        let slot = self.rpc
+            .read(|rpc| async move { rpc.get_slot_with_commitment(commitment).await })
+            .await?;
+        
+        Ok(slot)
+    }
+    
+    // Drop a deleted todo from the index, which isn't notified of closed accounts
+    fn forget_todo(&self, todo_account: &Pubkey) {
+        if let Some(indexer) = &self.indexer {
+            indexer.forget(todo_account);
+        }
+    }
+    
+    // Build phase: instructions for each operation, owned by `owner`.
+    // Nothing here signs or sends; callers decide who signs.
+    
+    pub async fn create_todo_instructions(
+        &self,
+        owner: &Pubkey,
+        request: &CreateTodoRequest,
+    ) -> Result<Vec<Instruction>, ApiError> {
+        let todo_account = self.todo_address(owner, request.task_id);
+        let mut instructions = Vec::new();
+        
+        // With a relayer, fund the owner with the account's rent so it can pay for `init`.
+        // The rent goes back to the owner when the todo is deleted, which is why the
+        // relayer budgets lamports as well as transactions.
+        if let Some(relayer) = &self.relayer {
+            let rent = self.todo_rent().await?;
+            instructions.push(system_instruction::transfer(&relayer.pubkey(), owner, rent));
+        }
+        
+        instructions.push(self.instruction(
+            "create_todo",
+            &[("todo", todo_account), ("owner", *owner), ("system_program", system_program::ID)],
+            json!({
+                "task_id": request.task_id,
+                "description": request.description,
+                "due_date": request.due_date,
+            }),
+        )?);
+            
+        Ok(instructions)
+    }
+    
+    pub async fn update_todo_instructions(
+        &self,
+        owner: &Pubkey,
+        task_id: u64,
+        request: &UpdateTodoRequest,
+    ) -> Result<Vec<Instruction>, ApiError> {
+        let todo_account = self.todo_address(owner, task_id);
+        
+        // First, fetch the current state to verify the account exists
+        let todo = self.fetch_todo(&todo_account, task_id).await?;
+            
+        let mut instructions = Vec::new();
+        
+        // Handle description update if provided
+        if let Some(description) = &request.description {
+            instructions.push(self.instruction(
+                "update_description",
+                &[("todo", todo_account), ("owner", *owner)],
+                json!({ "description": description }),
+            )?);
+        }
+        
+        // Handle completed status update if provided
+        if let Some(completed) = request.completed {
+            if completed != todo.completed {
+                instructions.push(self.instruction(
+                    "toggle_completed",
+                    &[("todo", todo_account), ("owner", *owner)],
+                    json!({}),
+                )?);
+            }
+        }
+        
+        Ok(instructions)
+    }
+    
+    pub async fn delete_todo_instructions(
+        &self,
+        owner: &Pubkey,
+        task_id: u64,
+    ) -> Result<Vec<Instruction>, ApiError> {
+        let todo_account = self.todo_address(owner, task_id);
+        
+        // Check if the account exists
+        self.fetch_todo(&todo_account, task_id).await?;
+            
+        let instruction = self.instruction(
+            "delete_todo",
+            &[("todo", todo_account), ("owner", *owner)],
+            json!({}),
+        )?;
+            
+        Ok(vec![instruction])
+    }
+    
+    // Reallocate a todo account holding `lamports` to the current layout
+    pub async fn migrate_todo_instructions(
+        &self,
+        owner: &Pubkey,
+        todo_account: &Pubkey,
+        lamports: u64,
+    ) -> Result<Vec<Instruction>, ApiError> {
+        let mut instructions = Vec::new();
+        
+        // The larger account needs more rent, which the relayer forwards as it does on create
+        if let Some(relayer) = &self.relayer {
+            let shortfall = self.todo_rent().await?.saturating_sub(lamports);
+            if shortfall > 0 {
+                instructions.push(system_instruction::transfer(&relayer.pubkey(), owner, shortfall));
+            }
+        }
+        
+        instructions.push(self.instruction(
+            MIGRATE_INSTRUCTION.0,
+            &[("todo", *todo_account), ("owner", *owner), ("system_program", system_program::ID)],
+            json!({}),
+        )?);
+        
+        Ok(instructions)
+    }
+
+    // Wrap instructions in an unsigned transaction with a recent blockhash and a
+    // compute budget, paid for by the relayer when one is configured and by the
+    // owner otherwise. `priority` overrides the configured compute unit price.
+    pub async fn build_transaction(
+        &self,
+        owner: &Pubkey,
+        instructions: &[Instruction],
+        priority: Option<PriorityOverride>,
+    ) -> Result<BuiltTransaction, ApiError> {
+        self.build_transaction_with_limit(owner, instructions, priority, None).await
+    }
+    
+    // An unsigned transaction encoded for the wallet to sign
+    pub async fn unsigned_transaction(
+        &self,
+        owner: &Pubkey,
+        todo_account: Pubkey,
+        instructions: &[Instruction],
+        priority: Option<PriorityOverride>,
+    ) -> Result<UnsignedTransactionResponse, ApiError> {
+        let built = self.build_transaction(owner, instructions, priority).await?;
+        let transaction = built.transaction;
+        
+        let bytes = bincode::serialize(&transaction)
+            .map_err(|e| ApiError::InternalServerError(format!("Failed to serialize transaction: {}", e)))?;
+        
+        Ok(UnsignedTransactionResponse {
+            transaction: BASE64.encode(bytes),
+            todo_account: todo_account.to_string(),
+            recent_blockhash: transaction.message.recent_blockhash.to_string(),
+            last_valid_block_height: built.last_valid_block_height,
+            compute_unit_limit: built.compute_budget.unit_limit,
+            compute_unit_price: built.compute_budget.unit_price,
+        })
+    }
+
+    async fn build_transaction_with_limit(
+        &self,
+        owner: &Pubkey,
+        instructions: &[Instruction],
+        priority: Option<PriorityOverride>,
+        compute_unit_limit: Option<u32>,
+    ) -> Result<BuiltTransaction, ApiError> {
+        let payer = self.fee_payer(owner);
+        
+        // Price against recent fees for the accounts this transaction writes
+        let mut writable_accounts = vec![payer];
+        writable_accounts.extend(
+            instructions
+                .iter()
+                .flat_map(|instruction| &instruction.accounts)
+                .filter(|meta| meta.is_writable)
+                .map(|meta| meta.pubkey),
         );
+        writable_accounts.sort();
+        writable_accounts.dedup();
+        let mut compute_budget = self.priority_fees
+            .budget(&self.rpc, &writable_accounts, priority)
+            .await?;
+        if let Some(unit_limit) = compute_unit_limit {
+            compute_budget.unit_limit = unit_limit;
+        }
         
-        let program = client.program(program_id);
+        let mut all_instructions = compute_budget.instructions().to_vec();
+        all_instructions.extend_from_slice(instructions);
         
-        Ok(SolanaService {
-            program,
-            program_id,
+        let commitment = self.rpc.commitment();
+        let (recent_blockhash, last_valid_block_height) = self.rpc
+            .read(|rpc| async move { rpc.get_latest_blockhash_with_commitment(commitment).await })
+            .await?;
+        
+        let message = Message::new_with_blockhash(&all_instructions, Some(&payer), &recent_blockhash);
+        
+        Ok(BuiltTransaction {
+            transaction: Transaction::new_unsigned(message),
+            last_valid_block_height,
+            compute_budget,
         })
     }
     
-    pub fn get_todos_for_wallet(&self, wallet_pubkey: &str) -> Result<Vec<TodoResponse>, ApiError> {
-        let wallet = Pubkey::from_str(wallet_pubkey)
-            .map_err(|_| ApiError::BadRequest("Invalid wallet address".to_string()))?;
+    fn fee_payer(&self, owner: &Pubkey) -> Pubkey {
+        match &self.relayer {
+            Some(relayer) => relayer.pubkey(),
+            None => *owner,
+        }
+    }
+    
+    // Check a wallet-signed transaction only touches our program on the wallet's behalf
+    async fn verify_submitted_transaction(
+        &self,
+        transaction: &Transaction,
+        wallet: &Pubkey,
+    ) -> Result<(), ApiError> {
+        let account_keys = &transaction.message.account_keys;
+        
+        if account_keys.first() != Some(&self.fee_payer(wallet)) {
+            return Err(ApiError::Forbidden("Transaction has an unexpected fee payer".to_string()));
+        }
+        
+        if transaction.message.instructions.is_empty() {
+            return Err(ApiError::BadRequest("Transaction has no instructions".to_string()));
+        }
+        
+        // The compute unit price is paid by the fee payer, so it is capped like our own
+        let (_, unit_price) = requested_budget(transaction);
+        if unit_price.unwrap_or_default() > self.priority_fees.max_price() {
+            return Err(ApiError::BadRequest(format!(
+                "Compute unit price exceeds the maximum of {} micro-lamports",
+                self.priority_fees.max_price()
+            )));
+        }
+        
+        let instructions = &transaction.message.instructions;
+        let mut sponsored = false;
+        for (index, instruction) in instructions.iter().enumerate() {
+            if account_keys.get(instruction.program_id_index as usize) == Some(&compute_budget::ID) {
+                continue;
+            }
             
-        // We're using getProgramAccounts with memcmp filters
-        // This is a simplified approach - in a production app, you might want to use an indexer
-        let accounts = self.program.accounts(vec![
-            // Filter by owner field
-            anchor_client::solana_sdk::rpc_filter::RpcFilterType::Memcmp(
-                anchor_client::solana_sdk::rpc_filter::Memcmp {
-                    offset: 8 + 8 + 4 + 280 + 1 + 8, // Position of owner field
-                    bytes: anchor_client::solana_sdk::rpc_filter::MemcmpEncodedBytes::Base58(
-                        wallet.to_string()
-                    ),
-                    encoding: Some(
-                        anchor_client::solana_sdk::rpc_filter::MemcmpEncoding::Base58
-                    ),
+            // The only other foreign instruction allowed is the relayer's rent transfer:
+            // once, right before the create or migrate it funds, for exactly what we'd
+            // have forwarded ourselves
+            if let Some((to, lamports)) = self.sponsored_transfer(transaction, instruction) {
+                let expected = match instructions.get(index + 1) {
+                    Some(funded) => self.sponsored_rent(transaction, funded, wallet).await?,
+                    None => None,
+                };
+                if sponsored || to != *wallet || expected != Some(lamports) {
+                    return Err(ApiError::Forbidden("Transaction moves fee payer funds".to_string()));
                 }
-            ),
-        ])?;
-        
-        let mut todos = Vec::new();
-        
-        for (_, account) in accounts {
-            todos.push(TodoResponse {
-                task_id: account.task_id,
-                description: account.description,
-                completed: account.completed,
-                due_date: account.due_date,
-                owner: account.owner.to_string(),
+                sponsored = true;
+                continue;
+            }
+            
+            let program_id = account_keys.get(instruction.program_id_index as usize);
+            if program_id != Some(&self.program_id) {
+                return Err(ApiError::Forbidden("Transaction calls a program other than the todo program".to_string()));
+            }
+            
+            // Every todo instruction takes the owner as its second account
+            let owner = instruction
+                .accounts
+                .get(1)
+                .and_then(|&index| account_keys.get(index as usize));
+            if owner != Some(wallet) {
+                return Err(ApiError::Forbidden("Transaction acts on accounts of another wallet".to_string()));
+            }
+        }
+        
+        Ok(())
+    }
+    
+    // A system transfer out of the relayer's account, as (recipient, lamports)
+    fn sponsored_transfer(
+        &self,
+        transaction: &Transaction,
+        instruction: &CompiledInstruction,
+    ) -> Option<(Pubkey, u64)> {
+        let relayer = self.relayer.as_ref()?;
+        let account_keys = &transaction.message.account_keys;
+        
+        if account_keys.get(instruction.program_id_index as usize) != Some(&system_program::ID) {
+            return None;
+        }
+        
+        let lamports = match bincode::deserialize(&instruction.data) {
+            Ok(SystemInstruction::Transfer { lamports }) => lamports,
+            _ => return None,
+        };
+        
+        let from = account_keys.get(*instruction.accounts.first()? as usize)?;
+        let to = account_keys.get(*instruction.accounts.get(1)? as usize)?;
+        
+        if *from != relayer.pubkey() {
+            return None;
+        }
+        
+        Some((*to, lamports))
+    }
+    
+    // The rent the relayer forwards for a create or migrate of the wallet's todo,
+    // as `create_todo_instructions` and `migrate_todo_instructions` add it
+    async fn sponsored_rent(
+        &self,
+        transaction: &Transaction,
+        instruction: &CompiledInstruction,
+        wallet: &Pubkey,
+    ) -> Result<Option<u64>, ApiError> {
+        let account_keys = &transaction.message.account_keys;
+        let account = |position: usize| {
+            instruction
+                .accounts
+                .get(position)
+                .and_then(|&index| account_keys.get(index as usize))
+        };
+        
+        if account_keys.get(instruction.program_id_index as usize) != Some(&self.program_id)
+            || account(1) != Some(wallet)
+        {
+            return Ok(None);
+        }
+        
+        let todo_account = match account(0) {
+            Some(todo_account) => *todo_account,
+            None => return Ok(None),
+        };
+        // The program's IDL may not have the migrate instruction, which then never matches
+        let calls = |name: &str| {
+            self.idl
+                .instruction_discriminator(name)
+                .is_ok_and(|discriminator| instruction.data.starts_with(&discriminator))
+        };
+        
+        if calls("create_todo") {
+            return Ok(Some(self.todo_rent().await?));
+        }
+        
+        // Only the shortfall of an account that already holds rent, and nothing when it has enough
+        if calls(MIGRATE_INSTRUCTION.0) {
+            let commitment = self.commitment;
+            let lamports = self.rpc
+                .read(|rpc| async move { rpc.get_balance_with_commitment(&todo_account, commitment).await })
+                .await?
+                .value;
+            let shortfall = self.todo_rent().await?.saturating_sub(lamports);
+            return Ok((shortfall > 0).then_some(shortfall));
+        }
+        
+        Ok(None)
+    }
+    
+    async fn todo_rent(&self) -> Result<u64, ApiError> {
+        let rent = self.rpc
+            .read(|rpc| async move {
+                rpc.get_minimum_balance_for_rent_exemption(self.todo_space).await
+            })
+            .await?;
+        
+        Ok(rent)
+    }
+    
+    // Lamports the relayer spends on a transaction: its fee plus any rent it forwards
+    async fn sponsored_cost(&self, transaction: &Transaction) -> Result<u64, ApiError> {
+        let fee = self.rpc
+            .read(|rpc| async move { rpc.get_fee_for_message(&transaction.message).await })
+            .await?;
+        
+        let transfers: u64 = transaction
+            .message
+            .instructions
+            .iter()
+            .filter_map(|instruction| self.sponsored_transfer(transaction, instruction))
+            .map(|(_, lamports)| lamports)
+            .sum();
+        
+        Ok(fee + transfers)
+    }
+    
+    // Send phase: submit a fully signed transaction and wait until it reaches the
+    // service's commitment, or its blockhash expires without it landing
+    async fn send_transaction(&self, transaction: &Transaction) -> Result<Signature, ClientError> {
+        let commitment = self.commitment;
+        let blockhash = transaction.message.recent_blockhash;
+        
+        self.rpc
+            .write(|rpc| async move {
+                let signature = rpc.send_transaction(transaction).await?;
+                loop {
+                    match rpc.get_signature_status_with_commitment(&signature, commitment).await? {
+                        Some(Ok(())) => return Ok(signature),
+                        Some(Err(e)) => return Err(e.into()),
+                        None => {}
+                    }
+                    // Once landed it is only a matter of waiting for the commitment,
+                    // even if the blockhash expires in the meantime
+                    let landed = rpc
+                        .get_signature_status_with_commitment(&signature, CommitmentConfig::processed())
+                        .await?
+                        .is_some();
+                    if !landed && !rpc.is_blockhash_valid(&blockhash, CommitmentConfig::processed()).await? {
+                        return Err(RpcError::ForUser("unable to confirm transaction".to_string()).into());
+                    }
+                    tokio::time::sleep(CONFIRMATION_POLL_INTERVAL).await;
+                }
+            })
+            .await
+    }
+    
+    async fn signature_status(&self, signature: &Signature) -> Result<Option<TransactionStatus>, ApiError> {
+        let statuses = self.rpc
+            .read(|rpc| async move { rpc.get_signature_statuses(&[*signature]).await })
+            .await?;
+        
+        Ok(statuses.value.into_iter().next().flatten())
+    }
+    
+    async fn blockhash_expired(&self, transaction: &Transaction) -> Result<bool, ApiError> {
+        let blockhash = transaction.message.recent_blockhash;
+        let valid = self.rpc
+            .read(|rpc| async move {
+                rpc.is_blockhash_valid(&blockhash, CommitmentConfig::processed()).await
+            })
+            .await?;
+        
+        Ok(!valid)
+    }
+    
+    // Send under the retry policy. Transient failures resend the same transaction,
+    // which can land at most once. A transaction with a fresh blockhash is only built
+    // once the previous one can no longer land: it isn't on-chain and its blockhash
+    // has expired. Transactions we can't re-sign fail at that point instead.
+    async fn send_with_retry(
+        &self,
+        mut transaction: Transaction,
+        custodial: Option<CustodialSigning<'_>>,
+    ) -> Result<TransactionReceipt, ApiError> {
+        let mut attempts = Vec::new();
+        
+        for attempt in 1..=self.retry.max_attempts {
+            let signature = transaction.signatures[0];
+            let error = match self.send_transaction(&transaction).await {
+                Ok(signature) => return Ok(self.receipt(&transaction, signature).await),
+                Err(error) => error,
+            };
+            
+            let failure = SendFailure::classify(&error);
+            log::warn!("Attempt {} to send {} failed ({}): {}", attempt, signature, failure.as_str(), error);
+            attempts.push(SendAttempt {
+                attempt,
+                signature: signature.to_string(),
+                recent_blockhash: transaction.message.recent_blockhash.to_string(),
+                failure: failure.as_str().to_string(),
+                error: error.to_string(),
             });
+            
+            if failure == SendFailure::Permanent || attempt == self.retry.max_attempts {
+                break;
+            }
+            
+            tokio::time::sleep(self.retry.backoff(attempt)).await;
+            
+            // The last send may have landed even though confirming it failed
+            if let Some(status) = self.signature_status(&signature).await? {
+                if let Err(e) = status.status {
+                    return Err(transaction_failed(format!("Transaction failed on-chain: {}", e), attempts));
+                }
+                if status.satisfies_commitment(self.commitment) {
+                    return Ok(self.receipt(&transaction, signature).await);
+                }
+                continue;
+            }
+            
+            if !self.blockhash_expired(&transaction).await? {
+                continue;
+            }
+            
+            transaction = match &custodial {
+                Some(custodial) => {
+                    let mut transaction = self.custodial_transaction(custodial).await?;
+                    self.cosign(&mut transaction)?;
+                    transaction
+                }
+                None => {
+                    return Err(transaction_failed(
+                        "Transaction expired before it landed; build and sign it again".to_string(),
+                        attempts,
+                    ));
+                }
+            };
         }
         
-        Ok(todos)
+        Err(transaction_failed(format!("Transaction failed after {} attempt(s)", attempts.len()), attempts))
+    }
+    
+    async fn fee_paid(&self, signature: &Signature) -> Result<Option<u64>, ApiError> {
+        // getTransaction doesn't serve processed transactions
+        let commitment = if self.commitment.is_at_least_confirmed() {
+            self.commitment
+        } else {
+            CommitmentConfig::confirmed()
+        };
+        let config = RpcTransactionConfig {
+            encoding: Some(UiTransactionEncoding::Base64),
+            commitment: Some(commitment),
+            max_supported_transaction_version: Some(0),
+        };
+        let confirmed = self.rpc
+            .read(|rpc| async move { rpc.get_transaction_with_config(signature, config).await })
+            .await?;
+        
+        Ok(confirmed.transaction.meta.map(|meta| meta.fee))
+    }
+    
+    // Look up where a landed transaction ended up and what it paid. Failed lookups
+    // leave fields out rather than failing an operation that already succeeded.
+    async fn receipt(&self, transaction: &Transaction, signature: Signature) -> TransactionReceipt {
+        let status = self.signature_status(&signature).await.unwrap_or_else(|e| {
+            log::warn!("Failed to look up the status of {}: {}", signature, e);
+            None
+        });
+        let fee_lamports = self.fee_paid(&signature).await.unwrap_or_else(|e| {
+            log::warn!("Failed to look up the fee paid by {}: {}", signature, e);
+            None
+        });
+        let (compute_unit_limit, compute_unit_price) = requested_budget(transaction);
+        
+        TransactionReceipt {
+            signature: signature.to_string(),
+            fee_lamports,
+            slot: status.as_ref().map(|status| status.slot),
+            commitment: status.map(|status| commitment_name(&status.confirmation_status()).to_string()),
+            compute_unit_limit,
+            compute_unit_price,
+        }
     }
     
-    pub fn create_todo(
+    // Where a transaction is now, from the cluster's full signature history
+    pub async fn transaction_status(&self, signature: &str) -> Result<TransactionStatusResponse, ApiError> {
+        let signature = Signature::from_str(signature)
+            .map_err(|_| ApiError::BadRequest("Invalid transaction signature".to_string()))?;
+        
+        let statuses = self.rpc
+            .read(|rpc| async move { rpc.get_signature_statuses_with_history(&[signature]).await })
+            .await?;
+        let status = statuses
+            .value
+            .into_iter()
+            .next()
+            .flatten()
+            .ok_or_else(|| ApiError::NotFound(format!("Transaction {} not found", signature)))?;
+        
+        // Fee lookups need the full transaction, which may not be available yet
+        let fee_lamports = self.fee_paid(&signature).await.unwrap_or(None);
+        
+        let state = match &status.err {
+            Some(_) => "failed",
+            None => commitment_name(&status.confirmation_status()),
+        };
+        
+        Ok(TransactionStatusResponse {
+            signature: signature.to_string(),
+            status: state.to_string(),
+            slot: status.slot,
+            confirmations: status.confirmations,
+            fee_lamports,
+            error: status.err.as_ref().map(|e| e.to_string()),
+            program_error: status.err.as_ref().and_then(|e| program_error::decode(e, &self.idl)),
+        })
+    }
+    
+    // Add the relayer's fee payer signature, if relaying
+    fn cosign(&self, transaction: &mut Transaction) -> Result<(), ApiError> {
+        match &self.relayer {
+            Some(relayer) => relayer.sign(transaction),
+            None => Ok(()),
+        }
+    }
+    
+    // Co-sign as fee payer (if relaying) and send, charging the wallet's sponsorship budget
+    async fn relay_and_send(
         &self,
-        keypair: &Keypair,
-        request: CreateTodoRequest,
-    ) -> Result<TodoResponse, ApiError> {
-        let owner = keypair.pubkey();
-        let task_id = request.task_id;
+        wallet: &str,
+        mut transaction: Transaction,
+        custodial: Option<CustodialSigning<'_>>,
+    ) -> Result<TransactionReceipt, ApiError> {
+        self.cosign(&mut transaction)?;
         
-        // Find the PDA for the todo account
-        let seeds = [
-            b"todo",
-            owner.as_ref(),
-            &task_id.to_le_bytes(),
-        ];
+        let relayer = match &self.relayer {
+            Some(relayer) => relayer,
+            None => return self.send_with_retry(transaction, custodial).await,
+        };
         
-        let (todo_account, bump) = Pubkey::find_program_address(
-            &seeds,
-            &self.program_id,
-        );
+        // Charged once per operation, however many attempts it takes
+        let cost = self.sponsored_cost(&transaction).await?;
+        relayer.reserve(wallet, cost)?;
         
-        // Build and send the transaction
-        let signature = self.program
-            .request()
-            .accounts(todo_solana_program::accounts::CreateTodo {
-                todo: todo_account,
-                owner,
-                system_program: system_program::ID,
+        let result = self.send_with_retry(transaction, custodial).await;
+        
+        // A failed send may still land, so the reservation is only given back once it can't.
+        // Failures outside sending keep it, as what was sent is unknown.
+        if let Err(ApiError::TransactionFailed { attempts, .. }) = &result {
+            self.refund_when_dropped(relayer.clone(), wallet, cost, attempts);
+        }
+        
+        result
+    }
+    
+    // Refund the reservation once none of the attempts can land: every blockhash has
+    // expired and no signature is in the cluster's history. That can take a blockhash
+    // lifetime, so it's watched in the background.
+    fn refund_when_dropped(&self, relayer: Arc<Relayer>, wallet: &str, cost: u64, attempts: &[SendAttempt]) {
+        let sent: Option<Vec<(Signature, Hash)>> = attempts
+            .iter()
+            .map(|attempt| {
+                let signature = Signature::from_str(&attempt.signature).ok()?;
+                let blockhash = Hash::from_str(&attempt.recent_blockhash).ok()?;
+                Some((signature, blockhash))
             })
-            .args(todo_solana_program::instruction::CreateTodo {
-                task_id,
-                description: request.description.clone(),
-                due_date: request.due_date,
+            .collect();
+        let sent = match sent {
+            Some(sent) if !sent.is_empty() => sent,
+            _ => return,
+        };
+        
+        let service = self.clone();
+        let wallet = wallet.to_string();
+        tokio::spawn(async move {
+            match service.dropped(&sent).await {
+                Ok(true) => relayer.refund(&wallet, cost),
+                Ok(false) => {}
+                Err(e) => log::warn!("Kept the sponsorship reservation of {}: {}", wallet, e),
+            }
+        });
+    }
+    
+    // Whether none of the sent transactions landed or still can
+    async fn dropped(&self, sent: &[(Signature, Hash)]) -> Result<bool, ApiError> {
+        // Expiry first, so nothing can land between it and the history lookup
+        for (_, blockhash) in sent {
+            loop {
+                let valid = self.rpc
+                    .read(|rpc| async move {
+                        rpc.is_blockhash_valid(blockhash, CommitmentConfig::processed()).await
+                    })
+                    .await?;
+                if !valid {
+                    break;
+                }
+                tokio::time::sleep(REFUND_POLL_INTERVAL).await;
+            }
+        }
+        
+        let signatures: Vec<Signature> = sent.iter().map(|(signature, _)| *signature).collect();
+        let statuses = self.rpc
+            .read(|rpc| {
+                let signatures = signatures.clone();
+                async move { rpc.get_signature_statuses_with_history(&signatures).await }
             })
-            .signer(keypair)
-            .send()?;
+            .await?;
+        
+        Ok(statuses.value.iter().all(Option::is_none))
+    }
+    
+    // Relay a transaction built by `build_transaction` and signed by the caller's wallet
+    pub async fn submit_signed_transaction(
+        &self,
+        wallet: &str,
+        transaction: Transaction,
+    ) -> Result<TransactionReceipt, ApiError> {
+        let wallet_pubkey = Pubkey::from_str(wallet)
+            .map_err(|_| ApiError::BadRequest("Invalid wallet address".to_string()))?;
+        
+        // Only relay transactions that act on the caller's own todos
+        self.verify_submitted_transaction(&transaction, &wallet_pubkey).await?;
+        
+        // The wallet's signature must be present and valid before we add ours
+        let wallet_position = transaction
+            .get_signing_keypair_positions(&[wallet_pubkey])
+            .ok()
+            .and_then(|positions| positions.first().copied().flatten())
+            .ok_or_else(|| ApiError::BadRequest("Transaction is not signed by the wallet".to_string()))?;
+        let wallet_signed = transaction.signatures[wallet_position]
+            .verify(wallet_pubkey.as_ref(), &transaction.message_data());
+        if !wallet_signed {
+            return Err(ApiError::BadRequest("Wallet signature is invalid".to_string()));
+        }
+        
+        self.relay_and_send(wallet, transaction, None).await
+    }
+    
+    // A fresh transaction for the operation, signed by the wallet's custodial key
+    async fn custodial_transaction(&self, custodial: &CustodialSigning<'_>) -> Result<Transaction, ApiError> {
+        let mut transaction = self
+            .build_transaction_with_limit(
+                custodial.owner,
+                custodial.instructions,
+                custodial.priority,
+                custodial.compute_unit_limit,
+            )
+            .await?
+            .transaction;
+        
+        sign_transaction(custodial.signer, custodial.wallet, &mut transaction).await?;
+        
+        Ok(transaction)
+    }
+    
+    async fn sign_and_send(
+        &self,
+        signer: &dyn TransactionSigner,
+        wallet: &str,
+        owner: &Pubkey,
+        instructions: &[Instruction],
+        priority: Option<PriorityOverride>,
+        compute_unit_limit: Option<u32>,
+    ) -> Result<TransactionReceipt, ApiError> {
+        let custodial = CustodialSigning {
+            signer,
+            wallet,
+            owner,
+            instructions,
+            priority,
+            compute_unit_limit,
+        };
+        let transaction = self.custodial_transaction(&custodial).await?;
+        
+        self.relay_and_send(wallet, transaction, Some(custodial)).await
+    }
+    
+    // The custodial methods below act for `wallet` through its custodial key,
+    // which owns the todos on-chain and pays for the transactions unless relayed.
+    
+    pub async fn create_todo(
+        &self,
+        signer: &dyn TransactionSigner,
+        wallet: &str,
+        request: CreateTodoRequest,
+        priority: Option<PriorityOverride>,
+    ) -> Result<TodoResponse, ApiError> {
+        let owner = signer.pubkey(wallet).await?;
+        let instructions = self.create_todo_instructions(&owner, &request).await?;
+        
+        // Build and send the transaction
+        let receipt = self.sign_and_send(signer, wallet, &owner, &instructions, priority, None).await?;
             
-        log::info!("Created todo with signature: {}", signature);
+        log::info!("Created todo with signature: {}", receipt.signature);
         
         Ok(TodoResponse {
-            task_id,
+            task_id: request.task_id,
             description: request.description,
             completed: false,
             due_date: request.due_date,
             owner: owner.to_string(),
+            layout_version: self.layout_version,
+            transaction: Some(receipt),
         })
     }
     
-    pub fn update_todo(
+    pub async fn update_todo(
         &self,
-        keypair: &Keypair,
+        signer: &dyn TransactionSigner,
+        wallet: &str,
         task_id: u64,
         request: UpdateTodoRequest,
+        priority: Option<PriorityOverride>,
     ) -> Result<TodoResponse, ApiError> {
-        let owner = keypair.pubkey();
+        let owner = signer.pubkey(wallet).await?;
+        let todo_account = self.todo_address(&owner, task_id);
+        let instructions = self.update_todo_instructions(&owner, task_id, &request).await?;
         
-        // Find the PDA for the todo account
-        let seeds = [
-            b"todo",
-            owner.as_ref(),
-            &task_id.to_le_bytes(),
-        ];
+        // All changes go in one transaction, so they apply together or not at all
+        let receipt = if instructions.is_empty() {
+            None
+        } else {
+            let receipt = self.sign_and_send(signer, wallet, &owner, &instructions, priority, None).await?;
+            
+            log::info!("Updated todo with signature: {}", receipt.signature);
+            
+            Some(receipt)
+        };
         
-        let (todo_account, _) = Pubkey::find_program_address(
-            &seeds,
-            &self.program_id,
-        );
+        // Fetch the updated state
+        let updated_todo = self.fetch_todo(&todo_account, task_id).await?;
         
-        // First, fetch the current state to verify the account exists
-        let todo = self.program.account::<todo_solana_program::TodoAccount>(todo_account)
-            .map_err(|_| ApiError::NotFound(format!("Todo with ID {} not found", task_id)))?;
+        Ok(todo_response(updated_todo, receipt))
+    }
+    
+    pub async fn delete_todo(
+        &self,
+        signer: &dyn TransactionSigner,
+        wallet: &str,
+        task_id: u64,
+        priority: Option<PriorityOverride>,
+    ) -> Result<TransactionReceipt, ApiError> {
+        let owner = signer.pubkey(wallet).await?;
+        let instructions = self.delete_todo_instructions(&owner, task_id).await?;
+        
+        // Delete the todo
+        let receipt = self.sign_and_send(signer, wallet, &owner, &instructions, priority, None).await?;
             
-        // Handle description update if provided
-        if let Some(description) = &request.description {
-            let signature = self.program
-                .request()
-                .accounts(todo_solana_program::accounts::UpdateTodo {
-                    todo: todo_account,
-                    owner,
-                })
-                .args(todo_solana_program::instruction::UpdateDescription {
-                    description: description.clone(),
-                })
-                .signer(keypair)
-                .send()?;
-                
-            log::info!("Updated todo description with signature: {}", signature);
+        log::info!("Deleted todo with signature: {}", receipt.signature);
+        self.forget_todo(&self.todo_address(&owner, task_id));
+        
+        Ok(receipt)
+    }
+    
+    // Bring the owner's todo accounts up to the current layout, one transaction per
+    // account. With the wallet's custodial signer each is sent; without one each comes
+    // back unsigned for the wallet to sign and submit. One account failing doesn't
+    // stop the others.
+    pub async fn migrate_todos(
+        &self,
+        signer: Option<&dyn TransactionSigner>,
+        wallet: &str,
+        owner: &Pubkey,
+        priority: Option<PriorityOverride>,
+    ) -> Result<MigrationResponse, ApiError> {
+        let outdated: Vec<_> = self
+            .todo_accounts(owner)
+            .await?
+            .into_iter()
+            .filter(|(_, _, todo)| todo.layout_version < self.layout_version)
+            .collect();
+        
+        if !outdated.is_empty() {
+            let (name, accounts, args) = MIGRATE_INSTRUCTION;
+            self.idl.require_instruction(name, accounts, args).map_err(|e| {
+                ApiError::InternalServerError(format!("Program IDL can't migrate todo accounts: {}", e))
+            })?;
         }
         
-        // Handle completed status update if provided
-        if let Some(completed) = request.completed {
-            if completed != todo.completed {
-                let signature = self.program
-                    .request()
-                    .accounts(todo_solana_program::accounts::UpdateTodo {
-                        todo: todo_account,
-                        owner,
-                    })
-                    .args(todo_solana_program::instruction::ToggleCompleted {})
-                    .signer(keypair)
-                    .send()?;
-                    
-                log::info!("Toggled todo completion status with signature: {}", signature);
+        let mut migrations = Vec::new();
+        for (address, lamports, todo) in outdated {
+            let mut migration = TodoMigration {
+                task_id: todo.task_id,
+                todo_account: address.to_string(),
+                layout_version: todo.layout_version,
+                transaction: None,
+                unsigned_transaction: None,
+                error: None,
+            };
+            
+            let instructions = self.migrate_todo_instructions(owner, &address, lamports).await;
+            let result = match (instructions, signer) {
+                (Ok(instructions), Some(signer)) => self
+                    .sign_and_send(signer, wallet, owner, &instructions, priority, None)
+                    .await
+                    .map(|receipt| {
+                        log::info!("Migrated todo {} with signature: {}", address, receipt.signature);
+                        migration.layout_version = self.layout_version;
+                        migration.transaction = Some(receipt);
+                    }),
+                (Ok(instructions), None) => self
+                    .unsigned_transaction(owner, address, &instructions, priority)
+                    .await
+                    .map(|unsigned| migration.unsigned_transaction = Some(unsigned)),
+                (Err(e), _) => Err(e),
+            };
+            if let Err(e) = result {
+                log::warn!("Failed to migrate todo {}: {}", address, e);
+                migration.error = Some(e.to_string());
             }
+            
+            migrations.push(migration);
         }
         
-        // Fetch the updated state
-        let updated_todo = self.program.account::<todo_solana_program::TodoAccount>(todo_account)?;
-        
-        Ok(TodoResponse {
-            task_id: updated_todo.task_id,
-            description: updated_todo.description,
-            completed: updated_todo.completed,
-            due_date: updated_todo.due_date,
-            owner: updated_todo.owner.to_string(),
+        Ok(MigrationResponse {
+            layout_version: self.layout_version,
+            migrations,
         })
     }
-    
-    pub fn delete_todo(
+
+    // Apply many operations with as few transactions as possible. Operations are
+    // packed in order while a transaction stays within the packet size and compute
+    // limits. Each transaction is atomic, so an operation that fails on-chain fails
+    // the others packed with it.
+    pub async fn batch(
         &self,
-        keypair: &Keypair,
-        task_id: u64,
-    ) -> Result<(), ApiError> {
-        let owner = keypair.pubkey();
+        signer: &dyn TransactionSigner,
+        wallet: &str,
+        operations: Vec<BatchOperation>,
+        priority: Option<PriorityOverride>,
+    ) -> Result<BatchResponse, ApiError> {
+        let owner = signer.pubkey(wallet).await?;
+        let mut results: Vec<BatchOperationResult> = operations
+            .iter()
+            .enumerate()
+            .map(|(index, operation)| BatchOperationResult {
+                index,
+                task_id: operation.task_id(),
+                success: false,
+                signature: None,
+                error: None,
+            })
+            .collect();
         
-        // Find the PDA for the todo account
-        let seeds = [
-            b"todo", 
-            owner.as_ref(),
-            &task_id.to_le_bytes(),
-        ];
+        // Operations that can't be built fail on their own
+        let mut built = Vec::new();
+        for (index, operation) in operations.iter().enumerate() {
+            match self.operation_instructions(&owner, operation).await {
+                Ok(instructions) => built.push((index, instructions)),
+                Err(e) => results[index].error = Some(e.to_string()),
+            }
+        }
         
-        let (todo_account, _) = Pubkey::find_program_address(
-            &seeds,
-            &self.program_id,
-        );
+        let payer = self.fee_payer(&owner);
+        let mut groups: Vec<Vec<(usize, Vec<Instruction>)>> = Vec::new();
+        for operation in built {
+            let fits = groups.last().is_some_and(|group| {
+                let instructions: Vec<Instruction> = group
+                    .iter()
+                    .chain(std::iter::once(&operation))
+                    .flat_map(|(_, instructions)| instructions.iter().cloned())
+                    .collect();
+                self.fits_in_transaction(&payer, &instructions, group.len() + 1)
+            });
+            
+            match groups.last_mut() {
+                Some(group) if fits => group.push(operation),
+                // An operation too big even on its own still gets sent, and fails there
+                _ => groups.push(vec![operation]),
+            }
+        }
         
-        // Check if the account exists
-        let _ = self.program.account::<todo_solana_program::TodoAccount>(todo_account)
-            .map_err(|_| ApiError::NotFound(format!("Todo with ID {} not found", task_id)))?;
+        let mut transactions = Vec::new();
+        for group in groups {
+            let instructions: Vec<Instruction> = group
+                .iter()
+                .flat_map(|(_, instructions)| instructions.iter().cloned())
+                .collect();
+            let unit_limit = self.batch_unit_limit(group.len());
             
-        // Delete the todo
-        let signature = self.program
-            .request()
-            .accounts(todo_solana_program::accounts::DeleteTodo {
-                todo: todo_account,
-                owner,
+            match self.sign_and_send(signer, wallet, &owner, &instructions, priority, Some(unit_limit)).await {
+                Ok(receipt) => {
+                    log::info!("Applied {} batched operations with signature: {}", group.len(), receipt.signature);
+                    for (index, _) in &group {
+                        results[*index].success = true;
+                        results[*index].signature = Some(receipt.signature.clone());
+                        if let BatchOperation::Delete { task_id } = operations[*index] {
+                            self.forget_todo(&self.todo_address(&owner, task_id));
+                        }
+                    }
+                    transactions.push(receipt);
+                }
+                Err(e) => {
+                    for (index, _) in &group {
+                        results[*index].error = Some(e.to_string());
+                    }
+                }
+            }
+        }
+        
+        Ok(BatchResponse { results, transactions })
+    }
+    
+    // Dry run: build an operation's transaction exactly as a send would, and
+    // simulate it unsigned against current state instead of sending it
+    pub async fn simulate_operation(
+        &self,
+        owner: &Pubkey,
+        operation: &BatchOperation,
+        priority: Option<PriorityOverride>,
+    ) -> Result<SimulationResponse, ApiError> {
+        let instructions = self.operation_instructions(owner, operation).await?;
+        let todo_account = self.todo_address(owner, operation.task_id());
+        let built = self.build_transaction(owner, &instructions, priority).await?;
+        let transaction = &built.transaction;
+        let commitment = self.commitment;
+        
+        let config = RpcSimulateTransactionConfig {
+            sig_verify: false,
+            replace_recent_blockhash: true,
+            commitment: Some(commitment),
+            // The todo account after the simulation, to see the rent it took
+            accounts: Some(RpcSimulateTransactionAccountsConfig {
+                encoding: Some(UiAccountEncoding::Base64),
+                addresses: vec![todo_account.to_string()],
+            }),
+            ..RpcSimulateTransactionConfig::default()
+        };
+        let simulated = self.rpc
+            .read(|rpc| {
+                let config = config.clone();
+                async move { rpc.simulate_transaction_with_config(transaction, config).await }
             })
-            .args(todo_solana_program::instruction::DeleteTodo {})
-            .signer(keypair)
-            .send()?;
-            
-        log::info!("Deleted todo with signature: {}", signature);
+            .await?
+            .value;
         
-        Ok(())
+        let fee_lamports = self.rpc
+            .read(|rpc| async move { rpc.get_fee_for_message(&transaction.message).await })
+            .await?;
+        let balance_before = self.rpc
+            .read(|rpc| async move { rpc.get_balance_with_commitment(&todo_account, commitment).await })
+            .await?
+            .value;
+        let rent_lamports = simulated
+            .accounts
+            .and_then(|accounts| accounts.into_iter().next().flatten())
+            .map(|account| account.lamports.saturating_sub(balance_before));
+        
+        Ok(SimulationResponse {
+            success: simulated.err.is_none(),
+            logs: simulated.logs.unwrap_or_default(),
+            units_consumed: simulated.units_consumed,
+            fee_lamports,
+            rent_lamports,
+            compute_unit_limit: built.compute_budget.unit_limit,
+            compute_unit_price: built.compute_budget.unit_price,
+            error: simulated.err.as_ref().map(|e| e.to_string()),
+            program_error: simulated.err.as_ref().and_then(|e| program_error::decode(e, &self.idl)),
+        })
+    }
+    
+    async fn operation_instructions(
+        &self,
+        owner: &Pubkey,
+        operation: &BatchOperation,
+    ) -> Result<Vec<Instruction>, ApiError> {
+        operation
+            .validate()
+            .map_err(|e| ApiError::BadRequest(e.to_string()))?;
+        
+        match operation {
+            BatchOperation::Create(request) => self.create_todo_instructions(owner, request).await,
+            BatchOperation::Update { task_id, changes } => {
+                let instructions = self.update_todo_instructions(owner, *task_id, changes).await?;
+                if instructions.is_empty() {
+                    return Err(ApiError::BadRequest("Nothing to update".to_string()));
+                }
+                Ok(instructions)
+            }
+            BatchOperation::Delete { task_id } => self.delete_todo_instructions(owner, *task_id).await,
+        }
+    }
+    
+    fn batch_unit_limit(&self, operations: usize) -> u32 {
+        (operations as u64 * self.batch_units_per_operation as u64).min(MAX_COMPUTE_UNIT_LIMIT as u64) as u32
+    }
+    
+    // Whether `operations` operations made of `instructions` fit in one transaction
+    fn fits_in_transaction(&self, payer: &Pubkey, instructions: &[Instruction], operations: usize) -> bool {
+        let units = operations as u64 * self.batch_units_per_operation as u64;
+        if units > MAX_COMPUTE_UNIT_LIMIT as u64 {
+            return false;
+        }
+        
+        // Compute budget instructions encode to a fixed size, whatever the values
+        let compute_budget = ComputeBudget {
+            unit_limit: MAX_COMPUTE_UNIT_LIMIT,
+            unit_price: 0,
+        };
+        let mut all_instructions = compute_budget.instructions().to_vec();
+        all_instructions.extend_from_slice(instructions);
+        
+        let transaction = Transaction::new_unsigned(Message::new(&all_instructions, Some(payer)));
+        
+        bincode::serialized_size(&transaction).is_ok_and(|size| size <= PACKET_DATA_SIZE as u64)
     }
 }
 
-// Define the account structure to match the Anchor program
-#[derive(Debug, Clone)]
+fn commitment_name(status: &TransactionConfirmationStatus) -> &'static str {
+    match status {
+        TransactionConfirmationStatus::Processed => "processed",
+        TransactionConfirmationStatus::Confirmed => "confirmed",
+        TransactionConfirmationStatus::Finalized => "finalized",
+    }
+}
+
+fn transaction_failed(message: String, attempts: Vec<SendAttempt>) -> ApiError {
+    ApiError::TransactionFailed { message, attempts }
+}
+
+// The API's view of a todo account
+#[derive(Debug, Clone, PartialEq)]
 pub struct TodoAccount {
     pub task_id: u64,
     pub description: String,
     pub completed: bool,
     pub due_date: i64,
     pub owner: Pubkey,
+    pub layout_version: u8,
 }
 
-pub mod todo_solana_program {
-    use anchor_lang::prelude::*;
+// The layout version and space of todo accounts the program creates now
+pub fn current_layout(idl: &Idl) -> Result<(u8, usize), String> {
+    let appended = idl.appended_size(TODO_ACCOUNT, TODO_ACCOUNT_FIELDS)?;
     
-    #[derive(Debug, Clone)]
-    pub struct TodoAccount {
-        pub task_id: u64,
-        pub description: String,
-        pub completed: bool,
-        pub due_date: i64, 
-        pub owner: Pubkey,
+    Ok(if appended == 0 {
+        (ORIGINAL_LAYOUT, TODO_ACCOUNT_SPACE)
+    } else {
+        (EXTENDED_LAYOUT, TODO_ACCOUNT_SPACE + appended)
+    })
+}
+
+// Decoding checks the discriminator, so data from any other account type is
+// rejected rather than read as a todo
+pub fn decode_todo(idl: &Idl, address: &Pubkey, data: &[u8]) -> Result<TodoAccount, ApiError> {
+    let invalid = |reason: String| {
+        ApiError::SolanaError(format!("Failed to decode todo account {}: {}", address, reason))
+    };
+    
+    // An original account read in the extended layout would take the appended
+    // fields from its unused description space, so only its own fields are read
+    let (current, _) = current_layout(idl).map_err(invalid)?;
+    let layout_version = if data.len() <= TODO_ACCOUNT_SPACE { ORIGINAL_LAYOUT } else { current };
+    let fields = if layout_version == ORIGINAL_LAYOUT {
+        idl.decode_account_fields(TODO_ACCOUNT, TODO_ACCOUNT_FIELDS.len(), data)
+    } else {
+        idl.decode_account(TODO_ACCOUNT, data)
     }
+    .map_err(invalid)?;
     
-    // These modules are needed to mimic the structure of your Solana program for type-checking
-    pub mod accounts {
-        use anchor_lang::prelude::*;
-        
-        #[derive(Accounts)]
-        pub struct CreateTodo<'info> {
-            pub todo: AccountInfo<'info>,
-            pub owner: AccountInfo<'info>,
-            pub system_program: AccountInfo<'info>,
-        }
-        
-        #[derive(Accounts)]
-        pub struct UpdateTodo<'info> {
-            pub todo: AccountInfo<'info>,
-            pub owner: AccountInfo<'info>,
-        }
-        
-        #[derive(Accounts)]
-        pub struct DeleteTodo<'info> {
-            pub todo: AccountInfo<'info>,
-            pub owner: AccountInfo<'info>,
-        }
+    todo_from_fields(&fields, layout_version).ok_or_else(|| invalid("unexpected field values".to_string()))
+}
+
+// The owner read straight from the account data, for accounts that fail to decode.
+// It sits after the description in every layout.
+fn raw_owner(data: &[u8]) -> Option<Pubkey> {
+    let length = u32::from_le_bytes(data.get(16..20)?.try_into().ok()?) as usize;
+    let offset = 20usize.checked_add(length)? + 1 + 8;
+    Pubkey::try_from(data.get(offset..offset + 32)?).ok()
+}
+
+// Field types were checked against the IDL at startup, so this only fails on a bug
+fn todo_from_fields(fields: &Map<String, Value>, layout_version: u8) -> Option<TodoAccount> {
+    Some(TodoAccount {
+        task_id: fields.get("task_id")?.as_u64()?,
+        description: fields.get("description")?.as_str()?.to_string(),
+        completed: fields.get("completed")?.as_bool()?,
+        due_date: fields.get("due_date")?.as_i64()?,
+        owner: Pubkey::from_str(fields.get("owner")?.as_str()?).ok()?,
+        layout_version,
+    })
+}
+
+fn todo_response(todo: TodoAccount, transaction: Option<TransactionReceipt>) -> TodoResponse {
+    TodoResponse {
+        task_id: todo.task_id,
+        description: todo.description,
+        completed: todo.completed,
+        due_date: todo.due_date,
+        owner: todo.owner.to_string(),
+        layout_version: todo.layout_version,
+        transaction,
+    }
+}
+
+// Fail at startup, listing everything missing, if the IDL can't serve the API
+fn check_idl(idl: &Idl) -> Result<(), ApiError> {
+    let mut problems: Vec<String> = REQUIRED_INSTRUCTIONS
+        .iter()
+        .filter_map(|(name, accounts, args)| idl.require_instruction(name, accounts, args).err())
+        .collect();
+    if let Err(problem) = idl.appended_size(TODO_ACCOUNT, TODO_ACCOUNT_FIELDS) {
+        problems.push(problem);
     }
     
-    pub mod instruction {
-        pub struct CreateTodo {
-            pub task_id: u64,
-            pub description: String,
-            pub due_date: i64,
-        }
-        
-        pub struct UpdateDescription {
-            pub description: String,
-        }
-        
-        pub struct ToggleCompleted {}
-        
-        pub struct DeleteTodo {}
+    if problems.is_empty() {
+        Ok(())
+    } else {
+        Err(ApiError::InternalServerError(format!(
+            "Program IDL doesn't match the API: {}",
+            problems.join("; ")
+        )))
     }
-}   
\ No newline at end of file
+}
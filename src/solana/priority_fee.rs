@@ -0,0 +1,185 @@
+// src/solana/priority_fee.rs
+use super::rpc_pool::RpcPool;
+use crate::config::Config;
+use crate::errors::ApiError;
+use actix_web::http::header::HeaderMap;
+use anchor_lang::AnchorDeserialize;
+use solana_sdk::compute_budget::{self, ComputeBudgetInstruction};
+use solana_sdk::instruction::Instruction;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::transaction::Transaction;
+
+pub const PRIORITY_FEE_HEADER: &str = "X-Priority-Fee";
+
+// The most compute units a single transaction may request
+pub const MAX_COMPUTE_UNIT_LIMIT: u32 = 1_400_000;
+
+// A caller's request for a different compute unit price on one operation
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PriorityOverride {
+    // Price at the urgent percentile of recent fees
+    Urgent,
+    // An explicit price in micro-lamports per compute unit
+    MicroLamports(u64),
+}
+
+impl PriorityOverride {
+    // `X-Priority-Fee: urgent` or `X-Priority-Fee: <micro-lamports>`
+    pub fn from_headers(headers: &HeaderMap) -> Result<Option<Self>, ApiError> {
+        let value = match headers.get(PRIORITY_FEE_HEADER) {
+            Some(value) => value,
+            None => return Ok(None),
+        };
+
+        let invalid = || {
+            ApiError::BadRequest(format!(
+                "{} must be `urgent` or a price in micro-lamports",
+                PRIORITY_FEE_HEADER
+            ))
+        };
+        let value = value.to_str().map_err(|_| invalid())?.trim();
+
+        if value.eq_ignore_ascii_case("urgent") {
+            return Ok(Some(PriorityOverride::Urgent));
+        }
+
+        value
+            .parse()
+            .map(|price| Some(PriorityOverride::MicroLamports(price)))
+            .map_err(|_| invalid())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum PriceSource {
+    Static,
+    // A percentile of the prioritization fees recently paid to write the same accounts
+    Dynamic,
+}
+
+// The compute budget every program transaction is built with
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ComputeBudget {
+    pub unit_limit: u32,
+    pub unit_price: u64, // micro-lamports per compute unit
+}
+
+impl ComputeBudget {
+    pub fn instructions(&self) -> [Instruction; 2] {
+        [
+            ComputeBudgetInstruction::set_compute_unit_limit(self.unit_limit),
+            ComputeBudgetInstruction::set_compute_unit_price(self.unit_price),
+        ]
+    }
+}
+
+#[derive(Clone)]
+pub struct PriorityFees {
+    source: PriceSource,
+    unit_limit: u32,
+    // The static price, and the floor for dynamic prices
+    base_price: u64,
+    max_price: u64,
+    percentile: u8,
+    urgent_percentile: u8,
+}
+
+impl PriorityFees {
+    pub fn from_config(config: &Config) -> Result<Self, ApiError> {
+        let source = match config.priority_fee_mode.as_str() {
+            "static" => PriceSource::Static,
+            "dynamic" => PriceSource::Dynamic,
+            other => {
+                return Err(ApiError::InternalServerError(format!(
+                    "Unknown priority fee mode: {}",
+                    other
+                )))
+            }
+        };
+
+        Ok(Self {
+            source,
+            unit_limit: config.compute_unit_limit,
+            base_price: config.priority_fee_micro_lamports,
+            max_price: config.priority_fee_max_micro_lamports,
+            percentile: config.priority_fee_percentile.min(100),
+            urgent_percentile: config.priority_fee_urgent_percentile.min(100),
+        })
+    }
+
+    pub fn max_price(&self) -> u64 {
+        self.max_price
+    }
+
+    pub async fn budget(
+        &self,
+        rpc: &RpcPool,
+        writable_accounts: &[Pubkey],
+        priority: Option<PriorityOverride>,
+    ) -> Result<ComputeBudget, ApiError> {
+        let price = match (priority, self.source) {
+            (Some(PriorityOverride::MicroLamports(price)), _) => price,
+            (Some(PriorityOverride::Urgent), _) => {
+                self.recent_fee(rpc, writable_accounts, self.urgent_percentile).await?
+            }
+            (None, PriceSource::Dynamic) => {
+                self.recent_fee(rpc, writable_accounts, self.percentile).await?
+            }
+            (None, PriceSource::Static) => self.base_price,
+        };
+
+        Ok(ComputeBudget {
+            unit_limit: self.unit_limit,
+            unit_price: price.clamp(self.base_price.min(self.max_price), self.max_price),
+        })
+    }
+
+    async fn recent_fee(
+        &self,
+        rpc: &RpcPool,
+        writable_accounts: &[Pubkey],
+        percentile: u8,
+    ) -> Result<u64, ApiError> {
+        let recent = rpc
+            .read(|rpc| async move { rpc.get_recent_prioritization_fees(writable_accounts).await })
+            .await?;
+
+        let mut fees: Vec<u64> = recent.iter().map(|fee| fee.prioritization_fee).collect();
+
+        Ok(fee_percentile(&mut fees, percentile))
+    }
+}
+
+// The fee at `percentile` (0-100) of the samples, using the nearest-rank method.
+// No samples means no recent competition, so no priority fee.
+pub fn fee_percentile(fees: &mut [u64], percentile: u8) -> u64 {
+    if fees.is_empty() {
+        return 0;
+    }
+
+    fees.sort_unstable();
+    let rank = (percentile.min(100) as usize * fees.len()).div_ceil(100);
+
+    fees[rank.saturating_sub(1)]
+}
+
+// The compute unit limit and price a built or submitted transaction sets, if any
+pub fn requested_budget(transaction: &Transaction) -> (Option<u32>, Option<u64>) {
+    let account_keys = &transaction.message.account_keys;
+    let mut unit_limit = None;
+    let mut unit_price = None;
+
+    for instruction in &transaction.message.instructions {
+        if account_keys.get(instruction.program_id_index as usize) != Some(&compute_budget::ID) {
+            continue;
+        }
+
+        match ComputeBudgetInstruction::try_from_slice(&instruction.data) {
+            Ok(ComputeBudgetInstruction::SetComputeUnitLimit(units)) => unit_limit = Some(units),
+            Ok(ComputeBudgetInstruction::SetComputeUnitPrice(price)) => unit_price = Some(price),
+            _ => {}
+        }
+    }
+
+    (unit_limit, unit_price)
+}
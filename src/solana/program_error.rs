@@ -0,0 +1,57 @@
+// src/solana/program_error.rs
+use super::idl::Idl;
+use crate::models::transaction::ProgramErrorDetails;
+use anchor_lang::error::ERROR_CODE_OFFSET;
+use solana_sdk::instruction::InstructionError;
+use solana_sdk::transaction::TransactionError;
+
+// Break a failed transaction's error down to the instruction and, for custom
+// program errors, the program's own error from its IDL or else the Anchor
+// error range the code falls in
+pub fn decode(error: &TransactionError, idl: &Idl) -> Option<ProgramErrorDetails> {
+    let (instruction_index, instruction_error) = match error {
+        TransactionError::InstructionError(index, error) => (*index, error),
+        _ => return None,
+    };
+
+    let code = match instruction_error {
+        InstructionError::Custom(code) => *code,
+        other => {
+            return Some(ProgramErrorDetails {
+                instruction_index,
+                code: None,
+                name: None,
+                message: other.to_string(),
+            })
+        }
+    };
+
+    let (name, message) = match idl.error(code) {
+        Some(error) => (
+            Some(error.name.clone()),
+            error.msg.clone().unwrap_or_else(|| error.name.clone()),
+        ),
+        None => (None, describe_custom(code)),
+    };
+
+    Some(ProgramErrorDetails {
+        instruction_index,
+        code: Some(code),
+        name,
+        message,
+    })
+}
+
+// Ranges as documented on anchor_lang::error::ErrorCode
+fn describe_custom(code: u32) -> String {
+    match code {
+        c if c >= ERROR_CODE_OFFSET => format!("Todo program error {}", c - ERROR_CODE_OFFSET),
+        100..=999 => format!("Anchor instruction error {}", code),
+        1000..=1999 => format!("Anchor IDL instruction error {}", code),
+        2000..=2999 => format!("Anchor constraint violated ({})", code),
+        3000..=3999 => format!("Anchor account error {}", code),
+        4100..=4999 => format!("Anchor error {}", code),
+        5000 => "Deprecated Anchor error".to_string(),
+        _ => format!("Custom program error {}", code),
+    }
+}
@@ -0,0 +1,176 @@
+// src/solana/relayer.rs
+use crate::auth::unix_now;
+use crate::errors::ApiError;
+use crate::models::sponsorship::SponsorshipResponse;
+use crate::repository::{RepositoryWriter, SponsorshipUsage, TodoRepository};
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::{read_keypair_file, Keypair, Signer};
+use solana_sdk::transaction::Transaction;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, MutexGuard};
+
+const SECONDS_PER_DAY: u64 = 86_400;
+
+#[derive(Default)]
+struct DailyUsage {
+    day: u64,
+    transactions: u32,
+    lamports: u64,
+}
+
+// Server fee payer that covers transaction fees and todo account rent,
+// within a daily budget per wallet. With a repository the ledger is saved on
+// every change, so a restart doesn't reset the day's budgets.
+pub struct Relayer {
+    fee_payer: Keypair,
+    max_transactions_per_day: u32,
+    max_lamports_per_day: u64,
+    usage: Mutex<HashMap<String, DailyUsage>>,
+    writer: Option<RepositoryWriter>,
+}
+
+impl Relayer {
+    pub fn new(fee_payer: Keypair, max_transactions_per_day: u32, max_lamports_per_day: u64) -> Self {
+        Self {
+            fee_payer,
+            max_transactions_per_day,
+            max_lamports_per_day,
+            usage: Mutex::new(HashMap::new()),
+            writer: None,
+        }
+    }
+
+    // Picks up today's usage from the last run
+    pub fn with_repository(self, repository: Arc<dyn TodoRepository>) -> Result<Self, ApiError> {
+        let day = unix_now()? / SECONDS_PER_DAY;
+        let saved = repository.sponsorship_usage(day)?;
+
+        {
+            let mut usage = self.lock()?;
+            for saved in saved {
+                usage.insert(
+                    saved.wallet,
+                    DailyUsage {
+                        day: saved.day,
+                        transactions: saved.transactions,
+                        lamports: saved.lamports,
+                    },
+                );
+            }
+        }
+
+        Ok(Self {
+            writer: Some(RepositoryWriter::spawn(repository)),
+            ..self
+        })
+    }
+
+    pub fn from_keypair_file(
+        path: &str,
+        max_transactions_per_day: u32,
+        max_lamports_per_day: u64,
+    ) -> Result<Self, ApiError> {
+        let fee_payer = read_keypair_file(path).map_err(|e| {
+            ApiError::InternalServerError(format!("Failed to read fee payer keypair: {}", e))
+        })?;
+
+        Ok(Self::new(fee_payer, max_transactions_per_day, max_lamports_per_day))
+    }
+
+    pub fn pubkey(&self) -> Pubkey {
+        self.fee_payer.pubkey()
+    }
+
+    // Add the fee payer's signature; other signers sign separately
+    pub fn sign(&self, transaction: &mut Transaction) -> Result<(), ApiError> {
+        let recent_blockhash = transaction.message.recent_blockhash;
+
+        transaction
+            .try_partial_sign(&[&self.fee_payer], recent_blockhash)
+            .map_err(|e| ApiError::InternalServerError(format!("Fee payer failed to sign: {}", e)))
+    }
+
+    // Charge one transaction costing `lamports` against the wallet's budget for today
+    pub fn reserve(&self, wallet: &str, lamports: u64) -> Result<(), ApiError> {
+        let day = unix_now()? / SECONDS_PER_DAY;
+        let mut usage = self.lock()?;
+        let entry = usage.entry(wallet.to_string()).or_default();
+
+        if entry.day != day {
+            *entry = DailyUsage { day, ..DailyUsage::default() };
+        }
+
+        if entry.transactions >= self.max_transactions_per_day {
+            return Err(ApiError::PaymentRequired(
+                "Daily sponsored transaction limit reached".to_string(),
+            ));
+        }
+
+        if entry.lamports + lamports > self.max_lamports_per_day {
+            return Err(ApiError::PaymentRequired(
+                "Daily sponsorship budget exhausted".to_string(),
+            ));
+        }
+
+        entry.transactions += 1;
+        entry.lamports += lamports;
+        self.save(wallet, entry);
+
+        Ok(())
+    }
+
+    // Give back a reservation for a transaction that can no longer land
+    pub fn refund(&self, wallet: &str, lamports: u64) {
+        if let Ok(mut usage) = self.lock() {
+            if let Some(entry) = usage.get_mut(wallet) {
+                entry.transactions = entry.transactions.saturating_sub(1);
+                entry.lamports = entry.lamports.saturating_sub(lamports);
+                self.save(wallet, entry);
+            }
+        }
+    }
+
+    // Called with the ledger locked, so saves are queued in the order of the changes.
+    // The in-memory ledger stays authoritative, so failures only log.
+    fn save(&self, wallet: &str, entry: &DailyUsage) {
+        if let Some(writer) = &self.writer {
+            let usage = SponsorshipUsage {
+                wallet: wallet.to_string(),
+                day: entry.day,
+                transactions: entry.transactions,
+                lamports: entry.lamports,
+            };
+            writer.write(move |repository| {
+                if let Err(e) = repository.save_sponsorship_usage(&usage) {
+                    log::warn!("Failed to save sponsorship usage of {}: {}", usage.wallet, e);
+                }
+            });
+        }
+    }
+
+    pub fn remaining(&self, wallet: &str) -> Result<SponsorshipResponse, ApiError> {
+        let day = unix_now()? / SECONDS_PER_DAY;
+        let usage = self.lock()?;
+
+        let (transactions, lamports) = match usage.get(wallet) {
+            Some(entry) if entry.day == day => (entry.transactions, entry.lamports),
+            _ => (0, 0),
+        };
+
+        Ok(SponsorshipResponse {
+            enabled: true,
+            fee_payer: Some(self.pubkey().to_string()),
+            max_transactions_per_day: self.max_transactions_per_day,
+            max_lamports_per_day: self.max_lamports_per_day,
+            remaining_transactions: self.max_transactions_per_day.saturating_sub(transactions),
+            remaining_lamports: self.max_lamports_per_day.saturating_sub(lamports),
+            resets_at: (day + 1) * SECONDS_PER_DAY,
+        })
+    }
+
+    fn lock(&self) -> Result<MutexGuard<'_, HashMap<String, DailyUsage>>, ApiError> {
+        self.usage
+            .lock()
+            .map_err(|_| ApiError::InternalServerError("Sponsorship ledger poisoned".to_string()))
+    }
+}
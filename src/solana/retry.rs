@@ -0,0 +1,95 @@
+// src/solana/retry.rs
+use super::rpc_pool::is_endpoint_error;
+use crate::config::Config;
+use rand::Rng;
+use solana_client::client_error::{ClientError, ClientErrorKind};
+use solana_client::rpc_custom_error::{
+    JSON_RPC_SERVER_ERROR_BLOCK_NOT_AVAILABLE, JSON_RPC_SERVER_ERROR_MIN_CONTEXT_SLOT_NOT_REACHED,
+    JSON_RPC_SERVER_ERROR_NODE_UNHEALTHY,
+};
+use solana_client::rpc_request::RpcError;
+use solana_sdk::transaction::TransactionError;
+use std::time::Duration;
+
+// What a failed send tells us about trying again
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SendFailure {
+    // The cluster doesn't know the blockhash; the transaction needs a fresh one
+    BlockhashExpired,
+    // The node, network or confirmation timed out. The transaction may still land,
+    // so it is resent as-is until its blockhash expires.
+    Transient,
+    // The program or runtime rejected the transaction; retrying won't help
+    Permanent,
+}
+
+impl SendFailure {
+    pub fn classify(error: &ClientError) -> Self {
+        match error.get_transaction_error() {
+            Some(TransactionError::BlockhashNotFound) => return SendFailure::BlockhashExpired,
+            Some(_) => return SendFailure::Permanent,
+            None => {}
+        }
+
+        if is_endpoint_error(error) {
+            return SendFailure::Transient;
+        }
+
+        match error.kind() {
+            ClientErrorKind::RpcError(RpcError::RpcResponseError {
+                code:
+                    JSON_RPC_SERVER_ERROR_NODE_UNHEALTHY
+                    | JSON_RPC_SERVER_ERROR_BLOCK_NOT_AVAILABLE
+                    | JSON_RPC_SERVER_ERROR_MIN_CONTEXT_SLOT_NOT_REACHED,
+                ..
+            }) => SendFailure::Transient,
+            // Raised when confirmation gives up waiting, e.g. "unable to confirm transaction"
+            ClientErrorKind::RpcError(RpcError::ForUser(_)) => SendFailure::Transient,
+            _ => SendFailure::Permanent,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SendFailure::BlockhashExpired => "blockhash_expired",
+            SendFailure::Transient => "transient",
+            SendFailure::Permanent => "permanent",
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct RetryPolicy {
+    pub max_attempts: u32,
+    base_delay: Duration,
+    max_delay: Duration,
+}
+
+impl RetryPolicy {
+    pub fn new(max_attempts: u32, base_delay: Duration, max_delay: Duration) -> Self {
+        Self {
+            max_attempts: max_attempts.max(1),
+            base_delay,
+            max_delay,
+        }
+    }
+
+    pub fn from_config(config: &Config) -> Self {
+        Self::new(
+            config.tx_max_attempts,
+            Duration::from_millis(config.tx_retry_base_delay_ms),
+            Duration::from_millis(config.tx_retry_max_delay_ms),
+        )
+    }
+
+    // Exponential backoff with up to 50% jitter, so retries from many requests spread out
+    pub fn backoff(&self, attempt: u32) -> Duration {
+        let exponential = self
+            .base_delay
+            .saturating_mul(2u32.saturating_pow(attempt.saturating_sub(1)))
+            .min(self.max_delay);
+        let jitter = rand::thread_rng().gen_range(0.5..=1.0);
+
+        exponential.mul_f64(jitter)
+    }
+}
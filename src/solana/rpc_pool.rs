@@ -0,0 +1,291 @@
+// src/solana/rpc_pool.rs
+use crate::config::Config;
+use crate::errors::ApiError;
+use crate::models::rpc::RpcEndpointMetrics;
+use futures::stream::{FuturesUnordered, StreamExt};
+use rand::Rng;
+use solana_client::client_error::{ClientError, ClientErrorKind};
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_client::rpc_custom_error::JSON_RPC_SERVER_ERROR_NODE_UNHEALTHY;
+use solana_client::rpc_request::RpcError;
+use solana_sdk::commitment_config::CommitmentConfig;
+use std::future::Future;
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::{Duration, Instant};
+
+#[derive(Default)]
+struct EndpointState {
+    // Endpoints start healthy and are demoted by probes or failed requests
+    unhealthy: bool,
+    slot: u64,
+    requests: u64,
+    failures: u64,
+    total_latency_ms: u64,
+    last_error: Option<String>,
+}
+
+struct RpcEndpoint {
+    url: String,
+    weight: u32,
+    client: Arc<RpcClient>,
+    state: Mutex<EndpointState>,
+}
+
+impl RpcEndpoint {
+    fn lock(&self) -> MutexGuard<'_, EndpointState> {
+        // Metrics only; a poisoned lock still holds usable counters
+        self.state.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    fn record(&self, started: Instant, result: Result<(), &ClientError>) {
+        let mut state = self.lock();
+        state.requests += 1;
+        state.total_latency_ms += started.elapsed().as_millis() as u64;
+
+        if let Err(error) = result {
+            state.failures += 1;
+            state.last_error = Some(error.to_string());
+            if is_endpoint_error(error) {
+                state.unhealthy = true;
+            }
+        }
+    }
+
+    // The URL without its query string, which often carries a provider API key
+    fn display_url(&self) -> &str {
+        self.url.split('?').next().unwrap_or_default()
+    }
+}
+
+// Weighted set of RPC endpoints. Reads are hedged across endpoints, writes fail
+// over in order, and a background task keeps each endpoint's health current.
+pub struct RpcPool {
+    endpoints: Vec<RpcEndpoint>,
+    commitment: CommitmentConfig,
+    max_slot_lag: u64,
+    hedge_delay: Duration,
+}
+
+impl RpcPool {
+    pub fn from_config(config: &Config) -> Result<Self, ApiError> {
+        let endpoints = config
+            .solana_rpc_urls
+            .iter()
+            .map(|entry| {
+                // `url` or `url|weight`
+                let (url, weight) = match entry.rsplit_once('|') {
+                    Some((url, weight)) => {
+                        let weight = weight.trim().parse().map_err(|_| {
+                            ApiError::InternalServerError(format!("Invalid RPC endpoint weight: {}", entry))
+                        })?;
+                        (url.trim(), weight)
+                    }
+                    None => (entry.as_str(), 1),
+                };
+
+                Ok(RpcEndpoint {
+                    url: url.to_string(),
+                    weight,
+                    client: Arc::new(RpcClient::new_with_commitment(url.to_string(), config.commitment)),
+                    state: Mutex::new(EndpointState::default()),
+                })
+            })
+            .collect::<Result<Vec<_>, ApiError>>()?;
+
+        if endpoints.iter().all(|endpoint| endpoint.weight == 0) {
+            return Err(ApiError::InternalServerError(
+                "At least one RPC endpoint with a non-zero weight is required".to_string(),
+            ));
+        }
+
+        Ok(Self {
+            endpoints,
+            commitment: config.commitment,
+            max_slot_lag: config.rpc_max_slot_lag,
+            hedge_delay: Duration::from_millis(config.rpc_hedge_delay_ms),
+        })
+    }
+
+    pub fn commitment(&self) -> CommitmentConfig {
+        self.commitment
+    }
+
+    // Run a read against the preferred endpoint, racing the next one whenever
+    // the current attempt takes longer than the hedge delay. The first success wins.
+    pub async fn read<T, F, Fut>(&self, op: F) -> Result<T, ClientError>
+    where
+        F: Fn(Arc<RpcClient>) -> Fut,
+        Fut: Future<Output = Result<T, ClientError>>,
+    {
+        let candidates = self.ordered();
+        let mut in_flight = FuturesUnordered::new();
+        let mut last_error = None;
+
+        for endpoint in candidates {
+            in_flight.push(self.attempt(endpoint, &op));
+
+            loop {
+                let hedge = tokio::time::sleep(self.hedge_delay);
+                tokio::pin!(hedge);
+
+                tokio::select! {
+                    Some(result) = in_flight.next() => match result {
+                        Ok(value) => return Ok(value),
+                        // Not the endpoint's fault, so another endpoint won't do better
+                        Err(error) if !is_endpoint_error(&error) => return Err(error),
+                        Err(error) => {
+                            last_error = Some(error);
+                            if in_flight.is_empty() {
+                                break;
+                            }
+                        }
+                    },
+                    _ = &mut hedge => break,
+                }
+            }
+        }
+
+        // Every endpoint has been tried; wait out the stragglers
+        while let Some(result) = in_flight.next().await {
+            match result {
+                Ok(value) => return Ok(value),
+                Err(error) => last_error = Some(error),
+            }
+        }
+
+        Err(last_error.unwrap_or_else(no_endpoints))
+    }
+
+    // Run a write on one endpoint at a time, moving on only when the endpoint
+    // itself failed. Resending the same signed transaction is harmless because
+    // the cluster deduplicates by signature.
+    pub async fn write<T, F, Fut>(&self, op: F) -> Result<T, ClientError>
+    where
+        F: Fn(Arc<RpcClient>) -> Fut,
+        Fut: Future<Output = Result<T, ClientError>>,
+    {
+        let mut last_error = None;
+
+        for endpoint in self.ordered() {
+            match self.attempt(endpoint, &op).await {
+                Ok(value) => return Ok(value),
+                Err(error) if !is_endpoint_error(&error) => return Err(error),
+                Err(error) => {
+                    log::warn!("RPC endpoint {} failed, failing over: {}", endpoint.display_url(), error);
+                    last_error = Some(error);
+                }
+            }
+        }
+
+        Err(last_error.unwrap_or_else(no_endpoints))
+    }
+
+    async fn attempt<T, F, Fut>(&self, endpoint: &RpcEndpoint, op: &F) -> Result<T, ClientError>
+    where
+        F: Fn(Arc<RpcClient>) -> Fut,
+        Fut: Future<Output = Result<T, ClientError>>,
+    {
+        let started = Instant::now();
+        let result = op(endpoint.client.clone()).await;
+        endpoint.record(started, result.as_ref().map(|_| ()));
+        result
+    }
+
+    // Healthy endpoints in weighted random order, then unhealthy ones as a last resort
+    fn ordered(&self) -> Vec<&RpcEndpoint> {
+        let mut rng = rand::thread_rng();
+        let mut keyed: Vec<(bool, f64, &RpcEndpoint)> = self
+            .endpoints
+            .iter()
+            .filter(|endpoint| endpoint.weight > 0)
+            .map(|endpoint| {
+                // Weighted shuffle: a smaller key for a larger weight, on average
+                let key = -rng.gen::<f64>().ln() / endpoint.weight as f64;
+                (endpoint.lock().unhealthy, key, endpoint)
+            })
+            .collect();
+
+        keyed.sort_by(|a, b| a.0.cmp(&b.0).then(a.1.total_cmp(&b.1)));
+        keyed.into_iter().map(|(_, _, endpoint)| endpoint).collect()
+    }
+
+    // Probe every endpoint with getHealth and getSlot, marking those that fail or
+    // have fallen more than `max_slot_lag` slots behind the best one as unhealthy.
+    pub async fn check_health(&self) {
+        let probes = self.endpoints.iter().map(|endpoint| async move {
+            let health = endpoint.client.get_health().await;
+            let slot = endpoint.client.get_slot().await;
+            (endpoint, health.and(slot))
+        });
+        let results = futures::future::join_all(probes).await;
+
+        let best_slot = results
+            .iter()
+            .filter_map(|(_, result)| result.as_ref().ok().copied())
+            .max()
+            .unwrap_or_default();
+
+        for (endpoint, result) in results {
+            let mut state = endpoint.lock();
+            match result {
+                Ok(slot) => {
+                    state.slot = slot;
+                    state.unhealthy = best_slot.saturating_sub(slot) > self.max_slot_lag;
+                    if state.unhealthy {
+                        state.last_error = Some(format!("{} slots behind", best_slot - slot));
+                    }
+                }
+                Err(error) => {
+                    state.unhealthy = true;
+                    state.last_error = Some(error.to_string());
+                }
+            }
+        }
+    }
+
+    pub fn spawn_health_checks(self: Arc<Self>, interval: Duration) {
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            loop {
+                ticker.tick().await;
+                self.check_health().await;
+            }
+        });
+    }
+
+    pub fn metrics(&self) -> Vec<RpcEndpointMetrics> {
+        self.endpoints
+            .iter()
+            .map(|endpoint| {
+                let state = endpoint.lock();
+                RpcEndpointMetrics {
+                    url: endpoint.display_url().to_string(),
+                    weight: endpoint.weight,
+                    healthy: !state.unhealthy,
+                    slot: state.slot,
+                    requests: state.requests,
+                    failures: state.failures,
+                    average_latency_ms: state.total_latency_ms.checked_div(state.requests).unwrap_or_default(),
+                    last_error: state.last_error.clone(),
+                }
+            })
+            .collect()
+    }
+}
+
+// Errors that say something about the endpoint rather than the request:
+// transport failures, rate limits and nodes reporting themselves unhealthy
+pub fn is_endpoint_error(error: &ClientError) -> bool {
+    match error.kind() {
+        ClientErrorKind::Io(_) | ClientErrorKind::Reqwest(_) => true,
+        ClientErrorKind::RpcError(RpcError::RpcRequestError(_)) => true,
+        ClientErrorKind::RpcError(RpcError::RpcResponseError { code, .. }) => {
+            *code == JSON_RPC_SERVER_ERROR_NODE_UNHEALTHY
+        }
+        _ => false,
+    }
+}
+
+fn no_endpoints() -> ClientError {
+    ClientErrorKind::Custom("No RPC endpoints available".to_string()).into()
+}
@@ -0,0 +1,19 @@
+// src/solana/simulation.rs
+use crate::errors::ApiError;
+use actix_web::{web, HttpRequest};
+use std::collections::HashMap;
+
+pub const DRY_RUN_QUERY_PARAM: &str = "dry_run";
+
+// `?dry_run=true` asks for a simulation instead of a send
+pub fn requested(req: &HttpRequest) -> Result<bool, ApiError> {
+    let query = web::Query::<HashMap<String, String>>::from_query(req.query_string())
+        .map_err(|e| ApiError::BadRequest(e.to_string()))?;
+
+    match query.get(DRY_RUN_QUERY_PARAM) {
+        Some(value) => value
+            .parse()
+            .map_err(|_| ApiError::BadRequest(format!("{} must be true or false", DRY_RUN_QUERY_PARAM))),
+        None => Ok(false),
+    }
+}
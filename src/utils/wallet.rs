@@ -1,10 +1,6 @@
 // src/utils/wallet.rs
 use crate::errors::ApiError;
-use solana_sdk::{
-    signature::{Keypair, Signature},
-    signer::keypair::keypair_from_seed,
-    pubkey::Pubkey,
-};
+use solana_sdk::pubkey::Pubkey;
 use std::str::FromStr;
 use ed25519_dalek::{PublicKey, Signature as Ed25519Signature, Verifier};
 use base58::FromBase58;
@@ -37,22 +33,7 @@ pub fn verify_wallet_signature(
     Ok(true)
 }
 
-// This is a simplified approach for demo purposes
-// In production, you'd use a proper wallet adapter
-pub fn get_keypair_from_signature(public_key: &str) -> Result<Keypair, ApiError> {
-    // This is just a placeholder implementation
-    // In a real application, you would have a more secure way to get the keypair
-    // based on the authenticated wallet
-    
-    // WARNING: This is not secure for production use!
-    // It generates a deterministic keypair from the public key
-    // just for demonstration purposes
-    let seed = Pubkey::from_str(public_key)
-        .map_err(|_| ApiError::BadRequest("Invalid public key".to_string()))?
-        .to_bytes();
-    
-    let keypair = keypair_from_seed(&seed)
-        .map_err(|_| ApiError::InternalServerError("Failed to generate keypair".to_string()))?;
-    
-    Ok(keypair)
-}
\ No newline at end of file
+pub fn parse_pubkey(public_key: &str) -> Result<Pubkey, ApiError> {
+    Pubkey::from_str(public_key)
+        .map_err(|_| ApiError::BadRequest("Invalid public key".to_string()))
+}
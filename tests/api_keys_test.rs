@@ -0,0 +1,114 @@
+// tests/api_keys_test.rs
+mod common;
+
+use actix_web::{http::StatusCode, test, web, App, ResponseError};
+use common::MockRpc;
+use serde_json::json;
+use std::sync::Arc;
+use todo_api::{
+    auth::api_keys::ApiKeyStore,
+    config,
+    errors::ApiError,
+    routes::todo_routes,
+    signer::{disabled::DisabledSigner, TransactionSigner},
+    solana::{idl::Idl, rpc_pool::RpcPool, SolanaService},
+};
+
+const WALLET: &str = "8z5jfiFVgyBCdMCYNgBivVNMmJgwQpEMjHvd4dVCsRJv";
+
+async fn read_key(store: &ApiKeyStore) -> (String, String) {
+    let (key, response) = store.create(WALLET, "ci", &["todos:read".to_string()]).await.unwrap();
+    (key, response.id)
+}
+
+fn assert_unauthorized(result: Result<impl std::fmt::Debug, ApiError>) {
+    match result {
+        Err(e) => assert_eq!(e.status_code(), StatusCode::UNAUTHORIZED),
+        Ok(claims) => panic!("expected Unauthorized, got {:?}", claims),
+    }
+}
+
+#[actix_web::test]
+async fn test_key_authenticates_as_its_wallet() {
+    let store = ApiKeyStore::new();
+    let (key, id) = read_key(&store).await;
+    
+    let claims = store.authenticate(&key).unwrap();
+    assert_eq!(claims.sub, WALLET);
+    assert_eq!(claims.sid, id);
+    assert_eq!(claims.scopes, vec!["todos:read".to_string()]);
+}
+
+#[actix_web::test]
+async fn test_wrong_secret_is_rejected() {
+    let store = ApiKeyStore::new();
+    let (key, id) = read_key(&store).await;
+    
+    // Well formed, for a key that exists, with another key's secret
+    let (other_key, _) = read_key(&store).await;
+    let other_secret = other_key.rsplit('_').next().unwrap();
+    let forged = format!("tdk_{}_{}", id, other_secret);
+    
+    assert_unauthorized(store.authenticate(&forged));
+    store.authenticate(&key).unwrap();
+}
+
+#[actix_web::test]
+async fn test_unknown_id_is_rejected() {
+    let store = ApiKeyStore::new();
+    let (key, id) = read_key(&store).await;
+    
+    assert_unauthorized(store.authenticate(&key.replace(&id, "UnknownKeyId")));
+}
+
+#[actix_web::test]
+async fn test_malformed_key_is_rejected() {
+    let store = ApiKeyStore::new();
+    let (key, _) = read_key(&store).await;
+    
+    assert_unauthorized(store.authenticate(&key.replacen("tdk_", "tdx_", 1)));
+    assert_unauthorized(store.authenticate(key.trim_start_matches("tdk_")));
+    assert_unauthorized(store.authenticate("tdk"));
+    assert_unauthorized(store.authenticate(""));
+}
+
+#[actix_web::test]
+async fn test_read_key_can_list_but_not_delete_todos() {
+    // A cluster without any todos
+    let rpc = MockRpc::start(|method, _| match method {
+        "getProgramAccounts" => Some(json!([])),
+        _ => None,
+    });
+    let mut config = config::load_config();
+    config.solana_rpc_urls = vec![rpc.url.clone()];
+    let rpc_pool = Arc::new(RpcPool::from_config(&config).unwrap());
+    let idl = Arc::new(Idl::from_file("idl/todo_program.json").unwrap());
+    let solana_service = SolanaService::new(&config, rpc_pool, idl, None).unwrap();
+    let signer: Arc<dyn TransactionSigner> = Arc::new(DisabledSigner);
+    
+    let store = ApiKeyStore::new();
+    let (key, _) = read_key(&store).await;
+    
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(store))
+            .app_data(web::Data::new(solana_service))
+            .app_data(web::Data::from(signer))
+            .service(web::scope("/api").configure(todo_routes::config))
+    ).await;
+    
+    let req = test::TestRequest::get()
+        .uri("/api/todos")
+        .insert_header(("Authorization", format!("ApiKey {}", key)))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), StatusCode::OK);
+    
+    let req = test::TestRequest::delete()
+        .uri("/api/todos/1")
+        .insert_header(("Authorization", format!("ApiKey {}", key)))
+        .to_request();
+    let resp = test::try_call_service(&app, req).await;
+    assert_eq!(resp.err().unwrap().error_response().status(), StatusCode::FORBIDDEN);
+    assert!(!rpc.called("sendTransaction"));
+}
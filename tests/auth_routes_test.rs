@@ -0,0 +1,138 @@
+// tests/auth_routes_test.rs
+use actix_web::{http::StatusCode, test, web, App};
+use todo_api::{
+    auth::{api_keys::ApiKeyStore, keys::JwtKeys, revocation::RevocationStore, session::SessionStore},
+    config,
+    models::auth::{Claims, Principal},
+    routes::auth_routes,
+};
+
+fn jwt_keys() -> JwtKeys {
+    let mut config = config::load_config();
+    config.jwt_secret = Some("test-secret".to_string());
+    config.jwt_algorithm = "HS256".to_string();
+    JwtKeys::from_config(&config).unwrap()
+}
+
+fn claims(sid: &str, scopes: &[&str]) -> Claims {
+    let now = chrono::Utc::now().timestamp() as usize;
+    Claims {
+        sub: "8z5jfiFVgyBCdMCYNgBivVNMmJgwQpEMjHvd4dVCsRJv".to_string(),
+        exp: now + 3600,
+        iat: now,
+        jti: format!("{}-token", sid),
+        sid: sid.to_string(),
+        scopes: scopes.iter().map(|scope| scope.to_string()).collect(),
+        roles: vec![],
+        principal: Principal::WalletSession,
+    }
+}
+
+fn token(jwt_keys: &JwtKeys, scopes: &[&str]) -> String {
+    jwt_keys.encode(&claims("test-session", scopes)).unwrap()
+}
+
+#[actix_web::test]
+async fn test_narrowed_token_can_log_out() {
+    let jwt_keys = jwt_keys();
+    let token = token(&jwt_keys, &["todos:read"]);
+    
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(jwt_keys))
+            .app_data(web::Data::new(RevocationStore::new(None, 3600).unwrap()))
+            .app_data(web::Data::new(SessionStore::new(3600)))
+            .configure(auth_routes::config)
+    ).await;
+    
+    let logout = || {
+        test::TestRequest::post()
+            .uri("/auth/logout")
+            .insert_header(("Authorization", format!("Bearer {}", token)))
+            .to_request()
+    };
+    
+    // A token without account:manage still ends its own session
+    let resp = test::call_service(&app, logout()).await;
+    assert_eq!(resp.status(), StatusCode::NO_CONTENT);
+    
+    // and can't be used afterwards
+    let resp = test::try_call_service(&app, logout()).await;
+    assert_eq!(resp.err().unwrap().error_response().status(), StatusCode::UNAUTHORIZED);
+}
+
+#[actix_web::test]
+async fn test_missing_revocation_store_fails_closed() {
+    let jwt_keys = jwt_keys();
+    let token = token(&jwt_keys, &["todos:read"]);
+    
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(jwt_keys))
+            .app_data(web::Data::new(SessionStore::new(3600)))
+            .configure(auth_routes::config)
+    ).await;
+    
+    let req = test::TestRequest::post()
+        .uri("/auth/logout")
+        .insert_header(("Authorization", format!("Bearer {}", token)))
+        .to_request();
+    let resp = test::try_call_service(&app, req).await;
+    assert_eq!(resp.err().unwrap().error_response().status(), StatusCode::INTERNAL_SERVER_ERROR);
+}
+
+#[actix_web::test]
+async fn test_logout_all_spares_tokens_issued_the_same_second() {
+    let revocations = RevocationStore::new(None, 3600).unwrap();
+    let old = claims("old-session", &["todos:read"]);
+    
+    revocations.revoke_wallet(&old.sub, std::slice::from_ref(&old.sid)).unwrap();
+    
+    // A login right after shares the cutoff's second but not its session
+    let new = claims("new-session", &["todos:read"]);
+    assert!(revocations.is_revoked(&old).unwrap());
+    assert!(!revocations.is_revoked(&new).unwrap());
+    
+    // Tokens from any earlier second are revoked whatever their session
+    let earlier = Claims { iat: old.iat - 1, ..claims("unknown-session", &[]) };
+    assert!(revocations.is_revoked(&earlier).unwrap());
+}
+
+#[actix_web::test]
+async fn test_api_key_cannot_log_out_the_wallet() {
+    let jwt_keys = jwt_keys();
+    // Issued a while ago, so a wallet-wide revocation now would cover it
+    let wallet_claims = Claims { iat: claims("test-session", &[]).iat - 5, ..claims("test-session", &["todos:read"]) };
+    let wallet_token = jwt_keys.encode(&wallet_claims).unwrap();
+    
+    let api_keys = ApiKeyStore::new();
+    let (key, _) = api_keys
+        .create(&wallet_claims.sub, "ci", &["todos:read".to_string()])
+        .await
+        .unwrap();
+    
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(jwt_keys))
+            .app_data(web::Data::new(api_keys))
+            .app_data(web::Data::new(RevocationStore::new(None, 3600).unwrap()))
+            .app_data(web::Data::new(SessionStore::new(3600)))
+            .configure(auth_routes::config)
+    ).await;
+    
+    let post = |uri: &str, authorization: String| {
+        test::TestRequest::post()
+            .uri(uri)
+            .insert_header(("Authorization", authorization))
+            .to_request()
+    };
+    
+    for uri in ["/auth/logout", "/auth/logout-all"] {
+        let resp = test::try_call_service(&app, post(uri, format!("ApiKey {}", key))).await;
+        assert_eq!(resp.err().unwrap().error_response().status(), StatusCode::FORBIDDEN);
+    }
+    
+    // The wallet's own token was left alone
+    let resp = test::call_service(&app, post("/auth/logout", format!("Bearer {}", wallet_token))).await;
+    assert_eq!(resp.status(), StatusCode::NO_CONTENT);
+}
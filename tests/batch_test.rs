@@ -0,0 +1,116 @@
+// tests/batch_test.rs
+mod common;
+
+use common::{cluster, MockRpc};
+use solana_sdk::packet::PACKET_DATA_SIZE;
+use solana_sdk::pubkey::Pubkey;
+use std::path::PathBuf;
+use std::sync::Arc;
+use todo_api::{
+    config::{self, Config},
+    models::todo::{BatchOperation, BatchResponse, CreateTodoRequest},
+    signer::{file::FileSigner, TransactionSigner},
+    solana::{idl::Idl, rpc_pool::RpcPool, SolanaService},
+};
+
+const WALLET: &str = "8z5jfiFVgyBCdMCYNgBivVNMmJgwQpEMjHvd4dVCsRJv";
+
+struct Batched {
+    response: BatchResponse,
+    service: SolanaService,
+    owner: Pubkey,
+    rpc: MockRpc,
+}
+
+fn creates(count: u64, description: &str) -> Vec<BatchOperation> {
+    (1..=count)
+        .map(|task_id| {
+            BatchOperation::Create(CreateTodoRequest {
+                task_id,
+                description: description.to_string(),
+                due_date: 0,
+            })
+        })
+        .collect()
+}
+
+async fn batch(mut config: Config, operations: Vec<BatchOperation>) -> Batched {
+    let rpc = MockRpc::start(cluster);
+    config.solana_rpc_urls = vec![rpc.url.clone()];
+    let rpc_pool = Arc::new(RpcPool::from_config(&config).unwrap());
+    let idl = Arc::new(Idl::from_file("idl/todo_program.json").unwrap());
+    let service = SolanaService::new(&config, rpc_pool, idl, None).unwrap();
+    
+    // A directory of its own, so runs don't share keys
+    let key_dir: PathBuf = std::env::temp_dir().join(format!("todo_api_test_keys_{}", Pubkey::new_unique()));
+    let signer = FileSigner::new(key_dir.clone()).unwrap();
+    let owner = signer.create(WALLET).await.unwrap();
+    
+    let response = service.batch(&signer, WALLET, operations, None).await.unwrap();
+    let _ = std::fs::remove_dir_all(key_dir);
+    
+    Batched { response, service, owner, rpc }
+}
+
+// Results come back in request order, each with the transaction it was packed into,
+// and the transactions sent carry the operations in that same order
+fn assert_packed_in_order(batched: &Batched, operations: u64) {
+    let results = &batched.response.results;
+    assert_eq!(results.len() as u64, operations);
+    for (position, result) in results.iter().enumerate() {
+        assert_eq!(result.index, position);
+        assert_eq!(result.task_id, position as u64 + 1);
+        assert!(result.success, "operation {} failed: {:?}", position, result.error);
+    }
+    
+    let sent = batched.rpc.sent();
+    assert_eq!(sent.len(), batched.response.transactions.len());
+    
+    let mut packed = Vec::new();
+    for (transaction, receipt) in sent.iter().zip(&batched.response.transactions) {
+        assert_eq!(transaction.signatures[0].to_string(), receipt.signature);
+        assert!(bincode::serialized_size(transaction).unwrap() <= PACKET_DATA_SIZE as u64);
+        
+        let keys = &transaction.message.account_keys;
+        for instruction in &transaction.message.instructions {
+            // Each create's first account is the todo it creates
+            let Some(todo) = instruction.accounts.first().map(|&index| keys[index as usize]) else {
+                continue;
+            };
+            let Some(task_id) = (1..=operations).find(|&id| batched.service.todo_address(&batched.owner, id) == todo) else {
+                continue;
+            };
+            packed.push(task_id);
+            assert_eq!(results[task_id as usize - 1].signature.as_deref(), Some(receipt.signature.as_str()));
+        }
+    }
+    assert_eq!(packed, (1..=operations).collect::<Vec<_>>());
+}
+
+#[actix_web::test]
+async fn test_batch_splits_at_the_packet_size() {
+    // Full-length descriptions, so only a few creates fit in a packet
+    let batched = batch(config::load_config(), creates(6, &"d".repeat(280))).await;
+    
+    let transactions = batched.response.transactions.len();
+    assert!(transactions > 1, "expected a split, got {} transaction(s)", transactions);
+    assert!(transactions < 6, "expected creates packed together, got {} transactions", transactions);
+    assert_packed_in_order(&batched, 6);
+}
+
+#[actix_web::test]
+async fn test_batch_splits_at_the_compute_limit() {
+    // Two operations fit under the 1.4M compute unit cap, a third doesn't
+    let mut config = config::load_config();
+    config.batch_compute_units_per_operation = 500_000;
+    let batched = batch(config, creates(5, "short")).await;
+    
+    let limits: Vec<Option<u32>> = batched
+        .response
+        .transactions
+        .iter()
+        .map(|receipt| receipt.compute_unit_limit)
+        .collect();
+    assert_eq!(limits, vec![Some(1_000_000), Some(1_000_000), Some(500_000)]);
+    assert_packed_in_order(&batched, 5);
+}
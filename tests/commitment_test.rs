@@ -0,0 +1,64 @@
+// tests/commitment_test.rs
+use actix_web::{http::StatusCode, test, ResponseError};
+use solana_sdk::commitment_config::CommitmentConfig;
+use todo_api::solana::commitment::{self, COMMITMENT_HEADER};
+
+#[actix_web::test]
+async fn test_parses_supported_levels() {
+    assert_eq!(commitment::parse("processed"), Some(CommitmentConfig::processed()));
+    assert_eq!(commitment::parse("confirmed"), Some(CommitmentConfig::confirmed()));
+    assert_eq!(commitment::parse(" Finalized "), Some(CommitmentConfig::finalized()));
+    
+    // Deprecated aliases and anything else are refused
+    assert_eq!(commitment::parse("max"), None);
+    assert_eq!(commitment::parse("recent"), None);
+    assert_eq!(commitment::parse(""), None);
+}
+
+#[actix_web::test]
+async fn test_reads_the_query_parameter_without_a_header() {
+    let req = test::TestRequest::get().uri("/api/todos?commitment=processed").to_http_request();
+    
+    assert_eq!(commitment::from_request(&req).unwrap(), Some(CommitmentConfig::processed()));
+}
+
+#[actix_web::test]
+async fn test_reads_the_header() {
+    let req = test::TestRequest::get()
+        .uri("/api/todos")
+        .insert_header((COMMITMENT_HEADER, "finalized"))
+        .to_http_request();
+    
+    assert_eq!(commitment::from_request(&req).unwrap(), Some(CommitmentConfig::finalized()));
+}
+
+#[actix_web::test]
+async fn test_query_parameter_wins_over_the_header() {
+    let req = test::TestRequest::get()
+        .uri("/api/todos?commitment=confirmed")
+        .insert_header((COMMITMENT_HEADER, "finalized"))
+        .to_http_request();
+    
+    assert_eq!(commitment::from_request(&req).unwrap(), Some(CommitmentConfig::confirmed()));
+}
+
+#[actix_web::test]
+async fn test_absent_commitment_uses_the_default() {
+    let req = test::TestRequest::get().uri("/api/todos").to_http_request();
+    
+    assert_eq!(commitment::from_request(&req).unwrap(), None);
+}
+
+#[actix_web::test]
+async fn test_unknown_levels_are_bad_requests() {
+    let from_query = test::TestRequest::get().uri("/api/todos?commitment=single").to_http_request();
+    let from_header = test::TestRequest::get()
+        .uri("/api/todos")
+        .insert_header((COMMITMENT_HEADER, "root"))
+        .to_http_request();
+    
+    for req in [from_query, from_header] {
+        let error = commitment::from_request(&req).unwrap_err();
+        assert_eq!(error.status_code(), StatusCode::BAD_REQUEST);
+    }
+}
@@ -0,0 +1,167 @@
+// tests/common/mod.rs
+// Not every test binary uses every helper
+#![allow(dead_code)]
+
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use serde_json::{json, Value};
+use solana_sdk::hash::Hash;
+use solana_sdk::transaction::Transaction;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+type Handler = dyn Fn(&str, &Value) -> Option<Value> + Send + Sync;
+
+// A JSON-RPC endpoint on localhost answering each method from a closure.
+// Methods the closure doesn't answer get a "method not found" error.
+pub struct MockRpc {
+    pub url: String,
+    calls: Arc<Mutex<Vec<(String, Value)>>>,
+}
+
+impl MockRpc {
+    pub fn start<F>(handler: F) -> Self
+    where
+        F: Fn(&str, &Value) -> Option<Value> + Send + Sync + 'static,
+    {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let calls = Arc::new(Mutex::new(Vec::new()));
+        let handler: Arc<Handler> = Arc::new(handler);
+    
+        let recorded = calls.clone();
+        thread::spawn(move || {
+            for stream in listener.incoming().flatten() {
+                let handler = handler.clone();
+                let recorded = recorded.clone();
+                thread::spawn(move || serve(stream, &*handler, &recorded));
+            }
+        });
+    
+        MockRpc { url, calls }
+    }
+    
+    pub fn called(&self, method: &str) -> bool {
+        self.calls.lock().unwrap().iter().any(|(called, _)| called == method)
+    }
+    
+    // The params of every call to `method` so far, in order
+    pub fn params(&self, method: &str) -> Vec<Value> {
+        self.calls
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|(called, _)| called == method)
+            .map(|(_, params)| params.clone())
+            .collect()
+    }
+    
+    // Every transaction sent so far, in order
+    pub fn sent(&self) -> Vec<Transaction> {
+        self.params("sendTransaction").iter().filter_map(decode_transaction).collect()
+    }
+}
+
+// Answer requests on one keep-alive connection until the client closes it
+fn serve(stream: TcpStream, handler: &Handler, calls: &Mutex<Vec<(String, Value)>>) {
+    let mut writer = stream.try_clone().unwrap();
+    let mut reader = BufReader::new(stream);
+    
+    loop {
+        let mut content_length = 0;
+        let mut line = String::new();
+        loop {
+            line.clear();
+            if reader.read_line(&mut line).unwrap_or(0) == 0 {
+                return;
+            }
+            let header = line.trim_end();
+            if header.is_empty() {
+                break;
+            }
+            if let Some((name, value)) = header.split_once(':') {
+                if name.eq_ignore_ascii_case("content-length") {
+                    content_length = value.trim().parse().unwrap_or(0);
+                }
+            }
+        }
+    
+        let mut body = vec![0; content_length];
+        if reader.read_exact(&mut body).is_err() {
+            return;
+        }
+        let request: Value = serde_json::from_slice(&body).unwrap_or_default();
+        let method = request["method"].as_str().unwrap_or_default().to_string();
+        calls.lock().unwrap().push((method.clone(), request["params"].clone()));
+    
+        let result = handler(&method, &request["params"]).or_else(|| match method.as_str() {
+            // Asked by the client before sending, to pick an encoding
+            "getVersion" => Some(json!({ "solana-core": "1.16.27", "feature-set": 0 })),
+            _ => None,
+        });
+        let response = match result {
+            Some(result) => json!({ "jsonrpc": "2.0", "id": request["id"], "result": result }),
+            None => json!({
+                "jsonrpc": "2.0",
+                "id": request["id"],
+                "error": { "code": -32601, "message": "Method not found" },
+            }),
+        };
+    
+        // In one write, so it isn't held back waiting on the client's ACK
+        let body = response.to_string();
+        let response = format!(
+            "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\n\r\n{}",
+            body.len(),
+            body
+        );
+        if writer.write_all(response.as_bytes()).is_err() {
+            return;
+        }
+    }
+}
+
+// A response wrapped with the context the client expects for `value` results
+pub fn with_context(value: Value) -> Value {
+    json!({ "context": { "slot": 1 }, "value": value })
+}
+
+fn decode_transaction(params: &Value) -> Option<Transaction> {
+    let bytes = BASE64.decode(params[0].as_str()?).ok()?;
+    bincode::deserialize(&bytes).ok()
+}
+
+pub const RENT: u64 = 2_000_000;
+pub const FEE: u64 = 5000;
+
+// A cluster where building, sending and confirming a transaction all succeed,
+// and every transaction sent lands finalized
+pub fn cluster(method: &str, params: &Value) -> Option<Value> {
+    match method {
+        "getMinimumBalanceForRentExemption" => Some(json!(RENT)),
+        "getFeeForMessage" => Some(with_context(json!(FEE))),
+        "getRecentPrioritizationFees" => Some(json!([])),
+        "getLatestBlockhash" => Some(with_context(json!({
+            "blockhash": Hash::new_unique().to_string(),
+            "lastValidBlockHeight": 1000,
+        }))),
+        "sendTransaction" => Some(json!(decode_transaction(params)?.signatures[0].to_string())),
+        "getSignatureStatuses" => {
+            let statuses: Vec<Value> = params[0]
+                .as_array()?
+                .iter()
+                .map(|_| json!({
+                    "slot": 1,
+                    "confirmations": null,
+                    "err": null,
+                    "status": { "Ok": null },
+                    "confirmationStatus": "finalized",
+                }))
+                .collect();
+            Some(with_context(json!(statuses)))
+        }
+        _ => None,
+    }
+}
@@ -0,0 +1,86 @@
+// tests/indexer_test.rs
+use solana_sdk::commitment_config::CommitmentConfig;
+use solana_sdk::pubkey::Pubkey;
+use std::time::Duration;
+use todo_api::solana::idl::Idl;
+use todo_api::solana::indexer::{websocket_url, TodoIndexer};
+use todo_api::solana::{decode_todo, TodoAccount, TODO_ACCOUNT, TODO_ACCOUNT_SPACE};
+
+fn idl() -> Idl {
+    Idl::from_file("idl/todo_program.json").unwrap()
+}
+
+fn indexer() -> TodoIndexer {
+    TodoIndexer::new(
+        "ws://localhost:8900".to_string(),
+        CommitmentConfig::confirmed(),
+        Duration::from_secs(600),
+        150,
+    )
+}
+
+fn account_data(idl: &Idl, task_id: u64, description: &str, owner: &Pubkey) -> Vec<u8> {
+    let mut data = idl.account_discriminator(TODO_ACCOUNT).unwrap().to_vec();
+    data.extend(task_id.to_le_bytes());
+    data.extend((description.len() as u32).to_le_bytes());
+    data.extend(description.as_bytes());
+    data.push(0);
+    data.extend(0i64.to_le_bytes());
+    data.extend(owner.to_bytes());
+    data.resize(TODO_ACCOUNT_SPACE, 0);
+    data
+}
+
+fn todo(idl: &Idl, address: &Pubkey, task_id: u64, description: &str, owner: &Pubkey) -> TodoAccount {
+    decode_todo(idl, address, &account_data(idl, task_id, description, owner)).unwrap()
+}
+
+#[test]
+fn test_websocket_url_from_rpc_url() {
+    assert_eq!(
+        websocket_url("https://api.devnet.solana.com|3").as_deref(),
+        Some("wss://api.devnet.solana.com")
+    );
+    assert_eq!(websocket_url("http://localhost:8899").as_deref(), Some("ws://localhost:8899"));
+    assert_eq!(websocket_url("localhost:8899"), None);
+}
+
+#[test]
+fn test_serves_reads_only_once_built() {
+    let idl = idl();
+    let indexer = indexer();
+    let owner = Pubkey::new_unique();
+    let address = Pubkey::new_unique();
+    assert!(indexer.todos_for(&owner).is_none());
+    
+    indexer.replace(100, vec![(address, todo(&idl, &address, 1, "Scanned", &owner))]);
+    let (todos, slot) = indexer.todos_for(&owner).unwrap();
+    assert_eq!(slot, 100);
+    assert_eq!(todos.len(), 1);
+    assert!(indexer.todos_for(&Pubkey::new_unique()).unwrap().0.is_empty());
+    
+    indexer.invalidate();
+    assert!(indexer.todos_for(&owner).is_none());
+}
+
+#[test]
+fn test_notifications_apply_in_slot_order() {
+    let idl = idl();
+    let indexer = indexer();
+    let owner = Pubkey::new_unique();
+    let address = Pubkey::new_unique();
+    indexer.replace(100, vec![(address, todo(&idl, &address, 1, "Scanned", &owner))]);
+    
+    // A notification older than the scan is stale
+    indexer.update(&idl, &address, 90, &account_data(&idl, 1, "Stale", &owner));
+    assert_eq!(indexer.todos_for(&owner).unwrap().0[0].description, "Scanned");
+    
+    indexer.update(&idl, &address, 110, &account_data(&idl, 1, "Updated", &owner));
+    let (todos, slot) = indexer.todos_for(&owner).unwrap();
+    assert_eq!(todos[0].description, "Updated");
+    assert_eq!(slot, 110);
+    
+    // A closed account no longer decodes and leaves the index
+    indexer.update(&idl, &address, 120, &[]);
+    assert!(indexer.todos_for(&owner).unwrap().0.is_empty());
+}
@@ -0,0 +1,40 @@
+// tests/jobs_test.rs
+use actix_web::http::header::{HeaderMap, HeaderName, HeaderValue};
+use std::time::Duration;
+use todo_api::errors::ApiError;
+use todo_api::models::job::JobStatus;
+use todo_api::models::todo::BatchOperation;
+use todo_api::solana::jobs::{prefers_async, JobQueue};
+
+fn prefer(value: &'static str) -> HeaderMap {
+    let mut headers = HeaderMap::new();
+    headers.insert(HeaderName::from_static("prefer"), HeaderValue::from_static(value));
+    headers
+}
+
+#[test]
+fn test_prefers_async() {
+    assert!(prefers_async(&prefer("respond-async")));
+    assert!(prefers_async(&prefer("return=minimal, Respond-Async; wait=10")));
+    assert!(!prefers_async(&prefer("return=representation")));
+    assert!(!prefers_async(&HeaderMap::new()));
+}
+
+#[actix_web::test]
+async fn test_jobs_are_queued_per_wallet_until_full() {
+    let queue = JobQueue::new(1, Duration::from_secs(60));
+    
+    let job = queue
+        .enqueue("wallet-a", BatchOperation::Delete { task_id: 7 }, None, None)
+        .unwrap();
+    assert_eq!(job.status, JobStatus::Queued);
+    assert_eq!(job.operation, "delete");
+    assert_eq!(queue.get("wallet-a", &job.id).await.unwrap().task_id, 7);
+    
+    // Another wallet can't see the job
+    assert!(matches!(queue.get("wallet-b", &job.id).await, Err(ApiError::NotFound(_))));
+    
+    // No workers are draining the queue, so the second job doesn't fit
+    let full = queue.enqueue("wallet-a", BatchOperation::Delete { task_id: 8 }, None, None);
+    assert!(matches!(full, Err(ApiError::ServiceUnavailable(_))));
+}
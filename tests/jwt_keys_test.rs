@@ -0,0 +1,130 @@
+// tests/jwt_keys_test.rs
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use todo_api::auth::keys::JwtKeys;
+use todo_api::config::{self, Config};
+use todo_api::models::auth::{Claims, Principal};
+
+fn key_config(algorithm: &str, kid: &str, private_key: &str, public_key: &str, additional: &[&str]) -> Config {
+    let mut config = config::load_config();
+    config.jwt_secret = None;
+    config.jwt_algorithm = algorithm.to_string();
+    config.jwt_key_id = kid.to_string();
+    config.jwt_private_key_path = Some(format!("tests/fixtures/{}", private_key));
+    config.jwt_public_key_path = Some(format!("tests/fixtures/{}", public_key));
+    config.jwt_additional_public_keys = additional.iter().map(|entry| entry.to_string()).collect();
+    config
+}
+
+fn claims() -> Claims {
+    Claims {
+        sub: "8z5jfiFVgyBCdMCYNgBivVNMmJgwQpEMjHvd4dVCsRJv".to_string(),
+        exp: chrono::Utc::now().timestamp() as usize + 3600,
+        iat: chrono::Utc::now().timestamp() as usize,
+        jti: "test-token".to_string(),
+        sid: "test-session".to_string(),
+        scopes: vec!["todos:read".to_string()],
+        roles: vec![],
+        principal: Principal::WalletSession,
+    }
+}
+
+#[test]
+fn test_loads_ed25519_and_rsa_keys() {
+    let ed25519 = JwtKeys::from_config(&key_config(
+        "EdDSA",
+        "ed-1",
+        "ed25519_private.pem",
+        "ed25519_public.pem",
+        &[],
+    ))
+    .unwrap();
+    let token = ed25519.encode(&claims()).unwrap();
+    assert_eq!(ed25519.decode(&token).unwrap().sub, claims().sub);
+    
+    let jwks = serde_json::to_value(ed25519.jwks()).unwrap();
+    assert_eq!(jwks["keys"][0]["kid"], "ed-1");
+    assert_eq!(jwks["keys"][0]["kty"], "OKP");
+    assert_eq!(jwks["keys"][0]["crv"], "Ed25519");
+    let x = URL_SAFE_NO_PAD.decode(jwks["keys"][0]["x"].as_str().unwrap()).unwrap();
+    assert_eq!(x.len(), 32);
+    
+    // The same RSA key given as a SubjectPublicKeyInfo and as a PKCS#1 key
+    let rsa = JwtKeys::from_config(&key_config(
+        "RS256",
+        "rsa-1",
+        "rsa_private.pem",
+        "rsa_public.pem",
+        &["rsa-0=RS256:tests/fixtures/rsa_public_pkcs1.pem"],
+    ))
+    .unwrap();
+    let token = rsa.encode(&claims()).unwrap();
+    assert_eq!(rsa.decode(&token).unwrap().jti, "test-token");
+    
+    let jwks = serde_json::to_value(rsa.jwks()).unwrap();
+    let keys = jwks["keys"].as_array().unwrap();
+    assert_eq!(keys.len(), 2);
+    assert_eq!(keys[0]["kty"], "RSA");
+    assert_eq!(keys[0]["alg"], "RS256");
+    assert_eq!(keys[0]["e"], "AQAB");
+    assert_eq!(keys[0]["n"], keys[1]["n"]);
+    let n = URL_SAFE_NO_PAD.decode(keys[0]["n"].as_str().unwrap()).unwrap();
+    assert_eq!(n.len(), 256);
+}
+
+#[test]
+fn test_rotated_out_key_still_verifies() {
+    let old = JwtKeys::from_config(&key_config(
+        "EdDSA",
+        "old",
+        "ed25519_private.pem",
+        "ed25519_public.pem",
+        &[],
+    ))
+    .unwrap();
+    let token = old.encode(&claims()).unwrap();
+    
+    let rotated = JwtKeys::from_config(&key_config(
+        "EdDSA",
+        "new",
+        "ed25519_other_private.pem",
+        "ed25519_other_public.pem",
+        &["old=EdDSA:tests/fixtures/ed25519_public.pem"],
+    ))
+    .unwrap();
+    assert!(rotated.decode(&token).is_ok());
+    assert_eq!(serde_json::to_value(rotated.jwks()).unwrap()["keys"][1]["kid"], "old");
+    
+    // Once the old key is dropped, its tokens are rejected
+    let dropped = JwtKeys::from_config(&key_config(
+        "EdDSA",
+        "new",
+        "ed25519_other_private.pem",
+        "ed25519_other_public.pem",
+        &[],
+    ))
+    .unwrap();
+    assert!(dropped.decode(&token).is_err());
+}
+
+#[test]
+fn test_rejects_mismatched_key_pair() {
+    let mismatched = JwtKeys::from_config(&key_config(
+        "EdDSA",
+        "ed-1",
+        "ed25519_private.pem",
+        "ed25519_other_public.pem",
+        &[],
+    ));
+    assert!(mismatched.is_err());
+    
+    // An RSA public key can't stand in for an Ed25519 one
+    let wrong_type = JwtKeys::from_config(&key_config(
+        "EdDSA",
+        "ed-1",
+        "ed25519_private.pem",
+        "rsa_public.pem",
+        &[],
+    ));
+    assert!(wrong_type.is_err());
+}
@@ -0,0 +1,33 @@
+// tests/keystore_test.rs
+use solana_sdk::pubkey::Pubkey;
+use todo_api::errors::ApiError;
+use todo_api::signer::{keystore::KeystoreSigner, TransactionSigner};
+
+const WALLET: &str = "8z5jfiFVgyBCdMCYNgBivVNMmJgwQpEMjHvd4dVCsRJv";
+
+#[actix_web::test]
+async fn test_keystore_round_trip() {
+    // A directory of its own, so runs don't share keys
+    let dir = std::env::temp_dir().join(format!("todo_api_test_keystore_{}", Pubkey::new_unique()));
+    let signer = KeystoreSigner::new(dir.clone(), "correct horse".to_string()).unwrap();
+    let pubkey = signer.create(WALLET).await.unwrap();
+    
+    // A fresh signer over the same directory decrypts the key and signs with it
+    let reloaded = KeystoreSigner::new(dir.clone(), "correct horse".to_string()).unwrap();
+    assert_eq!(reloaded.pubkey(WALLET).await.unwrap(), pubkey);
+    let signature = reloaded.sign_message(WALLET, b"message").await.unwrap();
+    assert!(signature.verify(pubkey.as_ref(), b"message"));
+    
+    // The wrong passphrase can't decrypt it
+    let wrong = KeystoreSigner::new(dir.clone(), "battery staple".to_string()).unwrap();
+    assert!(matches!(
+        wrong.sign_message(WALLET, b"message").await,
+        Err(ApiError::InternalServerError(_))
+    ));
+    
+    reloaded.delete(WALLET).await.unwrap();
+    assert!(matches!(signer.pubkey(WALLET).await, Err(ApiError::NotFound(_))));
+    assert!(matches!(signer.delete(WALLET).await, Err(ApiError::NotFound(_))));
+    
+    let _ = std::fs::remove_dir_all(dir);
+}
@@ -0,0 +1,54 @@
+// tests/nonce_test.rs
+use todo_api::auth::nonce::NonceStore;
+use todo_api::errors::ApiError;
+
+const WALLET: &str = "8z5jfiFVgyBCdMCYNgBivVNMmJgwQpEMjHvd4dVCsRJv";
+const OTHER_WALLET: &str = "9xQeWvG816bUx9EPjHmaT23yvVM2ZWbrrpZb9PusVFin";
+
+fn assert_unauthorized(result: Result<(), ApiError>) {
+    assert!(matches!(result, Err(ApiError::Unauthorized(_))), "expected Unauthorized, got {:?}", result);
+}
+
+#[test]
+fn test_nonce_is_single_use() {
+    let store = NonceStore::new(300);
+    let issued = store.issue(WALLET).unwrap();
+    
+    store.consume(WALLET, &issued.nonce).unwrap();
+    assert_unauthorized(store.consume(WALLET, &issued.nonce));
+}
+
+#[test]
+fn test_expired_nonce_is_rejected_and_removed() {
+    let store = NonceStore::new(0);
+    let issued = store.issue(WALLET).unwrap();
+    
+    match store.consume(WALLET, &issued.nonce) {
+        Err(ApiError::Unauthorized(message)) => assert!(message.contains("expired")),
+        other => panic!("expected an expired nonce, got {:?}", other),
+    }
+    
+    // Gone, not just expired
+    match store.consume(WALLET, &issued.nonce) {
+        Err(ApiError::Unauthorized(message)) => assert!(message.contains("Unknown")),
+        other => panic!("expected an unknown nonce, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_nonce_is_bound_to_its_wallet() {
+    let store = NonceStore::new(300);
+    let issued = store.issue(WALLET).unwrap();
+    
+    assert_unauthorized(store.consume(OTHER_WALLET, &issued.nonce));
+    
+    // Another wallet's attempt doesn't burn it
+    store.consume(WALLET, &issued.nonce).unwrap();
+}
+
+#[test]
+fn test_unknown_nonce_is_rejected() {
+    let store = NonceStore::new(300);
+    
+    assert_unauthorized(store.consume(WALLET, "not-a-nonce"));
+}
@@ -0,0 +1,39 @@
+// tests/priority_fee_test.rs
+use actix_web::http::header::{HeaderMap, HeaderName, HeaderValue};
+use todo_api::solana::priority_fee::{fee_percentile, PriorityOverride, PRIORITY_FEE_HEADER};
+
+fn headers(value: &str) -> HeaderMap {
+    let mut headers = HeaderMap::new();
+    headers.insert(
+        HeaderName::from_static("x-priority-fee"),
+        HeaderValue::from_str(value).unwrap(),
+    );
+    headers
+}
+
+#[test]
+fn test_fee_percentile_uses_nearest_rank() {
+    let mut fees = vec![500, 0, 100, 300, 200];
+    
+    assert_eq!(fee_percentile(&mut fees, 50), 200);
+    assert_eq!(fee_percentile(&mut fees, 90), 500);
+    assert_eq!(fee_percentile(&mut fees, 0), 0);
+    
+    // No recent fees means no competition to outbid
+    assert_eq!(fee_percentile(&mut [], 75), 0);
+}
+
+#[test]
+fn test_priority_override_header() {
+    assert_eq!(PRIORITY_FEE_HEADER.to_ascii_lowercase(), "x-priority-fee");
+    assert_eq!(PriorityOverride::from_headers(&HeaderMap::new()).unwrap(), None);
+    assert_eq!(
+        PriorityOverride::from_headers(&headers("urgent")).unwrap(),
+        Some(PriorityOverride::Urgent)
+    );
+    assert_eq!(
+        PriorityOverride::from_headers(&headers("25000")).unwrap(),
+        Some(PriorityOverride::MicroLamports(25000))
+    );
+    assert!(PriorityOverride::from_headers(&headers("asap")).is_err());
+}
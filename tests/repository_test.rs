@@ -0,0 +1,163 @@
+// tests/repository_test.rs
+use serde_json::{json, Map, Value};
+use solana_sdk::pubkey::Pubkey;
+use todo_api::models::job::{JobResponse, JobStatus};
+use todo_api::repository::sqlite::SqliteRepository;
+use solana_sdk::signature::Keypair;
+use std::sync::Arc;
+use todo_api::auth::api_keys::ApiKeyStore;
+use todo_api::repository::{SponsorshipUsage, StoredTodo, TodoRepository};
+use todo_api::solana::relayer::Relayer;
+use todo_api::solana::{TodoAccount, ORIGINAL_LAYOUT};
+
+fn stored(address: Pubkey, owner: Pubkey, task_id: u64, slot: u64, description: &str) -> StoredTodo {
+    StoredTodo {
+        address,
+        slot,
+        todo: TodoAccount {
+            owner,
+            task_id,
+            description: description.to_string(),
+            completed: false,
+            due_date: 1_700_000_000,
+            layout_version: ORIGINAL_LAYOUT,
+        },
+    }
+}
+
+fn job(id: &str, status: JobStatus) -> JobResponse {
+    JobResponse {
+        id: id.to_string(),
+        status,
+        operation: "delete".to_string(),
+        task_id: 7,
+        created_at: 1_700_000_000,
+        updated_at: 1_700_000_000,
+        todo: None,
+        transaction: None,
+        error: None,
+    }
+}
+
+#[test]
+fn test_todos_keep_the_latest_slot() {
+    let repository = SqliteRepository::open(":memory:").unwrap();
+    let owner = Pubkey::new_unique();
+    let (first, second) = (Pubkey::new_unique(), Pubkey::new_unique());
+    
+    let todos = |repository: &SqliteRepository| {
+        let mut todos = repository.todos().unwrap();
+        todos.sort_by_key(|stored| stored.todo.task_id);
+        todos
+    };
+    
+    repository
+        .replace_todos(&[stored(second, owner, 2, 100, "Second"), stored(first, owner, 1, 100, "First")])
+        .unwrap();
+    
+    // A notification older than the scan doesn't overwrite it
+    repository.upsert_todo(&stored(first, owner, 1, 90, "Stale")).unwrap();
+    repository.upsert_todo(&stored(second, owner, 2, 110, "Edited")).unwrap();
+    
+    let stored_todos = todos(&repository);
+    let descriptions: Vec<&str> = stored_todos.iter().map(|stored| stored.todo.description.as_str()).collect();
+    assert_eq!(descriptions, ["First", "Edited"]);
+    assert_eq!(stored_todos[1].slot, 110);
+    
+    repository.remove_todo(&first).unwrap();
+    assert_eq!(todos(&repository).len(), 1);
+    
+    // A later scan writes only what it found changed
+    let third = Pubkey::new_unique();
+    repository.sync_todos(&[stored(third, owner, 3, 120, "Third")], &[second]).unwrap();
+    let stored_todos = todos(&repository);
+    assert_eq!(stored_todos.len(), 1);
+    assert_eq!(stored_todos[0].address, third);
+}
+
+#[test]
+fn test_metadata_round_trips() {
+    let repository = SqliteRepository::open(":memory:").unwrap();
+    let owner = Pubkey::new_unique();
+    let metadata: Map<String, Value> = json!({ "tags": ["home"], "color": "blue" }).as_object().unwrap().clone();
+    
+    assert_eq!(repository.metadata(&owner, 1).unwrap(), None);
+    repository.set_metadata(&owner, 1, &metadata).unwrap();
+    assert_eq!(repository.metadata(&owner, 1).unwrap(), Some(metadata));
+    
+    repository.delete_metadata(&owner, 1).unwrap();
+    assert_eq!(repository.metadata(&owner, 1).unwrap(), None);
+}
+
+#[test]
+fn test_unfinished_jobs_fail_after_restart() {
+    let path = std::env::temp_dir().join(format!("todo_api_test_{}.db", Pubkey::new_unique()));
+    let path = path.to_str().unwrap();
+    
+    {
+        let repository = SqliteRepository::open(path).unwrap();
+        repository.save_job("wallet-a", &job("queued", JobStatus::Queued)).unwrap();
+        repository.save_job("wallet-a", &job("done", JobStatus::Confirmed)).unwrap();
+    }
+    
+    // Reopening finds the schema already migrated and the jobs still there
+    let repository = SqliteRepository::open(path).unwrap();
+    assert_eq!(repository.fail_unfinished_jobs("Interrupted").unwrap(), 1);
+    
+    let interrupted = repository.job("wallet-a", "queued").unwrap().unwrap();
+    assert_eq!(interrupted.status, JobStatus::Failed);
+    assert_eq!(interrupted.error.as_deref(), Some("Interrupted"));
+    assert_eq!(repository.job("wallet-a", "done").unwrap().unwrap().status, JobStatus::Confirmed);
+    
+    // Only the wallet that queued a job can load it
+    assert!(repository.job("wallet-b", "done").unwrap().is_none());
+    assert_eq!(repository.prune_jobs(i64::MAX).unwrap(), 2);
+    
+    drop(repository);
+    for suffix in ["", "-wal", "-shm"] {
+        let _ = std::fs::remove_file(format!("{}{}", path, suffix));
+    }
+}
+
+#[test]
+fn test_sponsorship_usage_survives_restart() {
+    let repository: Arc<dyn TodoRepository> = Arc::new(SqliteRepository::open(":memory:").unwrap());
+    let today = chrono::Utc::now().timestamp() as u64 / 86_400;
+    let usage = |wallet: &str, day: u64| SponsorshipUsage {
+        wallet: wallet.to_string(),
+        day,
+        transactions: 2,
+        lamports: 5_000,
+    };
+    
+    repository.save_sponsorship_usage(&usage("wallet-a", today)).unwrap();
+    repository.save_sponsorship_usage(&usage("wallet-b", today - 1)).unwrap();
+    assert_eq!(repository.sponsorship_usage(today).unwrap(), vec![usage("wallet-a", today)]);
+    
+    // A relayer started against the ledger carries on with today's usage
+    let relayer = Relayer::new(Keypair::new(), 10, 1_000_000).with_repository(repository).unwrap();
+    let remaining = relayer.remaining("wallet-a").unwrap();
+    assert_eq!(remaining.remaining_transactions, 8);
+    assert_eq!(remaining.remaining_lamports, 995_000);
+    assert_eq!(relayer.remaining("wallet-b").unwrap().remaining_transactions, 10);
+}
+
+#[actix_web::test]
+async fn test_api_keys_survive_restart() {
+    let repository: Arc<dyn TodoRepository> = Arc::new(SqliteRepository::open(":memory:").unwrap());
+    let scopes = vec!["todos:read".to_string()];
+    
+    let store = ApiKeyStore::new().with_repository(repository.clone()).unwrap();
+    let (key, created) = store.create("wallet-a", "ci", &scopes).await.unwrap();
+    let (_, revoked) = store.create("wallet-a", "old", &scopes).await.unwrap();
+    store.revoke("wallet-a", &revoked.id).await.unwrap();
+    
+    // Only the hash of the secret is stored
+    let saved = repository.api_keys().unwrap();
+    assert_eq!(saved.len(), 1);
+    assert!(!key.ends_with(&saved[0].secret_hash));
+    
+    let restarted = ApiKeyStore::new().with_repository(repository).unwrap();
+    assert_eq!(restarted.authenticate(&key).unwrap().scopes, scopes);
+    assert_eq!(restarted.list("wallet-a").unwrap()[0].id, created.id);
+}
@@ -0,0 +1,34 @@
+// tests/retry_test.rs
+use solana_client::client_error::ClientError;
+use solana_sdk::instruction::InstructionError;
+use solana_sdk::transaction::TransactionError;
+use std::time::Duration;
+use todo_api::solana::retry::{RetryPolicy, SendFailure};
+
+#[test]
+fn test_classifies_expired_blockhash_and_program_errors() {
+    let expired = ClientError::from(TransactionError::BlockhashNotFound);
+    assert_eq!(SendFailure::classify(&expired), SendFailure::BlockhashExpired);
+    
+    // A program error would fail the same way again, so it is never retried
+    let program_error = ClientError::from(TransactionError::InstructionError(
+        0,
+        InstructionError::Custom(6000),
+    ));
+    assert_eq!(SendFailure::classify(&program_error), SendFailure::Permanent);
+    
+    let timeout = ClientError::from(std::io::Error::new(std::io::ErrorKind::TimedOut, "timed out"));
+    assert_eq!(SendFailure::classify(&timeout), SendFailure::Transient);
+}
+
+#[test]
+fn test_backoff_grows_and_is_capped() {
+    let policy = RetryPolicy::new(5, Duration::from_millis(100), Duration::from_millis(300));
+    
+    // Jitter keeps each delay between half and all of the exponential step
+    let first = policy.backoff(1);
+    assert!(first >= Duration::from_millis(50) && first <= Duration::from_millis(100));
+    
+    let capped = policy.backoff(10);
+    assert!(capped >= Duration::from_millis(150) && capped <= Duration::from_millis(300));
+}
@@ -0,0 +1,145 @@
+// tests/rpc_pool_test.rs
+mod common;
+
+use common::MockRpc;
+use serde_json::json;
+use solana_client::client_error::{ClientError, ClientErrorKind};
+use solana_client::rpc_custom_error::JSON_RPC_SERVER_ERROR_NODE_UNHEALTHY;
+use solana_client::rpc_request::{RpcError, RpcResponseErrorData};
+use solana_sdk::transaction::TransactionError;
+use todo_api::config;
+use todo_api::models::rpc::RpcEndpointMetrics;
+use todo_api::solana::rpc_pool::{is_endpoint_error, RpcPool};
+
+// Nothing listens on port 1
+const UNREACHABLE: &str = "http://127.0.0.1:1";
+
+// An endpoint at `slot`, healthy unless told otherwise
+fn endpoint(slot: u64, healthy: bool) -> MockRpc {
+    MockRpc::start(move |method, _| match method {
+        "getHealth" if healthy => Some(json!("ok")),
+        "getSlot" => Some(json!(slot)),
+        _ => None,
+    })
+}
+
+// A pool over `url|weight` entries that never hedges, so each read goes to one endpoint
+fn pool(entries: &[String]) -> RpcPool {
+    let mut config = config::load_config();
+    config.solana_rpc_urls = entries.to_vec();
+    config.rpc_hedge_delay_ms = 60_000;
+    config.rpc_max_slot_lag = 150;
+    RpcPool::from_config(&config).unwrap()
+}
+
+fn metrics(pool: &RpcPool, url: &str) -> RpcEndpointMetrics {
+    pool.metrics().into_iter().find(|metrics| metrics.url == url).unwrap()
+}
+
+async fn read_slot(pool: &RpcPool) -> Result<u64, ClientError> {
+    pool.read(|rpc| async move { rpc.get_slot().await }).await
+}
+
+#[actix_web::test]
+async fn test_reads_follow_the_weights() {
+    let heavy = endpoint(1000, true);
+    let light = endpoint(1000, true);
+    let unused = endpoint(1000, true);
+    let pool = pool(&[
+        format!("{}|3", heavy.url),
+        format!("{}|1", light.url),
+        format!("{}|0", unused.url),
+    ]);
+    
+    for _ in 0..400 {
+        read_slot(&pool).await.unwrap();
+    }
+    
+    // Three to one, give or take; a zero weight is never picked
+    let heavy_requests = metrics(&pool, &heavy.url).requests;
+    assert!((240..=360).contains(&heavy_requests), "heavy endpoint served {} of 400", heavy_requests);
+    assert_eq!(metrics(&pool, &light.url).requests, 400 - heavy_requests);
+    assert_eq!(metrics(&pool, &unused.url).requests, 0);
+}
+
+#[actix_web::test]
+async fn test_writes_fail_over_and_demote_the_failed_endpoint() {
+    // Weighted so the unreachable endpoint is all but certain to be tried first
+    let backup = endpoint(1000, true);
+    let pool = pool(&[format!("{}|1000000", UNREACHABLE), format!("{}|1", backup.url)]);
+    
+    let slot = pool.write(|rpc| async move { rpc.get_slot().await }).await.unwrap();
+    assert_eq!(slot, 1000);
+    
+    let failed = metrics(&pool, UNREACHABLE);
+    assert_eq!((failed.requests, failed.failures), (1, 1));
+    assert!(!failed.healthy);
+    
+    // Now unhealthy, it's only a last resort
+    pool.write(|rpc| async move { rpc.get_slot().await }).await.unwrap();
+    assert_eq!(metrics(&pool, UNREACHABLE).requests, 1);
+    assert_eq!(metrics(&pool, &backup.url).requests, 2);
+}
+
+#[actix_web::test]
+async fn test_request_errors_do_not_fail_over() {
+    // Answers with a JSON-RPC error, which is about the request rather than the endpoint
+    let rejecting = MockRpc::start(|_, _| None);
+    let backup = endpoint(1000, true);
+    let pool = pool(&[format!("{}|1000000", rejecting.url), format!("{}|1", backup.url)]);
+    
+    assert!(pool.write(|rpc| async move { rpc.get_slot().await }).await.is_err());
+    assert!(metrics(&pool, &rejecting.url).healthy);
+    assert_eq!(metrics(&pool, &backup.url).requests, 0);
+}
+
+#[actix_web::test]
+async fn test_unhealthy_endpoints_are_skipped() {
+    let healthy = endpoint(1000, true);
+    let failing_probe = endpoint(1000, false);
+    let lagging = endpoint(10, true);
+    let pool = pool(&[
+        format!("{}|1", healthy.url),
+        format!("{}|1000", failing_probe.url),
+        format!("{}|1000", lagging.url),
+    ]);
+    
+    pool.check_health().await;
+    assert!(metrics(&pool, &healthy.url).healthy);
+    assert!(!metrics(&pool, &failing_probe.url).healthy);
+    assert!(!metrics(&pool, &lagging.url).healthy);
+    
+    // Heavier weights don't matter once an endpoint is unhealthy
+    for _ in 0..20 {
+        read_slot(&pool).await.unwrap();
+    }
+    assert_eq!(metrics(&pool, &healthy.url).requests, 20);
+    assert_eq!(metrics(&pool, &failing_probe.url).requests, 0);
+    assert_eq!(metrics(&pool, &lagging.url).requests, 0);
+}
+
+#[test]
+fn test_endpoint_errors_are_told_apart_from_request_errors() {
+    let error = |kind: ClientErrorKind| ClientError::from(kind);
+    let response_error = |code| {
+        error(ClientErrorKind::RpcError(RpcError::RpcResponseError {
+            code,
+            message: "error".to_string(),
+            data: RpcResponseErrorData::Empty,
+        }))
+    };
+    
+    // The endpoint's fault: another endpoint may do better
+    assert!(is_endpoint_error(&error(ClientErrorKind::Io(std::io::ErrorKind::ConnectionRefused.into()))));
+    assert!(is_endpoint_error(&error(ClientErrorKind::RpcError(RpcError::RpcRequestError(
+        "rate limited".to_string()
+    )))));
+    assert!(is_endpoint_error(&response_error(JSON_RPC_SERVER_ERROR_NODE_UNHEALTHY)));
+    
+    // The request's fault: every endpoint would say the same
+    assert!(!is_endpoint_error(&response_error(-32602)));
+    assert!(!is_endpoint_error(&error(ClientErrorKind::TransactionError(
+        TransactionError::BlockhashNotFound
+    ))));
+    assert!(!is_endpoint_error(&error(ClientErrorKind::Custom("bad request".to_string()))));
+}
@@ -0,0 +1,34 @@
+// tests/session_test.rs
+use todo_api::auth::session::SessionStore;
+
+const WALLET: &str = "8z5jfiFVgyBCdMCYNgBivVNMmJgwQpEMjHvd4dVCsRJv";
+
+fn scopes() -> Vec<String> {
+    vec!["todos:read".to_string()]
+}
+
+#[test]
+fn test_rotation_keeps_session_wallet_and_scopes() {
+    let store = SessionStore::new(3600);
+    let first = store.start_session(WALLET, &scopes()).unwrap();
+    
+    let (grant, second) = store.rotate(&first.token).unwrap();
+    
+    assert_eq!(grant.wallet, WALLET);
+    assert_eq!(grant.scopes, scopes());
+    assert_eq!(second.session_id, first.session_id);
+    assert_ne!(second.token, first.token);
+}
+
+#[test]
+fn test_reuse_revokes_whole_family() {
+    let store = SessionStore::new(3600);
+    let first = store.start_session(WALLET, &scopes()).unwrap();
+    let (_, second) = store.rotate(&first.token).unwrap();
+    
+    // Replaying the rotated token is detected...
+    assert!(store.rotate(&first.token).is_err());
+    
+    // ...and the legitimate successor is revoked along with it
+    assert!(store.rotate(&second.token).is_err());
+}
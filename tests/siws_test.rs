@@ -0,0 +1,76 @@
+// tests/siws_test.rs
+use chrono::{Duration, TimeZone, Utc};
+use todo_api::auth::siws::{SiwsExpectations, SiwsMessage};
+
+const ADDRESS: &str = "8z5jfiFVgyBCdMCYNgBivVNMmJgwQpEMjHvd4dVCsRJv";
+
+fn expectations() -> SiwsExpectations<'static> {
+    SiwsExpectations {
+        domain: "localhost:3000",
+        uri: "http://localhost:3000",
+        chain_id: "devnet",
+    }
+}
+
+fn sample_message() -> String {
+    format!(
+        "localhost:3000 wants you to sign in with your Solana account:\n\
+         {}\n\
+         \n\
+         Sign in to the Solana Todo API\n\
+         \n\
+         URI: http://localhost:3000\n\
+         Version: 1\n\
+         Chain ID: devnet\n\
+         Nonce: 5Hx3kT9m\n\
+         Issued At: 2026-01-01T00:00:00.000Z\n\
+         Expiration Time: 2026-01-01T00:05:00.000Z",
+        ADDRESS
+    )
+}
+
+#[test]
+fn test_parse_and_render_round_trip() {
+    let message = SiwsMessage::parse(&sample_message()).unwrap();
+    
+    assert_eq!(message.domain, "localhost:3000");
+    assert_eq!(message.address, ADDRESS);
+    assert_eq!(message.statement.as_deref(), Some("Sign in to the Solana Todo API"));
+    assert_eq!(message.nonce.as_deref(), Some("5Hx3kT9m"));
+    assert_eq!(message.to_string(), sample_message());
+}
+
+#[test]
+fn test_verify_accepts_matching_message() {
+    let message = SiwsMessage::parse(&sample_message()).unwrap();
+    let now = Utc.with_ymd_and_hms(2026, 1, 1, 0, 1, 0).unwrap();
+    
+    assert!(message.verify(&expectations(), ADDRESS, now).is_ok());
+}
+
+#[test]
+fn test_verify_rejects_wrong_domain_address_and_expiry() {
+    let message = SiwsMessage::parse(&sample_message()).unwrap();
+    let now = Utc.with_ymd_and_hms(2026, 1, 1, 0, 1, 0).unwrap();
+    
+    let other_domain = SiwsExpectations {
+        domain: "evil.example",
+        ..expectations()
+    };
+    assert!(message.verify(&other_domain, ADDRESS, now).is_err());
+    
+    // Address must equal the public key that signed the message
+    assert!(message
+        .verify(&expectations(), "11111111111111111111111111111111", now)
+        .is_err());
+    
+    // Expired messages are rejected
+    assert!(message
+        .verify(&expectations(), ADDRESS, now + Duration::minutes(10))
+        .is_err());
+}
+
+#[test]
+fn test_parse_rejects_missing_header() {
+    assert!(SiwsMessage::parse("hello\nworld").is_err());
+}
@@ -0,0 +1,209 @@
+// tests/submit_transaction_test.rs
+mod common;
+
+use common::{cluster, MockRpc, FEE, RENT};
+use solana_sdk::compute_budget::ComputeBudgetInstruction;
+use solana_sdk::hash::Hash;
+use solana_sdk::instruction::{AccountMeta, Instruction};
+use solana_sdk::message::Message;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::{Keypair, Signer};
+use solana_sdk::system_instruction;
+use solana_sdk::transaction::Transaction;
+use std::sync::Arc;
+use todo_api::{
+    config,
+    errors::ApiError,
+    models::todo::CreateTodoRequest,
+    solana::{idl::Idl, relayer::Relayer, rpc_pool::RpcPool, SolanaService},
+};
+
+struct Relayed {
+    service: SolanaService,
+    relayer: Arc<Relayer>,
+    max_price: u64,
+    rpc: MockRpc,
+}
+
+fn relayed() -> Relayed {
+    let rpc = MockRpc::start(cluster);
+    let mut config = config::load_config();
+    config.solana_rpc_urls = vec![rpc.url.clone()];
+    
+    let rpc_pool = Arc::new(RpcPool::from_config(&config).unwrap());
+    let idl = Arc::new(Idl::from_file("idl/todo_program.json").unwrap());
+    let relayer = Arc::new(Relayer::new(Keypair::new(), 10, 1_000_000_000));
+    let service = SolanaService::new(&config, rpc_pool, idl, Some(relayer.clone())).unwrap();
+    
+    Relayed {
+        service,
+        relayer,
+        max_price: config.priority_fee_max_micro_lamports,
+        rpc,
+    }
+}
+
+fn create_request(task_id: u64) -> CreateTodoRequest {
+    CreateTodoRequest {
+        task_id,
+        description: "Relayed todo".to_string(),
+        due_date: 0,
+    }
+}
+
+// The relayer's rent transfer followed by the create it funds
+async fn create_instructions(relayed: &Relayed, owner: &Pubkey, task_id: u64) -> Vec<Instruction> {
+    relayed
+        .service
+        .create_todo_instructions(owner, &create_request(task_id))
+        .await
+        .unwrap()
+}
+
+fn signed(instructions: &[Instruction], payer: &Pubkey, wallet: &Keypair) -> Transaction {
+    let blockhash = Hash::new_unique();
+    let message = Message::new_with_blockhash(instructions, Some(payer), &blockhash);
+    let mut transaction = Transaction::new_unsigned(message);
+    transaction.partial_sign(&[wallet], blockhash);
+    transaction
+}
+
+async fn submit(relayed: &Relayed, wallet: &Keypair, transaction: Transaction) -> Result<(), ApiError> {
+    relayed
+        .service
+        .submit_signed_transaction(&wallet.pubkey().to_string(), transaction)
+        .await
+        .map(|_| ())
+}
+
+fn assert_forbidden(result: Result<(), ApiError>) {
+    assert!(matches!(result, Err(ApiError::Forbidden(_))), "expected Forbidden, got {:?}", result);
+}
+
+fn assert_bad_request(result: Result<(), ApiError>) {
+    assert!(matches!(result, Err(ApiError::BadRequest(_))), "expected BadRequest, got {:?}", result);
+}
+
+#[actix_web::test]
+async fn test_relays_a_wallet_signed_create() {
+    let relayed = relayed();
+    let wallet = Keypair::new();
+    let instructions = create_instructions(&relayed, &wallet.pubkey(), 1).await;
+    let transaction = signed(&instructions, &relayed.relayer.pubkey(), &wallet);
+    
+    let receipt = relayed
+        .service
+        .submit_signed_transaction(&wallet.pubkey().to_string(), transaction)
+        .await
+        .unwrap();
+    
+    assert!(relayed.rpc.called("sendTransaction"));
+    assert_eq!(receipt.commitment.as_deref(), Some("finalized"));
+    
+    // Charged the fee and the forwarded rent
+    let remaining = relayed.relayer.remaining(&wallet.pubkey().to_string()).unwrap();
+    assert_eq!(remaining.remaining_transactions, 9);
+    assert_eq!(remaining.remaining_lamports, 1_000_000_000 - FEE - RENT);
+}
+
+#[actix_web::test]
+async fn test_rejects_a_foreign_program() {
+    let relayed = relayed();
+    let wallet = Keypair::new();
+    let instruction = Instruction::new_with_bytes(
+        Pubkey::new_unique(),
+        &[],
+        vec![AccountMeta::new(Pubkey::new_unique(), false), AccountMeta::new(wallet.pubkey(), true)],
+    );
+    let transaction = signed(&[instruction], &relayed.relayer.pubkey(), &wallet);
+    
+    assert_forbidden(submit(&relayed, &wallet, transaction).await);
+    assert!(!relayed.rpc.called("sendTransaction"));
+}
+
+#[actix_web::test]
+async fn test_rejects_another_wallet_as_owner() {
+    let relayed = relayed();
+    let wallet = Keypair::new();
+    let other = Keypair::new();
+    
+    // The other wallet's create, signed by it and submitted by this wallet
+    let create = create_instructions(&relayed, &other.pubkey(), 1).await.pop().unwrap();
+    let transaction = signed(&[create], &relayed.relayer.pubkey(), &other);
+    
+    assert_forbidden(submit(&relayed, &wallet, transaction).await);
+}
+
+#[actix_web::test]
+async fn test_rejects_a_transfer_of_the_wrong_amount() {
+    let relayed = relayed();
+    let wallet = Keypair::new();
+    let mut instructions = create_instructions(&relayed, &wallet.pubkey(), 1).await;
+    instructions[0] = system_instruction::transfer(&relayed.relayer.pubkey(), &wallet.pubkey(), RENT + 1);
+    let transaction = signed(&instructions, &relayed.relayer.pubkey(), &wallet);
+    
+    assert_forbidden(submit(&relayed, &wallet, transaction).await);
+}
+
+#[actix_web::test]
+async fn test_rejects_a_transfer_to_another_recipient() {
+    let relayed = relayed();
+    let wallet = Keypair::new();
+    let mut instructions = create_instructions(&relayed, &wallet.pubkey(), 1).await;
+    instructions[0] = system_instruction::transfer(&relayed.relayer.pubkey(), &Pubkey::new_unique(), RENT);
+    let transaction = signed(&instructions, &relayed.relayer.pubkey(), &wallet);
+    
+    assert_forbidden(submit(&relayed, &wallet, transaction).await);
+}
+
+#[actix_web::test]
+async fn test_rejects_a_second_transfer() {
+    let relayed = relayed();
+    let wallet = Keypair::new();
+    
+    // Two creates, each funded correctly, but only one transfer is sponsored per transaction
+    let mut instructions = create_instructions(&relayed, &wallet.pubkey(), 1).await;
+    instructions.extend(create_instructions(&relayed, &wallet.pubkey(), 2).await);
+    let transaction = signed(&instructions, &relayed.relayer.pubkey(), &wallet);
+    
+    assert_forbidden(submit(&relayed, &wallet, transaction).await);
+}
+
+#[actix_web::test]
+async fn test_rejects_a_compute_unit_price_over_the_cap() {
+    let relayed = relayed();
+    let wallet = Keypair::new();
+    let mut instructions = vec![ComputeBudgetInstruction::set_compute_unit_price(relayed.max_price + 1)];
+    instructions.extend(create_instructions(&relayed, &wallet.pubkey(), 1).await);
+    let transaction = signed(&instructions, &relayed.relayer.pubkey(), &wallet);
+    
+    assert_bad_request(submit(&relayed, &wallet, transaction).await);
+}
+
+#[actix_web::test]
+async fn test_rejects_a_fee_payer_other_than_the_relayer() {
+    let relayed = relayed();
+    let wallet = Keypair::new();
+    let instructions = create_instructions(&relayed, &wallet.pubkey(), 1).await;
+    let transaction = signed(&instructions[1..], &wallet.pubkey(), &wallet);
+    
+    assert_forbidden(submit(&relayed, &wallet, transaction).await);
+}
+
+#[actix_web::test]
+async fn test_rejects_a_missing_or_invalid_wallet_signature() {
+    let relayed = relayed();
+    let wallet = Keypair::new();
+    let instructions = create_instructions(&relayed, &wallet.pubkey(), 1).await;
+    
+    let message = Message::new_with_blockhash(&instructions, Some(&relayed.relayer.pubkey()), &Hash::new_unique());
+    let unsigned = Transaction::new_unsigned(message);
+    assert_bad_request(submit(&relayed, &wallet, unsigned).await);
+    
+    // Signed, then pointed at another blockhash
+    let mut tampered = signed(&instructions, &relayed.relayer.pubkey(), &wallet);
+    tampered.message.recent_blockhash = Hash::new_unique();
+    assert_bad_request(submit(&relayed, &wallet, tampered).await);
+    
+    assert!(!relayed.rpc.called("sendTransaction"));
+}
@@ -0,0 +1,226 @@
+// tests/todo_account_test.rs
+mod common;
+
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use common::{cluster, MockRpc};
+use serde_json::{json, Value};
+use solana_sdk::pubkey::Pubkey;
+use std::sync::Arc;
+use todo_api::config;
+use todo_api::solana::idl::Idl;
+use todo_api::solana::rpc_pool::RpcPool;
+use todo_api::solana::{
+    current_layout, decode_todo, SolanaService, TodoAccount, EXTENDED_LAYOUT, ORIGINAL_LAYOUT, TODO_ACCOUNT,
+    TODO_ACCOUNT_SPACE,
+};
+
+fn idl() -> Idl {
+    Idl::from_file("idl/todo_program.json").unwrap()
+}
+
+// The bundled IDL with a field appended to the todo account
+fn extended_idl_json(field_type: Value) -> Value {
+    let mut raw: Value = serde_json::from_str(&std::fs::read_to_string("idl/todo_program.json").unwrap()).unwrap();
+    raw["accounts"][0]["type"]["fields"]
+        .as_array_mut()
+        .unwrap()
+        .push(json!({ "name": "priority", "type": field_type }));
+    raw
+}
+
+fn extended_idl(field_type: Value) -> Idl {
+    Idl::from_json(&extended_idl_json(field_type).to_string()).unwrap()
+}
+
+// The extended IDL along with the instruction that migrates accounts to it
+fn migratable_idl() -> Idl {
+    let mut raw = extended_idl_json(json!("u8"));
+    raw["instructions"].as_array_mut().unwrap().push(json!({
+        "name": "migrateTodo",
+        "accounts": [
+            { "name": "todo", "isMut": true, "isSigner": false },
+            { "name": "owner", "isMut": true, "isSigner": true },
+            { "name": "systemProgram", "isMut": false, "isSigner": false },
+        ],
+        "args": [],
+    }));
+    Idl::from_json(&raw.to_string()).unwrap()
+}
+
+// A todo in either layout; `priority` is the field the extended layout appends
+fn encode(idl: &Idl, todo: &TodoAccount, priority: Option<u8>) -> Vec<u8> {
+    let mut data = idl.account_discriminator(TODO_ACCOUNT).unwrap().to_vec();
+    data.extend(todo.task_id.to_le_bytes());
+    data.extend((todo.description.len() as u32).to_le_bytes());
+    data.extend(todo.description.as_bytes());
+    data.push(todo.completed as u8);
+    data.extend(todo.due_date.to_le_bytes());
+    data.extend(todo.owner.to_bytes());
+    match priority {
+        Some(priority) => {
+            data.push(priority);
+            data.resize(TODO_ACCOUNT_SPACE + 1, 0);
+        }
+        None => data.resize(TODO_ACCOUNT_SPACE, 0),
+    }
+    data
+}
+
+// Borsh layout of the todo account, written out by hand
+fn account_data(idl: &Idl, task_id: u64, description: &str, completed: bool, owner: &Pubkey) -> Vec<u8> {
+    let mut data = idl.account_discriminator(TODO_ACCOUNT).unwrap().to_vec();
+    data.extend(task_id.to_le_bytes());
+    data.extend((description.len() as u32).to_le_bytes());
+    data.extend(description.as_bytes());
+    data.push(completed as u8);
+    data.extend(1_700_000_000i64.to_le_bytes());
+    data.extend(owner.to_bytes());
+    // Accounts are allocated at their largest, so short descriptions leave zeroed space
+    data.resize(TODO_ACCOUNT_SPACE, 0);
+    data
+}
+
+#[test]
+fn test_decodes_account_with_unused_space() {
+    let idl = idl();
+    let owner = Pubkey::new_unique();
+    
+    let data = account_data(&idl, 42, "Short", true, &owner);
+    let decoded = decode_todo(&idl, &Pubkey::new_unique(), &data).unwrap();
+    assert_eq!(decoded.task_id, 42);
+    assert_eq!(decoded.description, "Short");
+    assert!(decoded.completed);
+    assert_eq!(decoded.due_date, 1_700_000_000);
+    assert_eq!(decoded.owner, owner);
+}
+
+#[test]
+fn test_rejects_other_account_types() {
+    let idl = idl();
+    
+    let mut data = account_data(&idl, 1, "", false, &Pubkey::new_unique());
+    data[0] ^= 0xff;
+    assert!(decode_todo(&idl, &Pubkey::new_unique(), &data).is_err());
+    assert!(decode_todo(&idl, &Pubkey::new_unique(), &[0u8; 4]).is_err());
+}
+
+#[test]
+fn test_idl_requirements_are_checked() {
+    let idl = idl();
+    
+    assert!(idl.require_instruction("toggle_completed", &["todo", "owner"], &[]).is_ok());
+    assert!(idl.require_instruction("archive_todo", &["todo", "owner"], &[]).is_err());
+    assert!(idl
+        .require_instruction("update_description", &["todo", "owner"], &[("description", "u64")])
+        .is_err());
+}
+
+#[test]
+fn test_original_accounts_decode_under_extended_layout() {
+    let extended = extended_idl(json!("u8"));
+    assert_eq!(current_layout(&extended), Ok((EXTENDED_LAYOUT, TODO_ACCOUNT_SPACE + 1)));
+    let owner = Pubkey::new_unique();
+    
+    // Read in the extended layout, the unused description space would be taken as the new field
+    let original = account_data(&extended, 7, "Short", false, &owner);
+    let decoded = decode_todo(&extended, &Pubkey::new_unique(), &original).unwrap();
+    assert_eq!(decoded.layout_version, ORIGINAL_LAYOUT);
+    assert_eq!(decoded.owner, owner);
+    
+    let mut migrated = original[..8 + 8 + 4 + 5 + 1 + 8 + 32].to_vec();
+    migrated.push(3);
+    migrated.resize(TODO_ACCOUNT_SPACE + 1, 0);
+    let decoded = decode_todo(&extended, &Pubkey::new_unique(), &migrated).unwrap();
+    assert_eq!(decoded.layout_version, EXTENDED_LAYOUT);
+    assert_eq!(decoded.description, "Short");
+    
+    assert_eq!(current_layout(&idl()), Ok((ORIGINAL_LAYOUT, TODO_ACCOUNT_SPACE)));
+    assert!(current_layout(&extended_idl(json!("string"))).is_err());
+}
+
+#[test]
+fn test_layouts_round_trip() {
+    let idl = extended_idl(json!("u8"));
+    
+    for (layout_version, priority) in [(ORIGINAL_LAYOUT, None), (EXTENDED_LAYOUT, Some(3))] {
+        let todo = TodoAccount {
+            task_id: 9,
+            description: "Round trip".to_string(),
+            completed: true,
+            due_date: 1_700_000_000,
+            owner: Pubkey::new_unique(),
+            layout_version,
+        };
+        let data = encode(&idl, &todo, priority);
+        
+        let decoded = decode_todo(&idl, &Pubkey::new_unique(), &data).unwrap();
+        assert_eq!(decoded, todo);
+        
+        // The appended field is read back too, so the account re-encodes to the same bytes
+        let appended = idl
+            .decode_account(TODO_ACCOUNT, &data)
+            .ok()
+            .filter(|_| layout_version == EXTENDED_LAYOUT)
+            .and_then(|fields| fields.get("priority")?.as_u64())
+            .map(|priority| priority as u8);
+        assert_eq!(appended, priority);
+        assert_eq!(encode(&idl, &decoded, appended), data);
+    }
+}
+
+#[actix_web::test]
+async fn test_migrate_todos_skips_current_accounts() {
+    let mut config = config::load_config();
+    let idl = migratable_idl();
+    let owner = Pubkey::new_unique();
+    let todo = |task_id, layout_version| TodoAccount {
+        task_id,
+        description: "Migrate me".to_string(),
+        completed: false,
+        due_date: 0,
+        owner,
+        layout_version,
+    };
+    let original = (Pubkey::new_unique(), encode(&idl, &todo(1, ORIGINAL_LAYOUT), None));
+    let current = (Pubkey::new_unique(), encode(&idl, &todo(2, EXTENDED_LAYOUT), Some(0)));
+    let original_address = original.0;
+    
+    // The owner has one todo in each layout
+    let program_id = config.program_id.clone();
+    let rpc = MockRpc::start(move |method, params| match method {
+        "getProgramAccounts" => {
+            let accounts: Vec<Value> = [&original, &current]
+                .iter()
+                .map(|(address, data)| json!({
+                    "pubkey": address.to_string(),
+                    "account": {
+                        "lamports": 1_000_000,
+                        "data": [BASE64.encode(data), "base64"],
+                        "owner": program_id,
+                        "executable": false,
+                        "rentEpoch": 0,
+                    },
+                }))
+                .collect();
+            Some(json!(accounts))
+        }
+        _ => cluster(method, params),
+    });
+    config.solana_rpc_urls = vec![rpc.url.clone()];
+    let rpc_pool = Arc::new(RpcPool::from_config(&config).unwrap());
+    let service = SolanaService::new(&config, rpc_pool, Arc::new(idl), None).unwrap();
+    
+    // Without a custodial signer, each migration comes back for the wallet to sign
+    let response = service.migrate_todos(None, &owner.to_string(), &owner, None).await.unwrap();
+    
+    assert_eq!(response.layout_version, EXTENDED_LAYOUT);
+    assert_eq!(response.migrations.len(), 1);
+    let migration = &response.migrations[0];
+    assert_eq!(migration.task_id, 1);
+    assert_eq!(migration.layout_version, ORIGINAL_LAYOUT);
+    assert!(migration.error.is_none(), "migration failed: {:?}", migration.error);
+    assert!(migration.unsigned_transaction.is_some());
+    assert_eq!(migration.todo_account, original_address.to_string());
+    assert!(!rpc.called("sendTransaction"));
+}
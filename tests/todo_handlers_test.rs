@@ -1,17 +1,30 @@
 // tests/todo_handlers_test.rs
-use actix_web::{http::StatusCode, test, web, App};
+mod common;
+
+use actix_web::{http::StatusCode, test, web, App, HttpMessage};
+use common::{cluster, with_context, MockRpc, RENT};
+use serde_json::{json, Value};
 use todo_api::{
-    config,
+    config::{self, Config},
+    errors::ErrorResponse,
     handlers::todo,
-    models::auth::Claims,
-    models::todo::{CreateTodoRequest, UpdateTodoRequest},
+    models::auth::{Claims, Principal},
+    models::todo::CreateTodoRequest,
+    models::transaction::SimulationResponse,
+    signer::{file::FileSigner, TransactionSigner},
+    solana::{idl::Idl, jobs::JobQueue, rpc_pool::RpcPool, SolanaService},
 };
+use solana_sdk::pubkey::Pubkey;
+use std::sync::Arc;
 use std::time::{SystemTime, UNIX_EPOCH};
 
 #[actix_web::test]
 async fn test_create_todo() {
     // Mock configuration
     let config = config::load_config();
+    let rpc_pool = Arc::new(RpcPool::from_config(&config).unwrap());
+    let idl = Arc::new(Idl::from_file("idl/todo_program.json").unwrap());
+    let solana_service = SolanaService::new(&config, rpc_pool, idl, None).unwrap();
     
     // Mock request
     let todo_req = CreateTodoRequest {
@@ -23,16 +36,31 @@ async fn test_create_todo() {
             .as_secs() as i64 + 86400, // Tomorrow
     };
     
+    // Mock custodial signer holding a key for the wallet
+    let wallet = "8z5jfiFVgyBCdMCYNgBivVNMmJgwQpEMjHvd4dVCsRJv"; // Example public key
+    // A directory of its own, so runs don't share keys
+    let key_dir = std::env::temp_dir().join(format!("todo_api_test_keys_{}", Pubkey::new_unique()));
+    let signer: Arc<dyn TransactionSigner> = Arc::new(FileSigner::new(key_dir.clone()).unwrap());
+    let _ = signer.create(wallet).await;
+    
     // Mock claims
     let claims = Claims {
-        sub: "8z5jfiFVgyBCdMCYNgBivVNMmJgwQpEMjHvd4dVCsRJv".to_string(), // Example public key
+        sub: wallet.to_string(),
         exp: 0,
+        iat: 0,
+        jti: "test-token".to_string(),
+        sid: "test-session".to_string(),
+        scopes: vec!["todos:write".to_string()],
+        roles: vec![],
+        principal: Principal::WalletSession,
     };
     
     // Create test app
     let app = test::init_service(
         App::new()
-            .app_data(web::Data::new(config))
+            .app_data(web::Data::new(solana_service))
+            .app_data(web::Data::from(signer))
+            .app_data(web::Data::new(JobQueue::from_config(&config)))
             .app_data(web::JsonConfig::default().limit(4096))
             .route("/api/todos", web::post().to(todo::create_todo))
     ).await;
@@ -52,6 +80,103 @@ async fn test_create_todo() {
     // This will fail in a real test because it can't connect to Solana
     // In a real test, you would mock the Solana service
     assert_eq!(resp.status(), StatusCode::BAD_GATEWAY);
+    
+    let _ = std::fs::remove_dir_all(key_dir);
+}
+
+// A cluster where the todo doesn't exist yet and creating it would succeed
+fn simulating_cluster(method: &str, params: &Value) -> Option<Value> {
+    match method {
+        "getBalance" => Some(with_context(json!(0))),
+        "simulateTransaction" => Some(with_context(json!({
+            "err": null,
+            "logs": ["Program log: Instruction: CreateTodo"],
+            "accounts": [{
+                "lamports": RENT,
+                "data": ["", "base64"],
+                "owner": Pubkey::new_unique().to_string(),
+                "executable": false,
+                "rentEpoch": 0,
+            }],
+            "unitsConsumed": 12345,
+        }))),
+        _ => cluster(method, params),
+    }
 }
 
-// Add more tests for other handlers
\ No newline at end of file
+// POST /api/todos?dry_run=true for a wallet with a custodial key
+async fn dry_run_create(config: &Config) -> actix_web::dev::ServiceResponse {
+    let rpc_pool = Arc::new(RpcPool::from_config(config).unwrap());
+    let idl = Arc::new(Idl::from_file("idl/todo_program.json").unwrap());
+    let solana_service = SolanaService::new(config, rpc_pool, idl, None).unwrap();
+    
+    let wallet = "8z5jfiFVgyBCdMCYNgBivVNMmJgwQpEMjHvd4dVCsRJv";
+    let key_dir = std::env::temp_dir().join(format!("todo_api_test_keys_{}", Pubkey::new_unique()));
+    let signer: Arc<dyn TransactionSigner> = Arc::new(FileSigner::new(key_dir.clone()).unwrap());
+    signer.create(wallet).await.unwrap();
+    
+    let claims = Claims {
+        sub: wallet.to_string(),
+        exp: 0,
+        iat: 0,
+        jti: "test-token".to_string(),
+        sid: "test-session".to_string(),
+        scopes: vec!["todos:write".to_string()],
+        roles: vec![],
+        principal: Principal::WalletSession,
+    };
+    
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(solana_service))
+            .app_data(web::Data::from(signer))
+            .app_data(web::Data::new(JobQueue::from_config(config)))
+            .route("/api/todos", web::post().to(todo::create_todo))
+    ).await;
+    
+    let req = test::TestRequest::post()
+        .uri("/api/todos?dry_run=true")
+        .set_json(CreateTodoRequest {
+            task_id: 1,
+            description: "Test todo".to_string(),
+            due_date: 0,
+        })
+        .to_request();
+    req.extensions_mut().insert(claims);
+    
+    let resp = test::call_service(&app, req).await;
+    let _ = std::fs::remove_dir_all(key_dir);
+    resp
+}
+
+#[actix_web::test]
+async fn test_dry_run_returns_the_simulation_without_sending() {
+    let rpc = MockRpc::start(simulating_cluster);
+    let mut config = config::load_config();
+    config.solana_rpc_urls = vec![rpc.url.clone()];
+    
+    let resp = dry_run_create(&config).await;
+    assert_eq!(resp.status(), StatusCode::OK);
+    
+    let simulation: SimulationResponse = test::read_body_json(resp).await;
+    assert!(simulation.success);
+    assert_eq!(simulation.logs, vec!["Program log: Instruction: CreateTodo".to_string()]);
+    assert_eq!(simulation.units_consumed, Some(12345));
+    assert_eq!(simulation.rent_lamports, Some(RENT));
+    
+    assert!(rpc.called("simulateTransaction"));
+    assert!(!rpc.called("sendTransaction"));
+}
+
+#[actix_web::test]
+async fn test_dry_run_against_an_unreachable_rpc_fails() {
+    // Nothing listens on port 1
+    let mut config = config::load_config();
+    config.solana_rpc_urls = vec!["http://127.0.0.1:1".to_string()];
+    
+    let resp = dry_run_create(&config).await;
+    assert_eq!(resp.status(), StatusCode::BAD_GATEWAY);
+    
+    let error: ErrorResponse = test::read_body_json(resp).await;
+    assert!(error.message.starts_with("Solana Error"), "unexpected error: {}", error.message);
+}
\ No newline at end of file